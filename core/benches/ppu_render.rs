@@ -0,0 +1,134 @@
+//! Compares the single-threaded and multithreaded PPU renderers by running the same ROM with
+//! each, checking that every frame they produce is identical.
+//!
+//! Usage: `cargo bench -p ness-core --bench ppu_render -- <ROM path> [frames] [threads]`
+
+use ness_core::{
+    apu::dsp,
+    cart::{info::Info, Cart},
+    emu::Emu,
+    utils::BoxedByteSlice,
+    Model,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+const WARMUP_FRAMES: usize = 60;
+
+fn run(rom_contents: &[u8], frames: usize, threads: usize) -> (Duration, Vec<u64>) {
+    let mut rom = BoxedByteSlice::new_zeroed(rom_contents.len());
+    rom[..].copy_from_slice(rom_contents);
+    let info = Info::new(rom.as_byte_slice(), None).0;
+    let ram = BoxedByteSlice::new_zeroed(info.ram_size as usize);
+    let cart = Cart::new(rom, ram, &info).expect("Couldn't build cart");
+
+    let mut emu = Emu::new(
+        Model::Ntsc,
+        cart,
+        Box::new(dsp::DummyBackend),
+        512,
+        #[cfg(feature = "log")]
+        &slog::Logger::root(slog::Discard, slog::o!()),
+    );
+    emu.ppu.set_render_threads(threads);
+
+    for _ in 0..WARMUP_FRAMES {
+        emu.run_frame();
+    }
+
+    let mut frame_hashes = Vec::with_capacity(frames);
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..frames {
+        let start_time = Instant::now();
+        emu.run_frame();
+        elapsed += start_time.elapsed();
+
+        let mut hasher = DefaultHasher::new();
+        emu.ppu.framebuffer.0.hash(&mut hasher);
+        frame_hashes.push(hasher.finish());
+    }
+    (elapsed, frame_hashes)
+}
+
+const USAGE: &str = "Usage: ppu_render <ROM path> [frames] [threads]";
+
+fn main() {
+    // Skip the `--bench` flag passed by `cargo bench`
+    let mut args = env::args().skip(1).filter(|arg| arg != "--bench");
+    // A plain `cargo bench` runs this without a ROM, which shouldn't fail the whole run
+    let rom_path = match args.next() {
+        Some(rom_path) => rom_path,
+        None => {
+            eprintln!("{}", USAGE);
+            return;
+        }
+    };
+    let frames = match args.next() {
+        Some(frames) => match frames.parse::<usize>() {
+            Ok(frames) if frames != 0 => frames,
+            _ => {
+                eprintln!("Invalid frame count\n{}", USAGE);
+                return;
+            }
+        },
+        None => 600,
+    };
+    let threads = match args.next() {
+        Some(threads) => match threads.parse::<usize>() {
+            Ok(threads) if threads != 0 => threads,
+            _ => {
+                eprintln!("Invalid thread count\n{}", USAGE);
+                return;
+            }
+        },
+        None => std::thread::available_parallelism()
+            .map_or(2, usize::from)
+            .saturating_sub(1)
+            .max(1),
+    };
+
+    let mut rom_contents = match fs::read(&rom_path) {
+        Ok(rom_contents) => rom_contents,
+        Err(err) => {
+            eprintln!("Couldn't read ROM file: {}", err);
+            return;
+        }
+    };
+    if rom_contents.len() & 0x200 != 0 {
+        rom_contents.drain(..0x200);
+    }
+
+    let (single_time, single_hashes) = run(&rom_contents, frames, 0);
+    let (threaded_time, threaded_hashes) = run(&rom_contents, frames, threads);
+
+    if let Some(frame) = single_hashes
+        .iter()
+        .zip(&threaded_hashes)
+        .position(|(a, b)| a != b)
+    {
+        panic!(
+            "Framebuffers differ starting at frame {}",
+            frame + WARMUP_FRAMES
+        );
+    }
+
+    let print_result = |name: &str, time: Duration| {
+        println!(
+            "{:<24} {:>8.3} ms/frame {:>8.1} FPS",
+            name,
+            time.as_secs_f64() * 1000.0 / frames as f64,
+            frames as f64 / time.as_secs_f64(),
+        );
+    };
+    println!("{} frames, identical output", frames);
+    print_result("Single-threaded", single_time);
+    print_result(&format!("Threaded ({} workers)", threads), threaded_time);
+    println!(
+        "Speedup: {:.2}x",
+        single_time.as_secs_f64() / threaded_time.as_secs_f64()
+    );
+}
@@ -3,16 +3,21 @@ pub mod regs;
 mod timers;
 pub use timers::Timer;
 
+#[cfg(feature = "disasm")]
 pub mod disasm;
 mod interpreter;
 
 use super::Apu;
+#[cfg(feature = "disasm")]
+use crate::symbols::SymbolTable;
 use crate::{
     schedule::Timestamp,
     utils::{bitfield_debug, zeroed_box, Bytes},
     Model,
 };
 use regs::Regs;
+#[cfg(feature = "disasm")]
+use std::sync::Arc;
 
 bitfield_debug! {
     #[derive(Clone, Copy, PartialEq, Eq)]
@@ -36,6 +41,14 @@ pub struct Spc700 {
     pub cpu_to_apu: [u8; 4],
     pub apu_to_cpu: [u8; 4],
     dsp_reg_index: u8,
+    #[cfg(feature = "disasm")]
+    pub symbols: Arc<SymbolTable>,
+    #[cfg(feature = "debugger")]
+    pub trace_log: Option<crate::debugger::trace::Log>,
+    #[cfg(feature = "debugger")]
+    pub(crate) debug_break_after_instr: bool,
+    #[cfg(feature = "debugger")]
+    pub(crate) debug_instr_stepped: bool,
 }
 
 impl Spc700 {
@@ -56,6 +69,14 @@ impl Spc700 {
             cpu_to_apu: [0; 4],
             apu_to_cpu: [0; 4],
             dsp_reg_index: 0xFF,
+            #[cfg(feature = "disasm")]
+            symbols: Arc::new(SymbolTable::new()),
+            #[cfg(feature = "debugger")]
+            trace_log: None,
+            #[cfg(feature = "debugger")]
+            debug_break_after_instr: false,
+            #[cfg(feature = "debugger")]
+            debug_instr_stepped: false,
         }
     }
 
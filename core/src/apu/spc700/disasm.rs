@@ -3,8 +3,9 @@ use instrs::*;
 mod common;
 use common::*;
 
-use crate::apu::Apu;
+use crate::{apu::Apu, symbols::SymbolTable};
 use core::{mem::replace, ops::Range};
+use std::sync::Arc;
 
 static INSTR_TABLE: [fn(&mut Context); 0x100] =
     include!(concat!(env!("OUT_DIR"), "/instr_table_spc700_disasm.rs"));
@@ -12,27 +13,44 @@ static INSTR_TABLE: [fn(&mut Context); 0x100] =
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Instr {
     pub addr: u16,
+    /// The label defined at this instruction's address, if any.
+    pub label: Option<String>,
     pub opcode: String,
     pub op_addr: String,
+    pub comment: String,
 }
 
 struct Context<'a> {
     apu: &'a mut Apu,
     pc: u16,
     direct_page_base: Option<u16>,
+    symbols: Option<Arc<SymbolTable>>,
     next_instr: Instr,
 }
 
+fn new_instr(symbols: Option<&SymbolTable>, addr: u16) -> Instr {
+    Instr {
+        addr,
+        label: symbols
+            .and_then(|symbols| symbols.label(addr as u32))
+            .map(str::to_string),
+        opcode: String::new(),
+        op_addr: String::new(),
+        comment: symbols
+            .and_then(|symbols| symbols.comment(addr as u32))
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
 impl<'a> Context<'a> {
-    fn from_apu_state_and_addr(apu: &'a mut Apu, addr: u16) -> Self {
+    fn from_apu_state_and_addr(apu: &'a mut Apu, addr: u16, use_symbols: bool) -> Self {
+        let symbols = use_symbols.then(|| Arc::clone(&apu.spc700.symbols));
         Context {
             pc: addr,
             direct_page_base: Some(apu.spc700.regs.direct_page_base()),
-            next_instr: Instr {
-                addr,
-                opcode: String::new(),
-                op_addr: String::new(),
-            },
+            next_instr: new_instr(symbols.as_deref(), addr),
+            symbols,
             apu,
         }
     }
@@ -45,14 +63,8 @@ impl<'a> Context<'a> {
         while cond(&self, result) {
             let instr = self.consume_imm_8();
             INSTR_TABLE[instr as usize](&mut self);
-            result.push(replace(
-                &mut self.next_instr,
-                Instr {
-                    addr: self.pc,
-                    opcode: String::new(),
-                    op_addr: String::new(),
-                },
-            ));
+            let next_instr = new_instr(self.symbols.as_deref(), self.pc);
+            result.push(replace(&mut self.next_instr, next_instr));
         }
     }
 
@@ -64,7 +76,7 @@ impl<'a> Context<'a> {
 }
 
 pub fn disassemble_range_with_apu_state(apu: &mut Apu, addrs: Range<u16>, result: &mut Vec<Instr>) {
-    Context::from_apu_state_and_addr(apu, addrs.start)
+    Context::from_apu_state_and_addr(apu, addrs.start, true)
         .disassemble_while(result, |ctx, _| (ctx.pc as u16) < addrs.end);
 }
 
@@ -74,10 +86,15 @@ pub fn disassemble_count_with_apu_state(
     count: usize,
     result: &mut Vec<Instr>,
 ) {
-    Context::from_apu_state_and_addr(apu, start_addr)
+    Context::from_apu_state_and_addr(apu, start_addr, true)
         .disassemble_while(result, |_, result| result.len() < count);
 }
 
 pub fn disassemble_single_with_apu_state(apu: &mut Apu, addr: u16) -> Instr {
-    Context::from_apu_state_and_addr(apu, addr).disassemble_single()
+    Context::from_apu_state_and_addr(apu, addr, true).disassemble_single()
+}
+
+/// Like `disassemble_single_with_apu_state`, but always outputs raw addresses instead of labels.
+pub fn disassemble_single_without_symbols(apu: &mut Apu, addr: u16) -> Instr {
+    Context::from_apu_state_and_addr(apu, addr, false).disassemble_single()
 }
@@ -1,6 +1,6 @@
 use super::Context;
 use crate::apu::spc700::bus;
-use core::fmt::Write;
+use core::fmt::{self, Write};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AddrMode {
@@ -37,6 +37,19 @@ impl<'a> Context<'a> {
         res
     }
 
+    /// Appends an address operand to the opcode, replacing it with the corresponding label if
+    /// there is one.
+    pub fn push_addr_operand(&mut self, addr: Option<u16>, raw: fmt::Arguments, idx_opcode: &str) {
+        match addr
+            .zip(self.symbols.as_deref())
+            .and_then(|(addr, symbols)| symbols.label(addr as u32))
+        {
+            Some(label) => write!(self.next_instr.opcode, "{}{}", label, idx_opcode),
+            None => write!(self.next_instr.opcode, "{}{}", raw, idx_opcode),
+        }
+        .unwrap();
+    }
+
     fn resolve_direct_addr(&self, offset: u8) -> Option<u16> {
         self.direct_page_base.map(|base| base | offset as u16)
     }
@@ -54,7 +67,11 @@ impl<'a> Context<'a> {
     }
 
     pub fn handle_direct_addr_custom(&mut self, offset: u8, idx_opcode: &str, idx_op_addr: &str) {
-        write!(self.next_instr.opcode, "${:02X}{}", offset, idx_opcode).unwrap();
+        self.push_addr_operand(
+            self.resolve_direct_addr(offset),
+            format_args!("${:02X}", offset),
+            idx_opcode,
+        );
         self.next_instr.op_addr = if let Some(addr) = self.resolve_direct_addr(offset) {
             format!("{:04X}{}", addr, idx_op_addr)
         } else {
@@ -101,7 +118,7 @@ impl<'a> Context<'a> {
     }
 
     pub fn handle_absolute_addr_custom(&mut self, addr: u16, idx_opcode: &str, idx_op_addr: &str) {
-        write!(self.next_instr.opcode, "!${:04X}{}", addr, idx_opcode).unwrap();
+        self.push_addr_operand(Some(addr), format_args!("!${:04X}", addr), idx_opcode);
         self.next_instr.op_addr = format!("{:04X}{}", addr, idx_op_addr);
     }
 
@@ -130,18 +147,16 @@ impl<'a> Context<'a> {
 
     pub fn handle_branch_offset(&mut self) {
         let offset = self.consume_imm_8() as i8 as i16;
-        write!(
-            self.next_instr.opcode,
-            "${}{:02X}",
-            if offset < 0 { "-" } else { "" },
-            if offset < 0 { -offset } else { offset }
-        )
-        .unwrap();
-        write!(
-            self.next_instr.op_addr,
-            "{:04X}",
-            self.pc.wrapping_add(offset as u16)
-        )
-        .unwrap();
+        let target_addr = self.pc.wrapping_add(offset as u16);
+        self.push_addr_operand(
+            Some(target_addr),
+            format_args!(
+                "${}{:02X}",
+                if offset < 0 { "-" } else { "" },
+                if offset < 0 { -offset } else { offset }
+            ),
+            "",
+        );
+        write!(self.next_instr.op_addr, "{:04X}", target_addr).unwrap();
     }
 }
@@ -58,7 +58,12 @@ pub(super) fn call(ctx: &mut Context) {
 
 pub(super) fn pcall(ctx: &mut Context) {
     let offset = ctx.consume_imm_8();
-    ctx.next_instr.opcode = format!("PCALL ${:02X}", offset);
+    ctx.next_instr.opcode = "PCALL ".to_string();
+    ctx.push_addr_operand(
+        Some(0xFF00 | offset as u16),
+        format_args!("${:02X}", offset),
+        "",
+    );
     ctx.next_instr.op_addr = format!("{:04X}", 0xFF00 | offset as u16);
 }
 
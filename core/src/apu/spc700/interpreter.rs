@@ -10,6 +10,8 @@ mod other;
 use other::*;
 mod common;
 
+#[cfg(feature = "debugger")]
+use crate::debugger::trace;
 use crate::{apu::Apu, schedule::Timestamp};
 use common::{consume_imm_8, read_16, AddrMode, MemOrReg, Reg};
 
@@ -30,7 +32,18 @@ pub fn soft_reset(apu: &mut Apu) {
 
 pub fn run(apu: &mut Apu, end_timestamp: Timestamp) {
     while apu.spc700.cur_timestamp < end_timestamp {
+        #[cfg(feature = "debugger")]
+        if apu.spc700.trace_log.is_some() {
+            trace::log_spc_instr(apu);
+        }
         let instr = consume_imm_8(apu);
         INSTR_TABLE[instr as usize](apu);
+        #[cfg(feature = "debugger")]
+        if apu.spc700.debug_break_after_instr {
+            // Leave the rest of the time slice to be run once emulation is resumed
+            apu.spc700.debug_break_after_instr = false;
+            apu.spc700.debug_instr_stepped = true;
+            break;
+        }
     }
 }
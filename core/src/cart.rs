@@ -1,16 +1,25 @@
 pub mod info;
 mod map;
 
-use crate::utils::BoxedByteSlice;
+use crate::{cheats::Code, utils::BoxedByteSlice};
 use info::Info;
 use map::Map;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RomPatch {
+    offset: u32,
+    value: u8,
+    compare: Option<u8>,
+}
+
 #[derive(Clone)]
 pub struct Cart {
     rom: BoxedByteSlice,
     ram: BoxedByteSlice,
     ram_modified: bool,
     map: Map,
+    rom_patches: Vec<RomPatch>,
+    patched_pages: Vec<usize>,
 }
 
 impl Cart {
@@ -22,7 +31,7 @@ impl Cart {
             let offset = map::mirror(region.offset, size);
             size -= offset;
             for addr_range in &region.address_ranges {
-                map.map::<true, false>(
+                map.map::<true, false, true>(
                     Some(Self::handle_rom_read),
                     None,
                     addr_range.banks,
@@ -38,7 +47,7 @@ impl Cart {
             let offset = map::mirror(region.offset, size);
             size -= offset;
             for addr_range in &region.address_ranges {
-                map.map::<true, true>(
+                map.map::<true, true, false>(
                     Some(Self::handle_ram_read),
                     Some(Self::handle_ram_write),
                     addr_range.banks,
@@ -54,6 +63,8 @@ impl Cart {
             ram,
             ram_modified: false,
             map,
+            rom_patches: Vec::new(),
+            patched_pages: Vec::new(),
         })
     }
 
@@ -97,10 +108,91 @@ impl Cart {
             .map(|(write, addr)| write(self, addr, value))
     }
 
+    /// Returns the ROM offset `addr` is mapped to, if it's mapped to ROM at all.
+    pub fn rom_offset(&self, addr: u32) -> Option<u32> {
+        let page = (addr as usize >> Map::PAGE_SIZE_SHIFT) & (Map::ENTRIES - 1);
+        if self.map.is_rom_page(page) {
+            self.map.read_data(addr).map(|(_, offset)| offset)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cart RAM offset `addr` is mapped to, if it's mapped to RAM at all.
+    pub fn ram_offset(&self, addr: u32) -> Option<u32> {
+        // RAM is the only writable cart memory
+        self.map.write_data(addr).map(|(_, offset)| offset)
+    }
+
+    /// Replaces the current set of ROM read substitutions with the given codes (any codes whose
+    /// address isn't mapped to ROM get ignored).
+    ///
+    /// Substitutions are applied by ROM offset, so they affect all mirrors of the patched address;
+    /// only the pages containing patched bytes get redirected to a slower read handler, leaving
+    /// reads from all other pages unaffected.
+    pub fn set_rom_patches(&mut self, codes: &[Code]) {
+        for page in self.patched_pages.drain(..) {
+            self.map.read_fns[page] = Some(Self::handle_rom_read);
+        }
+
+        let mut rom_patches = codes
+            .iter()
+            .filter_map(|code| {
+                self.rom_offset(code.addr).map(|offset| RomPatch {
+                    offset,
+                    value: code.value,
+                    compare: code.compare,
+                })
+            })
+            .collect::<Vec<_>>();
+        // Keep the last code for each offset
+        rom_patches.reverse();
+        rom_patches.sort_by_key(|patch| patch.offset);
+        rom_patches.dedup_by_key(|patch| patch.offset);
+        self.rom_patches = rom_patches;
+
+        for page in 0..Map::ENTRIES {
+            if !self.map.is_rom_page(page) {
+                continue;
+            }
+            let page_start = (self.map.read_offsets[page] as u32) << Map::PAGE_SIZE_SHIFT;
+            let page_end = page_start + Map::PAGE_SIZE as u32;
+            let first_patch = self
+                .rom_patches
+                .partition_point(|patch| patch.offset < page_start);
+            if self
+                .rom_patches
+                .get(first_patch)
+                .map_or(false, |patch| patch.offset < page_end)
+            {
+                self.map.read_fns[page] = Some(Self::handle_patched_rom_read);
+                self.patched_pages.push(page);
+            }
+        }
+    }
+
     fn handle_rom_read(&mut self, offset: u32) -> u8 {
         self.rom[offset as usize]
     }
 
+    fn handle_patched_rom_read(&mut self, offset: u32) -> u8 {
+        let value = self.rom[offset as usize];
+        match self
+            .rom_patches
+            .binary_search_by_key(&offset, |patch| patch.offset)
+        {
+            Ok(i) => {
+                let patch = &self.rom_patches[i];
+                if patch.compare.map_or(true, |compare| compare == value) {
+                    patch.value
+                } else {
+                    value
+                }
+            }
+            Err(_) => value,
+        }
+    }
+
     fn handle_ram_read(&mut self, offset: u32) -> u8 {
         self.ram[offset as usize]
     }
@@ -253,6 +253,6 @@ impl<'a> Parser<'a> {
     }
 }
 
-pub(super) fn parse(input: &str) -> Result<Vec<Node>, ParseError> {
+pub fn parse(input: &str) -> Result<Vec<Node>, ParseError> {
     Parser::parse(input)
 }
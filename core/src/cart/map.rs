@@ -57,6 +57,8 @@ pub struct Map {
     pub write_offsets: Box<[Index; Self::ENTRIES]>,
     pub read_fns: Box<[Option<ReadHandler>; Self::ENTRIES]>,
     pub write_fns: Box<[Option<WriteHandler>; Self::ENTRIES]>,
+    /// Bitset of the pages whose reads are mapped to ROM.
+    rom_pages: Box<[u64; Self::ENTRIES / 64]>,
 }
 
 impl Map {
@@ -70,11 +72,19 @@ impl Map {
             write_offsets: zeroed_box(),
             read_fns: Box::new([None; Self::ENTRIES]),
             write_fns: Box::new([None; Self::ENTRIES]),
+            rom_pages: zeroed_box(),
         }
     }
 
+    /// Returns whether reads from page `i` were mapped to ROM, i.e. with `ROM` set in the last
+    /// [`map`](Self::map) call that covered it.
+    #[inline]
+    pub fn is_rom_page(&self, i: usize) -> bool {
+        self.rom_pages[i >> 6] & 1 << (i & 63) != 0
+    }
+
     #[allow(clippy::too_many_arguments)]
-    pub fn map<const READ: bool, const WRITE: bool>(
+    pub fn map<const READ: bool, const WRITE: bool, const ROM: bool>(
         &mut self,
         read_fn: Option<ReadHandler>,
         write_fn: Option<WriteHandler>,
@@ -103,6 +113,11 @@ impl Map {
                 if READ {
                     self.read_fns[i] = read_fn;
                     self.read_offsets[i] = (page_offset >> Self::PAGE_SIZE_SHIFT) as Index;
+                    if ROM {
+                        self.rom_pages[i >> 6] |= 1 << (i & 63);
+                    } else {
+                        self.rom_pages[i >> 6] &= !(1 << (i & 63));
+                    }
                 }
                 if WRITE {
                     self.write_fns[i] = write_fn;
@@ -0,0 +1,336 @@
+use crate::{cart::info::db::bml, emu::Emu};
+use core::fmt::{self, Display};
+use std::error::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Code {
+    pub addr: u32,
+    pub value: u8,
+    pub compare: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    UnknownFormat(String),
+    InvalidGameGenieChar(char),
+    InvalidHex(String),
+}
+
+impl Error for DecodeError {}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat(code) => write!(f, "Unknown cheat code format: `{}`", code),
+            Self::InvalidGameGenieChar(char) => {
+                write!(f, "Invalid Game Genie code character: `{}`", char)
+            }
+            Self::InvalidHex(value) => write!(f, "Invalid hexadecimal value: `{}`", value),
+        }
+    }
+}
+
+fn parse_hex<T: TryFrom<u32>>(str: &str, max_digits: usize) -> Result<T, DecodeError> {
+    if str.is_empty() || str.len() > max_digits {
+        return Err(DecodeError::InvalidHex(str.to_string()));
+    }
+    u32::from_str_radix(str, 16)
+        .ok()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| DecodeError::InvalidHex(str.to_string()))
+}
+
+static GAME_GENIE_CHARS: &[u8; 16] = b"DF4709156BC8A23E";
+
+/// Decodes a SNES Game Genie code (`VVAA-AAAA`, using the Game Genie alphabet); the address bits
+/// are scrambled as `ijklqrst opabcduv wxefghmn`, which gets reordered to `abcdefgh ijklmnop
+/// qrstuvwx`.
+pub fn decode_game_genie(code: &str) -> Result<Code, DecodeError> {
+    let mut raw = 0_u32;
+    let mut digits = 0;
+    for char in code.chars().filter(|c| *c != '-') {
+        let digit = GAME_GENIE_CHARS
+            .iter()
+            .position(|c| *c as char == char.to_ascii_uppercase())
+            .ok_or(DecodeError::InvalidGameGenieChar(char))?;
+        raw = raw << 4 | digit as u32;
+        digits += 1;
+    }
+    if digits != 8 {
+        return Err(DecodeError::UnknownFormat(code.to_string()));
+    }
+
+    let scrambled_addr = raw & 0xFF_FFFF;
+    let mut addr = 0;
+    for (i, bit_name) in b"ijklqrstopabcduvwxefghmn".iter().enumerate() {
+        let src_bit = 23 - i as u32;
+        let dst_bit = 23 - (bit_name - b'a') as u32;
+        addr |= (scrambled_addr >> src_bit & 1) << dst_bit;
+    }
+
+    Ok(Code {
+        addr,
+        value: (raw >> 24) as u8,
+        compare: None,
+    })
+}
+
+/// Decodes a Pro Action Replay code (`AAAAAAVV`, in hexadecimal).
+pub fn decode_par(code: &str) -> Result<Code, DecodeError> {
+    if code.len() != 8 || !code.is_char_boundary(6) {
+        return Err(DecodeError::UnknownFormat(code.to_string()));
+    }
+    Ok(Code {
+        addr: parse_hex(&code[..6], 6)?,
+        value: parse_hex(&code[6..], 2)?,
+        compare: None,
+    })
+}
+
+/// Decodes a raw bsnes-style code, either `address=value` or `address=compare?value`.
+pub fn decode_raw(code: &str) -> Result<Code, DecodeError> {
+    let (addr, data) = code
+        .split_once('=')
+        .ok_or_else(|| DecodeError::UnknownFormat(code.to_string()))?;
+    let (compare, value) = match data.split_once('?') {
+        Some((compare, value)) => (Some(parse_hex(compare, 2)?), value),
+        None => (None, data),
+    };
+    Ok(Code {
+        addr: parse_hex(addr, 6)?,
+        value: parse_hex(value, 2)?,
+        compare,
+    })
+}
+
+/// Decodes a single code, detecting its format automatically.
+pub fn decode(code: &str) -> Result<Code, DecodeError> {
+    let code = code.trim();
+    if code.contains('=') {
+        decode_raw(code)
+    } else if code.contains('-') {
+        decode_game_genie(code)
+    } else if code.len() == 8 && code.chars().all(|c| c.is_ascii_hexdigit()) {
+        decode_par(code)
+    } else {
+        Err(DecodeError::UnknownFormat(code.to_string()))
+    }
+}
+
+/// Decodes a list of codes separated by `+` or whitespace, as used in bsnes' cheat files.
+pub fn decode_list(codes: &str) -> Result<Vec<Code>, DecodeError> {
+    codes
+        .split(|c: char| c == '+' || c.is_whitespace())
+        .filter(|code| !code.is_empty())
+        .map(decode)
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChtEntry {
+    pub description: String,
+    pub code: String,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChtLoadError<'a> {
+    Bml(bml::ParseError),
+    UnexpectedRootNode(bml::Node<'a>),
+    MissingCode(bml::Node<'a>),
+}
+
+impl<'a> Error for ChtLoadError<'a> {}
+
+impl<'a> Display for ChtLoadError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Self::Bml(err) = self {
+            err.fmt(f)
+        } else {
+            f.write_str(match self {
+                Self::Bml(_) => unreachable!(),
+                Self::UnexpectedRootNode(_) => "Unexpected cheat file root node",
+                Self::MissingCode(_) => "Missing cheat code",
+            })
+        }
+    }
+}
+
+/// Loads a bsnes-style `.cht` cheat file, with entries in the form:
+/// ```text
+/// cheat
+///   description: Infinite lives
+///   code: 7e0dbe=09+7e0dbf=00
+///   enable
+/// ```
+/// Codes aren't decoded here, in order to allow them to be edited before use.
+pub fn load_cht(input: &str) -> Result<Vec<ChtEntry>, ChtLoadError> {
+    let mut result = Vec::new();
+    for node in bml::parse(input).map_err(ChtLoadError::Bml)? {
+        if node.name != "cheat" {
+            return Err(ChtLoadError::UnexpectedRootNode(node));
+        }
+
+        let mut description = String::new();
+        let mut code = None;
+        let mut enabled = false;
+        for attr in &node.attrs {
+            match attr.name {
+                "description" | "name" => {
+                    description = attr.value.as_deref().unwrap_or_default().to_string();
+                }
+                "code" => code = attr.value.as_deref().map(str::to_string),
+                "enable" | "enabled" => enabled = true,
+                _ => {}
+            }
+        }
+
+        let code = match code {
+            Some(code) => code,
+            None => return Err(ChtLoadError::MissingCode(node)),
+        };
+        result.push(ChtEntry {
+            description,
+            code,
+            enabled,
+        });
+    }
+    Ok(result)
+}
+
+/// Per-frame RAM writes; ROM substitutions are instead handled by the cart, see
+/// [`Cart::set_rom_patches`](crate::cart::Cart::set_rom_patches).
+pub struct Cheats {
+    ram_writes: Vec<Code>,
+}
+
+impl Cheats {
+    pub(crate) fn new() -> Self {
+        Cheats {
+            ram_writes: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn ram_writes(&self) -> &[Code] {
+        &self.ram_writes
+    }
+
+    #[inline]
+    pub(crate) fn set_ram_writes(&mut self, ram_writes: Vec<Code>) {
+        self.ram_writes = ram_writes;
+    }
+
+    /// Applies the RAM writes directly to WRAM and cart RAM, bypassing the bus so that no other
+    /// state (i.e. open bus) is affected; codes targeting anything else are ignored.
+    pub(crate) fn apply_ram_writes(emu: &mut Emu) {
+        for code in &emu.cheats.ram_writes {
+            let bank = (code.addr >> 16) as u8;
+            let wram_addr = match bank {
+                0x00..=0x3F | 0x80..=0xBF if code.addr & 0xE000 == 0 => {
+                    Some(code.addr as usize & 0x1FFF)
+                }
+                0x7E..=0x7F => Some(code.addr as usize & 0x1_FFFF),
+                _ => None,
+            };
+            if let Some(wram_addr) = wram_addr {
+                let byte = &mut emu.wram.contents[wram_addr];
+                if code.compare.map_or(true, |compare| *byte == compare) {
+                    *byte = code.value;
+                }
+            } else if let Some(offset) = emu.cart.ram_offset(code.addr) {
+                let byte = emu.cart.ram()[offset as usize];
+                // Avoid marking cart RAM as modified every frame
+                if byte != code.value && code.compare.map_or(true, |compare| byte == compare) {
+                    emu.cart.modify_ram(|ram| ram[offset as usize] = code.value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(addr: u32, value: u8, compare: Option<u8>) -> Code {
+        Code {
+            addr,
+            value,
+            compare,
+        }
+    }
+
+    #[test]
+    fn game_genie() {
+        // Results match snes9x's conversion to raw codes
+        assert_eq!(
+            decode_game_genie("C2D5-3F8D"),
+            Ok(code(0x8C_0376, 0xAD, None))
+        );
+        assert_eq!(
+            decode_game_genie("F3C5-3D07"),
+            Ok(code(0x80_AF71, 0x1E, None))
+        );
+        assert_eq!(
+            decode_game_genie("dd62-6dad"),
+            Ok(code(0x00_82D3, 0x00, None))
+        );
+        assert_eq!(
+            decode_game_genie("DD62-6DAG"),
+            Err(DecodeError::InvalidGameGenieChar('G'))
+        );
+        assert_eq!(
+            decode_game_genie("DD62-6DA"),
+            Err(DecodeError::UnknownFormat("DD62-6DA".to_string()))
+        );
+    }
+
+    #[test]
+    fn par() {
+        assert_eq!(decode_par("7E0DBE09"), Ok(code(0x7E_0DBE, 0x09, None)));
+        assert_eq!(decode_par("80ffd5ff"), Ok(code(0x80_FFD5, 0xFF, None)));
+        assert_eq!(
+            decode_par("7E0DBE0"),
+            Err(DecodeError::UnknownFormat("7E0DBE0".to_string()))
+        );
+        assert_eq!(
+            decode_par("7E0DBEXX"),
+            Err(DecodeError::InvalidHex("XX".to_string()))
+        );
+    }
+
+    #[test]
+    fn raw() {
+        assert_eq!(decode_raw("7e0dbe=09"), Ok(code(0x7E_0DBE, 0x09, None)));
+        assert_eq!(
+            decode_raw("00ffd5=20?21"),
+            Ok(code(0x00_FFD5, 0x21, Some(0x20)))
+        );
+        assert_eq!(
+            decode_raw("7e0dbe=109"),
+            Err(DecodeError::InvalidHex("109".to_string()))
+        );
+        assert_eq!(
+            decode_raw("1000000=00"),
+            Err(DecodeError::InvalidHex("1000000".to_string()))
+        );
+    }
+
+    #[test]
+    fn format_detection() {
+        assert_eq!(
+            decode_list("7e0dbe=09+7e0dbf=00 C2D5-3F8D\n7E0DBE09"),
+            Ok(vec![
+                code(0x7E_0DBE, 0x09, None),
+                code(0x7E_0DBF, 0x00, None),
+                code(0x8C_0376, 0xAD, None),
+                code(0x7E_0DBE, 0x09, None),
+            ])
+        );
+        assert_eq!(
+            decode("7E0DBE"),
+            Err(DecodeError::UnknownFormat("7E0DBE".to_string()))
+        );
+    }
+}
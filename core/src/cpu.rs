@@ -1,4 +1,8 @@
 use crate::emu::Emu;
+#[cfg(feature = "disasm")]
+use crate::symbols::SymbolTable;
+#[cfg(feature = "disasm")]
+use std::sync::Arc;
 
 pub mod bus;
 pub mod dma;
@@ -25,6 +29,8 @@ pub struct Cpu {
     pub math: Math,
     pub dmac: dma::Controller,
     pub bus_timings: bus::Timings,
+    #[cfg(feature = "disasm")]
+    pub symbols: Arc<SymbolTable>,
 }
 
 impl Cpu {
@@ -39,6 +45,8 @@ impl Cpu {
             math: Math::new(),
             dmac: dma::Controller::new(),
             bus_timings: bus::Timings::new(),
+            #[cfg(feature = "disasm")]
+            symbols: Arc::new(SymbolTable::new()),
         }
     }
 
@@ -1,3 +1,8 @@
+#[cfg(feature = "debugger")]
+use crate::debugger::{
+    breakpoints::{self, Kinds as BreakpointKinds},
+    events::{self, EventKind},
+};
 use crate::{cpu::dma, emu::Emu, ppu};
 
 pub trait AccessType {
@@ -117,6 +122,17 @@ fn write_a_io<A: AccessType>(emu: &mut Emu, addr: u32, value: u8) {
         return;
     }
 
+    #[cfg(feature = "debugger")]
+    if !A::IS_DMA && A::SIDE_EFFECTS && emu.debugger.events.is_some() {
+        events::log(
+            emu,
+            EventKind::CpuRegWrite {
+                addr: 0x4000 | (addr & 0x3FF) as u16,
+                value,
+            },
+        );
+    }
+
     match addr & 0x3FF {
         0x200 => {
             emu.controllers.set_joypad_auto_read_enabled(value & 1 != 0);
@@ -224,6 +240,11 @@ fn write_a_io<A: AccessType>(emu: &mut Emu, addr: u32, value: u8) {
 }
 
 pub fn read_b_io<A: AccessType>(emu: &mut Emu, addr: u8) -> u8 {
+    #[cfg(feature = "debugger")]
+    if A::IS_DMA && A::SIDE_EFFECTS {
+        breakpoints::check_access(emu, BreakpointKinds::DMA_SRC, 0x2100 | addr as u32, None);
+    }
+
     match addr {
         0x04..=0x06 | 0x08..=0x0A | 0x14..=0x16 | 0x18..=0x1A | 0x24..=0x26 | 0x28..=0x2A => {
             #[cfg(feature = "log")]
@@ -278,6 +299,24 @@ pub fn read_b_io<A: AccessType>(emu: &mut Emu, addr: u8) -> u8 {
 
 #[allow(clippy::needless_return)] // With logging disabled, the returns are detected as needless
 pub fn write_b_io<A: AccessType>(emu: &mut Emu, addr: u8, value: u8) {
+    #[cfg(feature = "debugger")]
+    if !A::IS_DMA && A::SIDE_EFFECTS && emu.debugger.events.is_some() {
+        events::log(emu, EventKind::BBusWrite { addr, value });
+    }
+    #[cfg(feature = "debugger")]
+    if A::IS_DMA && A::SIDE_EFFECTS {
+        breakpoints::check_access(
+            emu,
+            BreakpointKinds::DMA_DST,
+            0x2100 | addr as u32,
+            Some(value),
+        );
+    }
+
+    if A::SIDE_EFFECTS && addr <= 0x33 {
+        emu.ppu.catch_up_dot_render(emu.schedule.cur_time);
+    }
+
     match addr {
         0x00 => return emu.ppu.set_display_control_0(ppu::DisplayControl0(value)),
         0x01 => return emu.ppu.set_obj_control(ppu::ObjControl(value)),
@@ -412,6 +451,20 @@ pub fn read<A: AccessType>(emu: &mut Emu, addr: u32) -> u8 {
         };
     }
 
+    #[cfg(feature = "debugger")]
+    if A::SIDE_EFFECTS {
+        breakpoints::check_access(
+            emu,
+            if A::IS_DMA {
+                BreakpointKinds::DMA_SRC
+            } else {
+                BreakpointKinds::READ
+            },
+            addr,
+            None,
+        );
+    }
+
     let bank = (addr >> 16) as u8;
     match bank {
         // System area
@@ -442,6 +495,8 @@ pub fn read<A: AccessType>(emu: &mut Emu, addr: u32) -> u8 {
         _ => {}
     }
 
+    // ROM cheat substitutions are applied here too, by redirecting the affected cart pages to a
+    // patched read handler
     if let Some(result) = emu.cart.read_data(addr) {
         return update_mdr!(result);
     }
@@ -465,6 +520,20 @@ pub fn write<A: AccessType>(emu: &mut Emu, addr: u32, value: u8) {
         emu.cpu.mdr = value;
     }
 
+    #[cfg(feature = "debugger")]
+    if A::SIDE_EFFECTS {
+        breakpoints::check_access(
+            emu,
+            if A::IS_DMA {
+                BreakpointKinds::DMA_DST
+            } else {
+                BreakpointKinds::WRITE
+            },
+            addr,
+            Some(value),
+        );
+    }
+
     let bank = (addr >> 16) as u8;
     match bank {
         // System area
@@ -3,8 +3,11 @@ use instrs::*;
 mod common;
 use common::*;
 
-use crate::emu::Emu;
+#[cfg(feature = "debugger")]
+use crate::debugger::cdl;
+use crate::{emu::Emu, symbols::SymbolTable};
 use core::{mem::replace, ops::Range};
+use std::sync::Arc;
 
 static INSTR_TABLE: [fn(&mut Context); 0x400] =
     include!(concat!(env!("OUT_DIR"), "/instr_table_65c816_disasm.rs"));
@@ -12,6 +15,8 @@ static INSTR_TABLE: [fn(&mut Context); 0x400] =
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Instr {
     pub addr: u32,
+    /// The label defined at this instruction's address, if any.
+    pub label: Option<String>,
     pub opcode: String,
     pub op_addr: String,
     pub comment: String,
@@ -28,11 +33,30 @@ struct Context<'a> {
     psw_lut_base: u16,
     code_bank_base: u32,
     data_bank_base: Option<u32>,
+    symbols: Option<Arc<SymbolTable>>,
     next_instr: Instr,
 }
 
+fn new_instr(symbols: Option<&SymbolTable>, addr: u32) -> Instr {
+    Instr {
+        addr,
+        label: symbols
+            .and_then(|symbols| lookup_label(symbols, addr))
+            .map(str::to_string),
+        opcode: String::new(),
+        op_addr: String::new(),
+        comment: symbols
+            .and_then(|symbols| symbols.comment(addr))
+            .unwrap_or_default()
+            .to_string(),
+        followed_by_bank_boundary_crossing: false,
+    }
+}
+
 impl<'a> Context<'a> {
-    fn from_emu_state_and_addr(emu: &'a mut Emu, addr: u32) -> Self {
+    fn from_emu_state_and_addr(emu: &'a mut Emu, addr: u32, use_symbols: bool) -> Self {
+        let symbols = use_symbols.then(|| Arc::clone(&emu.cpu.symbols));
+        let next_instr = new_instr(symbols.as_deref(), addr);
         let mut ctx = Context {
             pc: addr as u16,
             direct_page_offset: Some(emu.cpu.regs.direct_page_offset),
@@ -42,13 +66,8 @@ impl<'a> Context<'a> {
             psw_lut_base: 0,
             code_bank_base: addr & 0xFF_0000,
             data_bank_base: Some((emu.cpu.regs.data_bank() as u32) << 16),
-            next_instr: Instr {
-                addr,
-                opcode: String::new(),
-                op_addr: String::new(),
-                comment: String::new(),
-                followed_by_bank_boundary_crossing: false,
-            },
+            symbols,
+            next_instr,
             emu,
         };
         ctx.update_psw_lut_base();
@@ -59,12 +78,37 @@ impl<'a> Context<'a> {
         self.psw_lut_base = (self.a_is_8_bit as u16) << 9 | (self.index_regs_are_8_bit as u16) << 8;
     }
 
+    /// If the next instruction was logged as code in the code/data log, decodes it with the
+    /// register widths it was executed with.
+    #[cfg(feature = "debugger")]
+    fn apply_code_data_log(&mut self) {
+        let log = match &self.emu.debugger.cdl {
+            Some(log) => log,
+            None => return,
+        };
+        let flags = match self
+            .emu
+            .cart
+            .rom_offset(self.pc as u32 | self.code_bank_base)
+        {
+            Some(rom_offset) => log.flags(rom_offset),
+            None => return,
+        };
+        if flags.contains(cdl::Flags::CODE) {
+            self.a_is_8_bit = flags.contains(cdl::Flags::MEMORY_MODE_8);
+            self.index_regs_are_8_bit = flags.contains(cdl::Flags::INDEX_MODE_8);
+            self.update_psw_lut_base();
+        }
+    }
+
     fn disassemble_while(
         mut self,
         result: &mut Vec<Instr>,
         mut cond: impl FnMut(&Self, &Vec<Instr>) -> bool,
     ) {
         while cond(&self, result) {
+            #[cfg(feature = "debugger")]
+            self.apply_code_data_log();
             let instr = self.consume_imm::<u8>();
             unsafe {
                 INSTR_TABLE.get_unchecked(instr as usize | self.psw_lut_base as usize)(&mut self)
@@ -72,16 +116,11 @@ impl<'a> Context<'a> {
             if self.next_instr.followed_by_bank_boundary_crossing {
                 self.code_bank = self.code_bank.wrapping_add(1);
             }
-            result.push(replace(
-                &mut self.next_instr,
-                Instr {
-                    addr: self.pc as u32 | self.code_bank_base,
-                    opcode: String::new(),
-                    op_addr: String::new(),
-                    comment: String::new(),
-                    followed_by_bank_boundary_crossing: false,
-                },
-            ));
+            let next_instr = new_instr(
+                self.symbols.as_deref(),
+                self.pc as u32 | self.code_bank_base,
+            );
+            result.push(replace(&mut self.next_instr, next_instr));
         }
     }
 
@@ -95,7 +134,7 @@ impl<'a> Context<'a> {
 }
 
 pub fn disassemble_range_with_emu_state(emu: &mut Emu, addrs: Range<u32>, result: &mut Vec<Instr>) {
-    Context::from_emu_state_and_addr(emu, addrs.start).disassemble_while(result, |ctx, _| {
+    Context::from_emu_state_and_addr(emu, addrs.start, true).disassemble_while(result, |ctx, _| {
         ctx.pc as u32 | ctx.code_bank_base < addrs.end
     });
 }
@@ -106,10 +145,16 @@ pub fn disassemble_count_with_emu_state(
     count: usize,
     result: &mut Vec<Instr>,
 ) {
-    Context::from_emu_state_and_addr(emu, start_addr)
+    Context::from_emu_state_and_addr(emu, start_addr, true)
         .disassemble_while(result, |_, result| result.len() < count);
 }
 
 pub fn disassemble_single_with_emu_state(emu: &mut Emu, addr: u32) -> Instr {
-    Context::from_emu_state_and_addr(emu, addr).disassemble_single()
+    Context::from_emu_state_and_addr(emu, addr, true).disassemble_single()
+}
+
+/// Like `disassemble_single_with_emu_state`, but always outputs raw addresses instead of labels
+/// (i.e. for trace logs, which need to be comparable with other emulators' output).
+pub fn disassemble_single_without_symbols(emu: &mut Emu, addr: u32) -> Instr {
+    Context::from_emu_state_and_addr(emu, addr, false).disassemble_single()
 }
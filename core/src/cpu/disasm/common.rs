@@ -1,8 +1,26 @@
 pub use super::super::common::{AddrMode, JumpAddr, RegSize};
 
 use super::Context;
-use crate::cpu::bus;
-use core::fmt::Write;
+use crate::{cpu::bus, symbols::SymbolTable};
+use core::fmt::{self, Write};
+
+/// Looks up the label for `addr`, falling back to the label for the same address in the WRAM
+/// bank (for low RAM mirrors) or in the other half of the address space (for FastROM mirrors).
+pub fn lookup_label(symbols: &SymbolTable, addr: u32) -> Option<&str> {
+    symbols.label(addr).or_else(|| {
+        let bank = addr >> 16;
+        if bank & 0x40 == 0 && addr & 0xFFFF < 0x2000 {
+            if let Some(label) = symbols.label(0x7E_0000 | (addr & 0xFFFF)) {
+                return Some(label);
+            }
+        }
+        match bank {
+            0x00..=0x7D => symbols.label(addr | 0x80_0000),
+            0x80..=0xFD => symbols.label(addr & 0x7F_FFFF),
+            _ => None,
+        }
+    })
+}
 
 impl<'a> Context<'a> {
     pub fn read_8(&mut self, addr: u32) -> u8 {
@@ -32,6 +50,19 @@ impl<'a> Context<'a> {
         res
     }
 
+    /// Appends an address operand to the opcode, replacing it with the corresponding label if
+    /// there is one.
+    pub fn push_addr_operand(&mut self, addr: Option<u32>, raw: fmt::Arguments, idx_opcode: &str) {
+        match addr
+            .zip(self.symbols.as_deref())
+            .and_then(|(addr, symbols)| lookup_label(symbols, addr))
+        {
+            Some(label) => write!(self.next_instr.opcode, "{}{}", label, idx_opcode),
+            None => write!(self.next_instr.opcode, "{}{}", raw, idx_opcode),
+        }
+        .unwrap();
+    }
+
     fn resolve_direct_addr(&self, offset: u8) -> Option<u16> {
         self.direct_page_offset
             .map(|dp_off| dp_off.wrapping_add(offset as u16))
@@ -65,7 +96,11 @@ impl<'a> Context<'a> {
 
     fn handle_direct_addr(&mut self, idx_opcode: &str, idx_op_addr: &str) {
         let offset = self.read_direct_addr();
-        write!(self.next_instr.opcode, "${:02X}{}", offset, idx_opcode).unwrap();
+        self.push_addr_operand(
+            self.resolve_direct_addr(offset).map(|addr| addr as u32),
+            format_args!("${:02X}", offset),
+            idx_opcode,
+        );
         self.next_instr.op_addr = if let Some(addr) = self.resolve_direct_addr(offset) {
             format!("{:04X}{}", addr, idx_op_addr)
         } else {
@@ -129,7 +164,11 @@ impl<'a> Context<'a> {
 
     fn handle_absolute_short_addr(&mut self, idx_opcode: &str, idx_op_addr: &str) {
         let short_addr = self.read_absolute_short_addr();
-        write!(self.next_instr.opcode, "${:04X}{}", short_addr, idx_opcode).unwrap();
+        self.push_addr_operand(
+            self.resolve_short_addr(short_addr),
+            format_args!("${:04X}", short_addr),
+            idx_opcode,
+        );
         self.next_instr.op_addr = if let Some(addr) = self.resolve_short_addr(short_addr) {
             format!("{:06X}{}", addr, idx_op_addr)
         } else {
@@ -139,7 +178,7 @@ impl<'a> Context<'a> {
 
     fn handle_absolute_long_addr(&mut self, idx_opcode: &str, idx_op_addr: &str) {
         let addr = self.read_absolute_long_addr();
-        write!(self.next_instr.opcode, "${:06X}{}", addr, idx_opcode).unwrap();
+        self.push_addr_operand(Some(addr), format_args!("${:06X}", addr), idx_opcode);
         self.next_instr.op_addr = format!("{:06X}{}", addr, idx_op_addr);
     }
 
@@ -10,29 +10,34 @@ pub(super) fn mem_op<A: RegSize, const OP: &'static str, const ADDR: AddrMode>(c
 
 pub(super) fn branch<const COND: &'static str>(ctx: &mut Context) {
     let offset = ctx.consume_imm::<u8>() as i8 as i16;
-    ctx.next_instr.opcode = format!(
-        "B{} ${}{:02X}",
-        COND,
-        if offset < 0 { "-" } else { "" },
-        if offset < 0 { -offset } else { offset }
-    );
-    ctx.next_instr.op_addr = format!(
-        "{:06X}",
-        ctx.code_bank_base | ctx.pc.wrapping_add(offset as u16) as u32
+    let target_addr = ctx.code_bank_base | ctx.pc.wrapping_add(offset as u16) as u32;
+    ctx.next_instr.opcode = format!("B{} ", COND);
+    ctx.push_addr_operand(
+        Some(target_addr),
+        format_args!(
+            "${}{:02X}",
+            if offset < 0 { "-" } else { "" },
+            if offset < 0 { -offset } else { offset }
+        ),
+        "",
     );
+    ctx.next_instr.op_addr = format!("{:06X}", target_addr);
 }
 
 pub(super) fn brl(ctx: &mut Context) {
     let offset = ctx.consume_imm::<u16>() as i16 as i32;
-    ctx.next_instr.opcode = format!(
-        "BRL ${}{:04X}",
-        if offset < 0 { "-" } else { "" },
-        if offset < 0 { -offset } else { offset }
-    );
-    ctx.next_instr.op_addr = format!(
-        "{:06X}",
-        ctx.code_bank_base | ctx.pc.wrapping_add(offset as u16) as u32
+    let target_addr = ctx.code_bank_base | ctx.pc.wrapping_add(offset as u16) as u32;
+    ctx.next_instr.opcode = "BRL ".to_string();
+    ctx.push_addr_operand(
+        Some(target_addr),
+        format_args!(
+            "${}{:04X}",
+            if offset < 0 { "-" } else { "" },
+            if offset < 0 { -offset } else { offset }
+        ),
+        "",
     );
+    ctx.next_instr.op_addr = format!("{:06X}", target_addr);
 }
 
 pub(super) fn jmp<const SUBROUTINE: bool, const ADDR: JumpAddr>(ctx: &mut Context) {
@@ -54,12 +59,14 @@ pub(super) fn jmp<const SUBROUTINE: bool, const ADDR: JumpAddr>(ctx: &mut Contex
         JumpAddr::Absolute => {
             let new_pc = ctx.read_absolute_short_addr();
             let long_addr = ctx.code_bank_base | new_pc as u32;
-            ctx.next_instr.opcode = format!("{} ${:04X}", instr_name, new_pc);
+            ctx.next_instr.opcode = format!("{} ", instr_name);
+            ctx.push_addr_operand(Some(long_addr), format_args!("${:04X}", new_pc), "");
             ctx.next_instr.op_addr = format!("{:06X}", long_addr);
         }
         JumpAddr::AbsoluteLong => {
             let long_addr = ctx.read_absolute_long_addr();
-            ctx.next_instr.opcode = format!("{} ${:06X}", instr_name, long_addr);
+            ctx.next_instr.opcode = format!("{} ", instr_name);
+            ctx.push_addr_operand(Some(long_addr), format_args!("${:06X}", long_addr), "");
             ctx.next_instr.op_addr = format!("{:06X}", long_addr);
         }
         JumpAddr::AbsoluteIndirect => {
@@ -1,4 +1,9 @@
 use super::bus;
+#[cfg(feature = "debugger")]
+use crate::debugger::{
+    cdl,
+    events::{self, EventKind},
+};
 use crate::utils::bitfield_debug;
 use crate::{emu::Emu, schedule::Schedule};
 
@@ -68,6 +73,11 @@ impl Channel {
     pub fn gp_addr_step(&self) -> i8 {
         self.gp_addr_step
     }
+
+    #[inline]
+    pub fn h_do_transfer(&self) -> bool {
+        self.h_do_transfer
+    }
 }
 
 pub struct Controller {
@@ -77,6 +87,10 @@ pub struct Controller {
     h_frame_enabled: u8,
     h_requested: u8,
     pub cur_channel: Option<Index>,
+    /// Mask of channels whose transfers are skipped entirely, as if they had never been
+    /// requested; only meant for debugging.
+    #[cfg(feature = "debugger")]
+    pub disabled_channels: u8,
 }
 
 impl Controller {
@@ -100,9 +114,19 @@ impl Controller {
             h_frame_enabled: 0,
             h_requested: 0,
             cur_channel: None,
+            #[cfg(feature = "debugger")]
+            disabled_channels: 0,
         }
     }
 
+    #[inline]
+    fn enabled_channels(&self) -> u8 {
+        #[cfg(feature = "debugger")]
+        return !self.disabled_channels;
+        #[cfg(not(feature = "debugger"))]
+        0xFF
+    }
+
     fn select_next_channel(&mut self) {
         let requested = (self.gp_requested as u16) << 8 | self.h_requested as u16;
         if requested == 0 {
@@ -125,7 +149,7 @@ impl Controller {
 
     #[inline]
     pub fn set_gp_requested(&mut self, value: u8, schedule: &mut Schedule) {
-        self.gp_requested = value;
+        self.gp_requested = value & self.enabled_channels();
         self.select_next_channel();
         if self.cur_channel.is_some() {
             schedule.set_target_to_cur();
@@ -143,6 +167,11 @@ impl Controller {
         self.h_frame_enabled &= value;
     }
 
+    #[inline]
+    pub fn h_frame_enabled(&self) -> u8 {
+        self.h_frame_enabled
+    }
+
     fn reload_hdma_data<const INITIAL: bool>(emu: &mut Emu, i: Index) {
         let channel = &emu.cpu.dmac.channels[i.get() as usize];
         let mut table_addr = channel.h_cur_table_addr;
@@ -193,10 +222,10 @@ impl Controller {
         }
 
         emu.schedule.cur_time += 18;
-        emu.cpu.dmac.h_frame_enabled = emu.cpu.dmac.h_enabled;
+        emu.cpu.dmac.h_frame_enabled = emu.cpu.dmac.h_enabled & emu.cpu.dmac.enabled_channels();
 
         for i in 0..8 {
-            if emu.cpu.dmac.h_enabled & 1 << i == 0 {
+            if emu.cpu.dmac.h_frame_enabled & 1 << i == 0 {
                 continue;
             }
 
@@ -208,7 +237,7 @@ impl Controller {
     }
 
     pub(crate) fn start_hdmas(&mut self) {
-        self.h_requested |= self.h_frame_enabled;
+        self.h_requested |= self.h_frame_enabled & self.enabled_channels();
         self.select_next_channel();
     }
 
@@ -251,6 +280,10 @@ impl Controller {
                                     let $channel = &mut emu.cpu.dmac.channels[i.get() as usize];
                                     let a_addr = $get_a_addr;
                                     let b_addr = $get_b_addr;
+                                    #[cfg(feature = "debugger")]
+                                    if emu.debugger.cdl.is_some() {
+                                        cdl::log_dma_read(emu, a_addr, b_addr);
+                                    }
                                     let value = bus::read::<bus::DmaAccess>(emu, a_addr);
                                     bus::write_b_io::<bus::DmaAccess>(emu, b_addr, value);
                                     emu.schedule.cur_time += 8;
@@ -301,6 +334,17 @@ impl Controller {
         if emu.cpu.dmac.h_requested & 1 << i.get() != 0 {
             let channel = &emu.cpu.dmac.channels[i.get() as usize];
             if channel.h_do_transfer {
+                #[cfg(feature = "debugger")]
+                if emu.debugger.events.is_some() {
+                    let b_addr = channel.b_addr;
+                    events::log(
+                        emu,
+                        EventKind::Hdma {
+                            channel: i.get(),
+                            b_addr,
+                        },
+                    );
+                }
                 transfer!(
                     channel,
                     if channel.control.h_indirect() {
@@ -333,7 +377,17 @@ impl Controller {
             emu.cpu.dmac.cur_channel = None;
             emu.cpu.dmac.select_next_channel();
         } else {
+            #[cfg(feature = "debugger")]
+            let start_position = emu
+                .debugger
+                .events
+                .is_some()
+                .then(|| events::cur_position(emu));
             while emu.schedule.cur_time < emu.schedule.next_event_time() {
+                #[cfg(feature = "debugger")]
+                if emu.debugger.stop_reason().is_some() {
+                    break;
+                }
                 transfer!(
                     channel,
                     channel.gp_a_addr_h_table_start_addr as u32
@@ -354,6 +408,21 @@ impl Controller {
                     },
                 );
             }
+            #[cfg(feature = "debugger")]
+            if let Some(start_position) = start_position {
+                let (end_v, end_h) = events::cur_position(emu);
+                let b_addr = emu.cpu.dmac.channels[i.get() as usize].b_addr;
+                events::log_at(
+                    emu,
+                    start_position,
+                    EventKind::Dma {
+                        channel: i.get(),
+                        b_addr,
+                        end_v,
+                        end_h,
+                    },
+                );
+            }
         }
     }
 }
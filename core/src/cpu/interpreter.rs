@@ -13,6 +13,8 @@ mod transfers;
 use transfers::*;
 
 use super::dma;
+#[cfg(feature = "debugger")]
+use crate::debugger::{breakpoints, call_stack, cdl, step, trace};
 use crate::emu::Emu;
 use common::jump_to_exc_vector;
 
@@ -38,6 +40,10 @@ static INSTR_TABLE: [fn(&mut Emu); 0x800] =
 #[inline]
 pub fn run_until_next_event(emu: &mut Emu) {
     while emu.schedule.cur_time < emu.schedule.next_event_time() {
+        #[cfg(feature = "debugger")]
+        if emu.debugger.stop_reason().is_some() {
+            return;
+        }
         if let Some(channel) = emu.cpu.dmac.cur_channel {
             dma::Controller::run_dma(emu, channel);
         } else {
@@ -60,6 +66,22 @@ pub fn run_until_next_event(emu: &mut Emu) {
                 jump_to_exc_vector(emu, 0xFFEE);
             }
             while emu.schedule.cur_time < emu.schedule.target_time {
+                #[cfg(feature = "debugger")]
+                if breakpoints::check_exec(emu) || step::check_instr(emu) {
+                    return;
+                }
+                #[cfg(feature = "debugger")]
+                if emu.debugger.cpu_trace_log.is_some() {
+                    trace::log_cpu_instr(emu);
+                }
+                #[cfg(feature = "debugger")]
+                if emu.debugger.cdl.is_some() {
+                    cdl::log_instr(emu);
+                }
+                #[cfg(feature = "debugger")]
+                if emu.debugger.call_stack.is_some() {
+                    call_stack::log_instr(emu);
+                }
                 let instr = consume_imm::<u8>(emu);
                 unsafe {
                     INSTR_TABLE.get_unchecked(instr as usize | emu.cpu.regs.psw_lut_base() as usize)(
@@ -1,4 +1,6 @@
 pub use super::super::common::{AddrMode, JumpAddr, RegSize};
+#[cfg(feature = "debugger")]
+use crate::debugger::{call_stack, cdl};
 use crate::{cpu::bus, emu::Emu, schedule::Timestamp};
 
 pub fn add_io_cycles(emu: &mut Emu, cycles: u8) {
@@ -6,6 +8,22 @@ pub fn add_io_cycles(emu: &mut Emu, cycles: u8) {
 }
 
 pub fn read_8(emu: &mut Emu, addr: u32) -> u8 {
+    #[cfg(feature = "debugger")]
+    if emu.debugger.cdl.is_some() {
+        cdl::log_data(emu, addr);
+    }
+    let cycles = emu.cpu.bus_timings.get(addr);
+    let result = bus::read::<bus::CpuAccess>(emu, addr);
+    emu.schedule.cur_time += cycles as Timestamp;
+    result
+}
+
+/// Like `read_8`, but for instruction fetches.
+fn fetch_8(emu: &mut Emu, addr: u32) -> u8 {
+    #[cfg(feature = "debugger")]
+    if emu.debugger.cdl.is_some() {
+        cdl::log_code(emu, addr);
+    }
     let cycles = emu.cpu.bus_timings.get(addr);
     let result = bus::read::<bus::CpuAccess>(emu, addr);
     emu.schedule.cur_time += cycles as Timestamp;
@@ -49,12 +67,12 @@ pub fn consume_imm<T: RegSize>(emu: &mut Emu) -> T {
     if T::IS_U16 {
         let code_bank_base = emu.cpu.regs.code_bank_base();
         let pc = emu.cpu.regs.pc;
-        let res = read_8(emu, code_bank_base | pc as u32) as u16
-            | (read_8(emu, code_bank_base | pc.wrapping_add(1) as u32) as u16) << 8;
+        let res = fetch_8(emu, code_bank_base | pc as u32) as u16
+            | (fetch_8(emu, code_bank_base | pc.wrapping_add(1) as u32) as u16) << 8;
         emu.cpu.regs.pc = pc.wrapping_add(2);
         T::trunc_u16(res)
     } else {
-        let res = read_8(emu, emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32);
+        let res = fetch_8(emu, emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32);
         emu.cpu.regs.pc = emu.cpu.regs.pc.wrapping_add(1);
         T::zext_u8(res)
     }
@@ -88,6 +106,10 @@ pub fn pull<T: RegSize>(emu: &mut Emu) -> T {
 }
 
 pub fn jump_to_exc_vector(emu: &mut Emu, addr: u16) {
+    #[cfg(feature = "debugger")]
+    if emu.debugger.call_stack.is_some() {
+        call_stack::log_exception(emu, addr);
+    }
     emu.cpu.regs.set_psw(
         emu.cpu
             .regs
@@ -98,6 +120,10 @@ pub fn jump_to_exc_vector(emu: &mut Emu, addr: u16) {
     emu.cpu.irqs.set_irqs_enabled(false, &mut emu.schedule);
     emu.cpu.regs.pc = read_16_bank0(emu, addr);
     emu.cpu.regs.set_code_bank(0);
+    #[cfg(feature = "debugger")]
+    if emu.debugger.cdl.is_some() {
+        cdl::log_exception(emu);
+    }
 }
 
 pub fn read_direct_addr(emu: &mut Emu) -> u16 {
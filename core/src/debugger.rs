@@ -0,0 +1,90 @@
+pub mod breakpoints;
+pub mod call_stack;
+pub mod cdl;
+pub mod events;
+pub mod step;
+pub mod trace;
+
+use crate::emu::Emu;
+use breakpoints::Breakpoints;
+use step::Step;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint(breakpoints::Hit),
+    StepFinished,
+}
+
+pub struct Debugger {
+    pub breakpoints: Breakpoints,
+    pub cpu_trace_log: Option<trace::Log>,
+    pub cdl: Option<cdl::Log>,
+    pub call_stack: Option<call_stack::CallStack>,
+    pub events: Option<events::Log>,
+    stop_reason: Option<StopReason>,
+    skip_exec_check: bool,
+    step: Option<step::State>,
+}
+
+impl Debugger {
+    pub(crate) fn new() -> Self {
+        Debugger {
+            breakpoints: Breakpoints::new(),
+            cpu_trace_log: None,
+            cdl: None,
+            call_stack: None,
+            events: None,
+            stop_reason: None,
+            skip_exec_check: false,
+            step: None,
+        }
+    }
+
+    #[inline]
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    #[inline]
+    pub fn is_stepping(&self) -> bool {
+        self.step.is_some()
+    }
+
+    /// Starts a step, which will stop emulation once finished (calling `Emu::run_frame` afterwards
+    /// is needed to actually execute it); any previous step is cancelled.
+    pub fn start_step(emu: &mut Emu, step: Step) {
+        Self::cancel_step(emu);
+        emu.debugger.step = Some(step::start(emu, step));
+    }
+
+    pub fn cancel_step(emu: &mut Emu) {
+        emu.debugger.step = None;
+        emu.apu.spc700.debug_break_after_instr = false;
+    }
+
+    /// Requests emulation to stop after the current instruction (or DMA transfer unit); only the
+    /// first reason given before emulation actually stops is kept, and any running step is
+    /// cancelled.
+    pub(crate) fn stop(emu: &mut Emu, reason: StopReason) {
+        if emu.debugger.stop_reason.is_none() {
+            emu.debugger.stop_reason = Some(reason);
+        }
+        Self::cancel_step(emu);
+        emu.schedule.set_target_to_cur();
+    }
+
+    /// Clears the last stop reason before resuming emulation; if emulation was stopped by an
+    /// execution breakpoint, the next instruction will be executed without checking it again, to
+    /// allow continuing past it.
+    pub(crate) fn resume(&mut self) {
+        self.skip_exec_check = match self.stop_reason.take() {
+            Some(StopReason::Breakpoint(hit)) => hit.kind == breakpoints::Kinds::EXEC,
+            Some(StopReason::StepFinished) | None => false,
+        };
+    }
+
+    #[inline]
+    pub(crate) fn take_skip_exec_check(&mut self) -> bool {
+        core::mem::take(&mut self.skip_exec_check)
+    }
+}
@@ -0,0 +1,454 @@
+use super::{Debugger, StopReason};
+use crate::{
+    cpu::bus::{self, DebugCpuAccess},
+    emu::Emu,
+};
+use core::fmt::{self, Display};
+use std::error::Error;
+
+bitflags::bitflags! {
+    pub struct Kinds: u8 {
+        const EXEC = 1 << 0;
+        const READ = 1 << 1;
+        const WRITE = 1 << 2;
+        const DMA_SRC = 1 << 3;
+        const DMA_DST = 1 << 4;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    A,
+    X,
+    Y,
+    Sp,
+    DirectPage,
+    DataBank,
+    CodeBank,
+    Psw,
+    Pc,
+    /// The value being read or written by the access that triggered the breakpoint (or the
+    /// opcode, for execution breakpoints).
+    Value,
+    Mem8(u32),
+    Mem16(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub lhs: Operand,
+    pub comparison: Comparison,
+    pub rhs: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConditionParseError {
+    MissingComparison,
+    UnknownOperand(String),
+    InvalidValue(String),
+}
+
+impl Error for ConditionParseError {}
+
+impl Display for ConditionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingComparison => f.write_str("Missing comparison operator"),
+            Self::UnknownOperand(operand) => write!(f, "Unknown operand: `{}`", operand),
+            Self::InvalidValue(value) => write!(f, "Invalid value: `{}`", value),
+        }
+    }
+}
+
+fn parse_value(value: &str) -> Result<u32, ConditionParseError> {
+    let value = value.trim();
+    let result = if let Some(hex) = value.strip_prefix('$').or_else(|| value.strip_prefix("0x")) {
+        u32::from_str_radix(hex, 16)
+    } else {
+        value.parse()
+    };
+    result.map_err(|_| ConditionParseError::InvalidValue(value.to_string()))
+}
+
+impl Condition {
+    /// Parses a condition in the form `<operand> <comparison> <value>`, where `<operand>` is
+    /// either a register name (`a`, `x`, `y`, `sp`, `d`, `db`, `pb`, `p`, `pc`), `value` (the
+    /// accessed value) or a memory location (`[addr]` for 8-bit reads, `[addr].w` for 16-bit
+    /// ones); values can be either decimal or hexadecimal, prefixed with `$` or `0x`.
+    pub fn parse(input: &str) -> Result<Self, ConditionParseError> {
+        let (comparison, op_str) = [
+            (Comparison::Eq, "=="),
+            (Comparison::Ne, "!="),
+            (Comparison::Le, "<="),
+            (Comparison::Ge, ">="),
+            (Comparison::Lt, "<"),
+            (Comparison::Gt, ">"),
+        ]
+        .into_iter()
+        .find(|(_, op_str)| input.contains(op_str))
+        .ok_or(ConditionParseError::MissingComparison)?;
+        let (lhs, rhs) = input.split_once(op_str).unwrap();
+
+        let lhs = lhs.trim().to_ascii_lowercase();
+        let lhs = match lhs.as_str() {
+            "a" => Operand::A,
+            "x" => Operand::X,
+            "y" => Operand::Y,
+            "s" | "sp" => Operand::Sp,
+            "d" | "dp" => Operand::DirectPage,
+            "b" | "db" | "dbr" => Operand::DataBank,
+            "k" | "pb" | "pbr" => Operand::CodeBank,
+            "p" | "psw" => Operand::Psw,
+            "pc" => Operand::Pc,
+            "value" => Operand::Value,
+            _ => {
+                let (addr, is_16_bit) = if let Some(addr) = lhs.strip_suffix(".w") {
+                    (addr, true)
+                } else {
+                    (lhs.as_str(), false)
+                };
+                let addr = addr
+                    .strip_prefix('[')
+                    .and_then(|addr| addr.strip_suffix(']'))
+                    .ok_or_else(|| ConditionParseError::UnknownOperand(lhs.clone()))?;
+                let addr = parse_value(addr)? & 0xFF_FFFF;
+                if is_16_bit {
+                    Operand::Mem16(addr)
+                } else {
+                    Operand::Mem8(addr)
+                }
+            }
+        };
+
+        Ok(Condition {
+            lhs,
+            comparison,
+            rhs: parse_value(rhs)?,
+        })
+    }
+
+    fn check(&self, emu: &mut Emu, value: u32) -> bool {
+        let regs = &emu.cpu.regs;
+        let lhs = match self.lhs {
+            Operand::A => regs.a as u32,
+            Operand::X => regs.x as u32,
+            Operand::Y => regs.y as u32,
+            Operand::Sp => regs.sp as u32,
+            Operand::DirectPage => regs.direct_page_offset as u32,
+            Operand::DataBank => regs.data_bank() as u32,
+            Operand::CodeBank => regs.code_bank() as u32,
+            Operand::Psw => regs.psw().0 as u32,
+            Operand::Pc => regs.pc as u32,
+            Operand::Value => value,
+            Operand::Mem8(addr) => bus::read::<DebugCpuAccess>(emu, addr) as u32,
+            Operand::Mem16(addr) => {
+                bus::read::<DebugCpuAccess>(emu, addr) as u32
+                    | (bus::read::<DebugCpuAccess>(emu, (addr + 1) & 0xFF_FFFF) as u32) << 8
+            }
+        };
+        match self.comparison {
+            Comparison::Eq => lhs == self.rhs,
+            Comparison::Ne => lhs != self.rhs,
+            Comparison::Lt => lhs < self.rhs,
+            Comparison::Le => lhs <= self.rhs,
+            Comparison::Gt => lhs > self.rhs,
+            Comparison::Ge => lhs >= self.rhs,
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.lhs {
+            Operand::A => f.write_str("a")?,
+            Operand::X => f.write_str("x")?,
+            Operand::Y => f.write_str("y")?,
+            Operand::Sp => f.write_str("sp")?,
+            Operand::DirectPage => f.write_str("d")?,
+            Operand::DataBank => f.write_str("db")?,
+            Operand::CodeBank => f.write_str("pb")?,
+            Operand::Psw => f.write_str("p")?,
+            Operand::Pc => f.write_str("pc")?,
+            Operand::Value => f.write_str("value")?,
+            Operand::Mem8(addr) => write!(f, "[${:06X}]", addr)?,
+            Operand::Mem16(addr) => write!(f, "[${:06X}].w", addr)?,
+        }
+        write!(
+            f,
+            " {} ${:X}",
+            match self.comparison {
+                Comparison::Eq => "==",
+                Comparison::Ne => "!=",
+                Comparison::Lt => "<",
+                Comparison::Le => "<=",
+                Comparison::Gt => ">",
+                Comparison::Ge => ">=",
+            },
+            self.rhs
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub kinds: Kinds,
+    pub start_addr: u32,
+    pub end_addr: u32,
+    pub condition: Option<Condition>,
+    pub enabled: bool,
+}
+
+impl Breakpoint {
+    #[inline]
+    pub fn contains(&self, addr: u32) -> bool {
+        (self.start_addr..=self.end_addr).contains(&addr)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub index: usize,
+    pub kind: Kinds,
+    pub addr: u32,
+    pub value: Option<u8>,
+}
+
+pub struct Breakpoints {
+    list: Vec<Breakpoint>,
+    enabled_kinds: Kinds,
+}
+
+impl Breakpoints {
+    pub(super) fn new() -> Self {
+        Breakpoints {
+            list: Vec::new(),
+            enabled_kinds: Kinds::empty(),
+        }
+    }
+
+    fn update_enabled_kinds(&mut self) {
+        self.enabled_kinds = self
+            .list
+            .iter()
+            .filter(|breakpoint| breakpoint.enabled)
+            .fold(Kinds::empty(), |acc, breakpoint| acc | breakpoint.kinds);
+    }
+
+    #[inline]
+    pub fn list(&self) -> &[Breakpoint] {
+        &self.list
+    }
+
+    #[inline]
+    pub fn enabled_kinds(&self) -> Kinds {
+        self.enabled_kinds
+    }
+
+    pub fn add(&mut self, breakpoint: Breakpoint) -> usize {
+        self.list.push(breakpoint);
+        self.update_enabled_kinds();
+        self.list.len() - 1
+    }
+
+    pub fn remove(&mut self, i: usize) -> Breakpoint {
+        let result = self.list.remove(i);
+        self.update_enabled_kinds();
+        result
+    }
+
+    pub fn set(&mut self, i: usize, breakpoint: Breakpoint) {
+        self.list[i] = breakpoint;
+        self.update_enabled_kinds();
+    }
+
+    pub fn set_all(&mut self, list: Vec<Breakpoint>) {
+        self.list = list;
+        self.update_enabled_kinds();
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.enabled_kinds = Kinds::empty();
+    }
+}
+
+#[cold]
+fn check(emu: &mut Emu, kind: Kinds, addr: u32, value: Option<u8>) -> bool {
+    for i in 0..emu.debugger.breakpoints.list.len() {
+        let breakpoint = emu.debugger.breakpoints.list[i];
+        if !breakpoint.enabled || !breakpoint.kinds.contains(kind) || !breakpoint.contains(addr) {
+            continue;
+        }
+        if let Some(condition) = &breakpoint.condition {
+            let value = match value {
+                Some(value) => value,
+                None => bus::read::<DebugCpuAccess>(emu, addr),
+            };
+            if !condition.check(emu, value as u32) {
+                continue;
+            }
+        }
+        Debugger::stop(
+            emu,
+            StopReason::Breakpoint(Hit {
+                index: i,
+                kind,
+                addr,
+                value,
+            }),
+        );
+        return true;
+    }
+    false
+}
+
+/// Checks execution breakpoints for the instruction at the current PC, returning whether emulation
+/// should stop before executing it.
+#[inline]
+pub(crate) fn check_exec(emu: &mut Emu) -> bool {
+    if !emu.debugger.breakpoints.enabled_kinds.contains(Kinds::EXEC)
+        || emu.debugger.take_skip_exec_check()
+    {
+        return false;
+    }
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    check(emu, Kinds::EXEC, pc, None)
+}
+
+/// Checks breakpoints of the given access kind (other than execution) for an access to `addr`;
+/// `value` should be `Some` for writes, while reads will perform a side effect-free read to
+/// evaluate conditions if needed.
+#[inline]
+pub(crate) fn check_access(emu: &mut Emu, kind: Kinds, addr: u32, value: Option<u8>) {
+    if emu.debugger.breakpoints.enabled_kinds.contains(kind) {
+        check(emu, kind, addr, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(lhs: Operand, comparison: Comparison, rhs: u32) -> Condition {
+        Condition {
+            lhs,
+            comparison,
+            rhs,
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        // Two-character operators must be matched before the one-character ones they contain
+        for (input, comparison) in [
+            ("a == 1", Comparison::Eq),
+            ("a != 1", Comparison::Ne),
+            ("a < 1", Comparison::Lt),
+            ("a <= 1", Comparison::Le),
+            ("a > 1", Comparison::Gt),
+            ("a >= 1", Comparison::Ge),
+        ] {
+            assert_eq!(
+                Condition::parse(input),
+                Ok(condition(Operand::A, comparison, 1)),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            Condition::parse("x<=$10"),
+            Ok(condition(Operand::X, Comparison::Le, 0x10))
+        );
+    }
+
+    #[test]
+    fn operands() {
+        assert_eq!(
+            Condition::parse("DB == 0x7E"),
+            Ok(condition(Operand::DataBank, Comparison::Eq, 0x7E))
+        );
+        assert_eq!(
+            Condition::parse("value != 0"),
+            Ok(condition(Operand::Value, Comparison::Ne, 0))
+        );
+        assert_eq!(
+            Condition::parse("[$7E0010] == 3"),
+            Ok(condition(Operand::Mem8(0x7E_0010), Comparison::Eq, 3))
+        );
+        assert_eq!(
+            Condition::parse("[0x7E0010].w >= $1234"),
+            Ok(condition(Operand::Mem16(0x7E_0010), Comparison::Ge, 0x1234))
+        );
+        assert_eq!(
+            Condition::parse("[256] < 10"),
+            Ok(condition(Operand::Mem8(0x100), Comparison::Lt, 10))
+        );
+        // Addresses wrap around to the 24-bit bus
+        assert_eq!(
+            Condition::parse("[$1000000] == 0"),
+            Ok(condition(Operand::Mem8(0), Comparison::Eq, 0))
+        );
+    }
+
+    #[test]
+    fn values() {
+        for (input, rhs) in [
+            ("pc == $8000", 0x8000),
+            ("pc == 0x8000", 0x8000),
+            ("pc == 32768", 32768),
+            ("pc == $ff", 0xFF),
+        ] {
+            assert_eq!(
+                Condition::parse(input),
+                Ok(condition(Operand::Pc, Comparison::Eq, rhs)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Condition::parse("a = 1"),
+            Err(ConditionParseError::MissingComparison)
+        );
+        assert_eq!(
+            Condition::parse("q == 1"),
+            Err(ConditionParseError::UnknownOperand("q".to_string()))
+        );
+        assert_eq!(
+            Condition::parse("[$10 == 1"),
+            Err(ConditionParseError::UnknownOperand("[$10".to_string()))
+        );
+        assert_eq!(
+            Condition::parse("a == $xyz"),
+            Err(ConditionParseError::InvalidValue("$xyz".to_string()))
+        );
+        assert_eq!(
+            Condition::parse("[$zz] == 1"),
+            Err(ConditionParseError::InvalidValue("$zz".to_string()))
+        );
+        assert_eq!(
+            Condition::parse("a == "),
+            Err(ConditionParseError::InvalidValue(String::new()))
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let condition = condition(Operand::Mem16(0x7E_0010), Comparison::Le, 0x1234);
+        assert_eq!(condition.to_string(), "[$7E0010].w <= $1234");
+        assert_eq!(Condition::parse(&condition.to_string()), Ok(condition));
+    }
+}
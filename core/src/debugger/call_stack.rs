@@ -0,0 +1,218 @@
+use crate::{
+    cpu::bus::{self, DebugCpuAccess},
+    emu::Emu,
+    schedule::Timestamp,
+};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    Jsr,
+    Jsl,
+    Nmi,
+    Irq,
+    Brk,
+    Cop,
+}
+
+impl FrameKind {
+    /// Returns the kind of frame entered through the exception vector at `vector`, or `None` for
+    /// the reset vector. In emulation mode, BRK shares the IRQ vector.
+    fn from_vector(vector: u16) -> Option<Self> {
+        match vector {
+            0xFFEA | 0xFFFA => Some(FrameKind::Nmi),
+            0xFFEE | 0xFFFE => Some(FrameKind::Irq),
+            0xFFE6 => Some(FrameKind::Brk),
+            0xFFE4 | 0xFFF4 => Some(FrameKind::Cop),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the amount of bytes pushed when entering an exception through `vector`: native mode
+/// pushes the code bank, PC and flags, while emulation mode (with its own vectors at
+/// 0xFFF0-0xFFFF) doesn't push the code bank.
+fn exception_frame_len(vector: u16) -> u16 {
+    if vector >= 0xFFF0 {
+        3
+    } else {
+        4
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    pub kind: FrameKind,
+    /// The address of the calling instruction (or of the instruction that would have executed
+    /// next, for interrupts).
+    pub call_site: u32,
+    pub entry: u32,
+    /// The stack pointer at the routine's entry point, after the return address was pushed; the
+    /// frame is popped once a return instruction moves it above this value.
+    pub sp: u16,
+    start_time: Timestamp,
+    child_cycles: Timestamp,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoutineStats {
+    pub calls: u64,
+    /// Master cycles spent in the routine, including the routines it called.
+    pub inclusive_cycles: u64,
+    /// Master cycles spent in the routine itself, excluding the routines it called.
+    pub exclusive_cycles: u64,
+}
+
+/// Per-routine cycle counts, keyed by entry point; cycles are only accounted for once a routine
+/// returns.
+#[derive(Clone, Debug, Default)]
+pub struct Profiler {
+    routines: HashMap<u32, RoutineStats>,
+}
+
+impl Profiler {
+    #[inline]
+    pub fn routines(&self) -> &HashMap<u32, RoutineStats> {
+        &self.routines
+    }
+
+    pub fn reset(&mut self) {
+        self.routines.clear();
+    }
+}
+
+/// Tracks the 65816 call stack by following JSR/JSL/RTS/RTL/RTI and exception entry, profiling
+/// the time spent in each routine.
+pub struct CallStack {
+    frames: Vec<Frame>,
+    pending_call: Option<(FrameKind, u32)>,
+    pending_return: bool,
+    pub profiler: Profiler,
+}
+
+impl CallStack {
+    /// The maximum amount of frames to keep track of; if code never returns from its routines
+    /// (i.e. by manually adjusting the stack pointer), the oldest ones get dropped.
+    const MAX_DEPTH: usize = 256;
+
+    pub fn new() -> Self {
+        CallStack {
+            frames: Vec::new(),
+            pending_call: None,
+            pending_return: false,
+            profiler: Profiler::default(),
+        }
+    }
+
+    /// Returns the current call stack, innermost frame last.
+    #[inline]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.pending_call = None;
+        self.pending_return = false;
+    }
+
+    fn push(&mut self, kind: FrameKind, call_site: u32, entry: u32, sp: u16, time: Timestamp) {
+        if self.frames.len() == Self::MAX_DEPTH {
+            self.frames.remove(0);
+        }
+        self.frames.push(Frame {
+            kind,
+            call_site,
+            entry,
+            sp,
+            start_time: time,
+            child_cycles: 0,
+        });
+    }
+
+    fn pop_returned(&mut self, sp: u16, time: Timestamp) {
+        while let Some(frame) = self.frames.last() {
+            if frame.sp >= sp {
+                break;
+            }
+            let frame = self.frames.pop().unwrap();
+            let inclusive_cycles = time - frame.start_time;
+            let stats = self.profiler.routines.entry(frame.entry).or_default();
+            stats.calls += 1;
+            stats.inclusive_cycles += inclusive_cycles as u64;
+            stats.exclusive_cycles += inclusive_cycles.saturating_sub(frame.child_cycles) as u64;
+            if let Some(parent) = self.frames.last_mut() {
+                parent.child_cycles += inclusive_cycles;
+            }
+        }
+    }
+}
+
+impl Default for CallStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn push_pending_call(emu: &mut Emu, sp: u16) {
+    let entry = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let time = emu.schedule.cur_time;
+    if let Some(call_stack) = &mut emu.debugger.call_stack {
+        if let Some((kind, call_site)) = call_stack.pending_call.take() {
+            call_stack.push(kind, call_site, entry, sp, time);
+        }
+    }
+}
+
+/// Called before executing the instruction at the current PC, to push the frame for a call that
+/// just happened, pop frames after a return, and detect calls and returns in the instruction
+/// itself.
+#[cold]
+pub(crate) fn log_instr(emu: &mut Emu) {
+    let sp = emu.cpu.regs.sp;
+    push_pending_call(emu, sp);
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let opcode = bus::read::<DebugCpuAccess>(emu, pc);
+    let time = emu.schedule.cur_time;
+    let call_stack = match &mut emu.debugger.call_stack {
+        Some(call_stack) => call_stack,
+        None => return,
+    };
+    if core::mem::take(&mut call_stack.pending_return) {
+        call_stack.pop_returned(sp, time);
+    }
+    match opcode {
+        // JSR abs, JSR (a,x)
+        0x20 | 0xFC => call_stack.pending_call = Some((FrameKind::Jsr, pc)),
+        // JSL
+        0x22 => call_stack.pending_call = Some((FrameKind::Jsl, pc)),
+        // RTI, RTS, RTL
+        0x40 | 0x60 | 0x6B => call_stack.pending_return = true,
+        _ => {}
+    }
+}
+
+/// Called when the CPU is about to jump to the exception vector at `vector`, after the return
+/// address and flags have been pushed.
+#[cold]
+pub(crate) fn log_exception(emu: &mut Emu, vector: u16) {
+    // The stack pointer before the exception's pushes
+    let sp = emu.cpu.regs.sp.wrapping_add(exception_frame_len(vector));
+    // If an interrupt arrives right after a call, the called routine's frame needs to be pushed
+    // first
+    push_pending_call(emu, sp);
+    let call_site = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let time = emu.schedule.cur_time;
+    let call_stack = match &mut emu.debugger.call_stack {
+        Some(call_stack) => call_stack,
+        None => return,
+    };
+    // Same for a return, as the stack pointer is about to be decremented
+    if core::mem::take(&mut call_stack.pending_return) {
+        call_stack.pop_returned(sp, time);
+    }
+    match FrameKind::from_vector(vector) {
+        Some(kind) => call_stack.pending_call = Some((kind, call_site)),
+        None => call_stack.clear(),
+    }
+}
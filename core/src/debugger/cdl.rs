@@ -0,0 +1,187 @@
+use crate::{
+    cpu::bus::{self, DebugCpuAccess},
+    emu::Emu,
+};
+use std::io::{self, Write};
+
+bitflags::bitflags! {
+    /// Per-byte ROM flags; the low 8 bits match Mesen-S's CDL format.
+    pub struct Flags: u16 {
+        const CODE = 1 << 0;
+        const DATA = 1 << 1;
+        const JUMP_TARGET = 1 << 2;
+        const SUB_ENTRY_POINT = 1 << 3;
+        const INDEX_MODE_8 = 1 << 4;
+        const MEMORY_MODE_8 = 1 << 5;
+        /// Set for bytes DMA'd to VRAM, CGRAM or OAM. Mesen-S's format has no bit left for this
+        /// (bits 6 and 7 mark Super FX and Cx4 code), so it's left out of saved logs.
+        const PPU_DMA = 1 << 8;
+    }
+}
+
+const HEADER_MAGIC: &[u8; 5] = b"CDLv2";
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = crc >> 1 ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// A code/data log, tracking how each byte of the ROM has been accessed, by ROM offset (so that
+/// all mirrors of a byte share the same flags).
+pub struct Log {
+    flags: Box<[u16]>,
+    prev_instr: Option<(u32, u8)>,
+}
+
+impl Log {
+    pub fn new(rom_len: usize) -> Self {
+        Log {
+            flags: vec![0; rom_len].into_boxed_slice(),
+            prev_instr: None,
+        }
+    }
+
+    /// Loads a log previously saved with `save` for the given ROM, returning `None` if it was
+    /// created for a different ROM or is otherwise invalid. Headerless (v1) logs are accepted if
+    /// their size matches.
+    pub fn load(rom: &[u8], data: &[u8]) -> Option<Self> {
+        let flags = match data.strip_prefix(HEADER_MAGIC) {
+            Some(data) => {
+                let (crc, flags) = data.split_at(4.min(data.len()));
+                if crc != crc32(rom).to_le_bytes() {
+                    return None;
+                }
+                flags
+            }
+            None => data,
+        };
+        if flags.len() != rom.len() {
+            return None;
+        }
+        Some(Log {
+            flags: flags.iter().map(|&flags| flags as u16).collect(),
+            prev_instr: None,
+        })
+    }
+
+    /// Writes the log in Mesen-S's `.cdl` format; `rom` is needed to compute the header checksum.
+    pub fn save(&self, rom: &[u8], writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(HEADER_MAGIC)?;
+        writer.write_all(&crc32(rom).to_le_bytes())?;
+        let flags: Vec<u8> = self.flags.iter().map(|&flags| flags as u8).collect();
+        writer.write_all(&flags)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    #[inline]
+    pub fn flags(&self, rom_offset: u32) -> Flags {
+        self.flags
+            .get(rom_offset as usize)
+            .map_or(Flags::empty(), |&flags| Flags::from_bits_truncate(flags))
+    }
+
+    pub fn reset(&mut self) {
+        self.flags.fill(0);
+        self.prev_instr = None;
+    }
+
+    fn mark(&mut self, rom_offset: Option<u32>, flags: Flags) {
+        if let Some(value) = rom_offset.and_then(|offset| self.flags.get_mut(offset as usize)) {
+            *value |= flags.bits();
+        }
+    }
+}
+
+/// Marks the ROM byte at `addr` as fetched by the CPU as part of an instruction.
+#[cold]
+pub(crate) fn log_code(emu: &mut Emu, addr: u32) {
+    let rom_offset = emu.cart.rom_offset(addr);
+    let psw = emu.cpu.regs.psw();
+    let mut flags = Flags::CODE;
+    flags.set(Flags::MEMORY_MODE_8, psw.a_is_8_bit());
+    flags.set(Flags::INDEX_MODE_8, psw.index_regs_are_8_bit());
+    if let Some(log) = &mut emu.debugger.cdl {
+        log.mark(rom_offset, flags);
+    }
+}
+
+/// Marks the ROM byte at `addr` as read by the CPU as data.
+#[cold]
+pub(crate) fn log_data(emu: &mut Emu, addr: u32) {
+    let rom_offset = emu.cart.rom_offset(addr);
+    if let Some(log) = &mut emu.debugger.cdl {
+        log.mark(rom_offset, Flags::DATA);
+    }
+}
+
+/// Marks the ROM byte at `a_addr` as read by a DMA transfer to the B bus register `b_addr`.
+#[cold]
+pub(crate) fn log_dma_read(emu: &mut Emu, a_addr: u32, b_addr: u8) {
+    let rom_offset = emu.cart.rom_offset(a_addr);
+    let mut flags = Flags::DATA;
+    // OAMDATA, VMDATAL/VMDATAH, CGDATA
+    flags.set(Flags::PPU_DMA, matches!(b_addr, 0x04 | 0x18 | 0x19 | 0x22));
+    if let Some(log) = &mut emu.debugger.cdl {
+        log.mark(rom_offset, flags);
+    }
+}
+
+/// Called before executing the instruction at the current PC; marks it as a jump target or
+/// subroutine entry point depending on how control was transferred to it from the previous one.
+#[cold]
+pub(crate) fn log_instr(emu: &mut Emu) {
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let opcode = bus::read::<DebugCpuAccess>(emu, pc);
+    let rom_offset = emu.cart.rom_offset(pc);
+    let log = match &mut emu.debugger.cdl {
+        Some(log) => log,
+        None => return,
+    };
+    let flags = match log.prev_instr.replace((pc, opcode)) {
+        // JSR, JSL, JSR (a,x)
+        Some((_, 0x20 | 0x22 | 0xFC)) => Flags::SUB_ENTRY_POINT,
+        // JMP
+        Some((_, 0x4C | 0x5C | 0x6C | 0x7C | 0xDC)) => Flags::JUMP_TARGET,
+        // Conditional branches and BRA (2 bytes), BRL (3 bytes); only taken ones are marked
+        Some((
+            prev_pc,
+            prev_opcode @ (0x10 | 0x30 | 0x50 | 0x70 | 0x90 | 0xB0 | 0xD0 | 0xF0 | 0x80 | 0x82),
+        )) => {
+            let len = if prev_opcode == 0x82 { 3 } else { 2 };
+            let next_pc = (prev_pc & 0xFF_0000) | (prev_pc as u16).wrapping_add(len) as u32;
+            if pc != next_pc {
+                Flags::JUMP_TARGET
+            } else {
+                return;
+            }
+        }
+        _ => return,
+    };
+    log.mark(rom_offset, flags);
+}
+
+/// Called after jumping to an exception vector, marking the handler as a subroutine entry point.
+#[cold]
+pub(crate) fn log_exception(emu: &mut Emu) {
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let rom_offset = emu.cart.rom_offset(pc);
+    if let Some(log) = &mut emu.debugger.cdl {
+        log.prev_instr = None;
+        log.mark(rom_offset, Flags::SUB_ENTRY_POINT);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::emu::Emu;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// A CPU write to a PPU/APU/WRAM register in the `$21xx` range.
+    BBusWrite {
+        addr: u8,
+        value: u8,
+    },
+    /// A CPU write to an internal CPU register in the `$4000`-`$43FF` range.
+    CpuRegWrite {
+        addr: u16,
+        value: u8,
+    },
+    /// An HDMA transfer for a single scanline.
+    Hdma {
+        channel: u8,
+        b_addr: u8,
+    },
+    /// A (possibly partial) general-purpose DMA transfer, ending at the given position.
+    Dma {
+        channel: u8,
+        b_addr: u8,
+        end_v: u16,
+        end_h: u16,
+    },
+    Nmi,
+    Irq,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub v: u16,
+    pub h: u16,
+    /// The address of the CPU instruction that was executing when the event happened.
+    pub pc: u32,
+}
+
+/// Records timing-sensitive events over the course of a frame, together with their scanline
+/// positions.
+pub struct Log {
+    cur_frame: Vec<Event>,
+    last_frame: Vec<Event>,
+}
+
+impl Log {
+    /// The maximum amount of events to record per frame, to avoid unbounded memory usage if a game
+    /// writes to registers in a tight loop.
+    pub const MAX_EVENTS: usize = 0x4_0000;
+
+    pub fn new() -> Self {
+        Log {
+            cur_frame: Vec::new(),
+            last_frame: Vec::new(),
+        }
+    }
+
+    /// Returns the events recorded during the last complete frame (starting and ending at the
+    /// start of vblank).
+    #[inline]
+    pub fn last_frame(&self) -> &[Event] {
+        &self.last_frame
+    }
+
+    /// Returns the events recorded so far in the current frame.
+    #[inline]
+    pub fn cur_frame(&self) -> &[Event] {
+        &self.cur_frame
+    }
+
+    pub(crate) fn finish_frame(&mut self) {
+        core::mem::swap(&mut self.cur_frame, &mut self.last_frame);
+        self.cur_frame.clear();
+    }
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the current PPU position as `(v, h)`.
+pub(crate) fn cur_position(emu: &Emu) -> (u16, u16) {
+    (
+        emu.ppu.counters.v_counter(),
+        emu.ppu.counters.h_dot(emu.schedule.cur_time),
+    )
+}
+
+#[cold]
+pub(crate) fn log(emu: &mut Emu, kind: EventKind) {
+    let position = cur_position(emu);
+    log_at(emu, position, kind);
+}
+
+/// Records an event that started at the given earlier position.
+#[cold]
+pub(crate) fn log_at(emu: &mut Emu, (v, h): (u16, u16), kind: EventKind) {
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    if let Some(log) = &mut emu.debugger.events {
+        if log.cur_frame.len() < Log::MAX_EVENTS {
+            log.cur_frame.push(Event { kind, v, h, pc });
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use super::{Debugger, StopReason};
+use crate::{
+    cpu::bus::{self, DebugCpuAccess},
+    emu::Emu,
+};
+
+const DOTS_PER_SCANLINE: u32 = 341;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Executes a single 65816 instruction (entering an interrupt handler if one gets triggered).
+    CpuInstr,
+    /// Like `CpuInstr`, but runs `JSR`/`JSL` subroutine calls until they return.
+    CpuOver,
+    /// Runs until the current 65816 subroutine or interrupt handler returns through
+    /// `RTS`/`RTL`/`RTI`.
+    CpuOut,
+    /// Executes a single SPC700 instruction.
+    SpcInstr,
+    /// Runs until the PPU reaches the given scanline and dot.
+    ToPosition { v: u16, h: u16 },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum State {
+    CpuInstr { started: bool },
+    CpuOver { return_addr: u32, sp: u16 },
+    CpuOut { sp: u16 },
+    SpcInstr,
+    ToPosition { target: u32, last: u32 },
+}
+
+fn cpu_pc(emu: &Emu) -> u32 {
+    emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32
+}
+
+fn ppu_position(emu: &Emu) -> u32 {
+    emu.ppu.counters.v_counter() as u32 * DOTS_PER_SCANLINE
+        + emu.ppu.counters.h_dot(emu.schedule.cur_time) as u32
+}
+
+pub(super) fn start(emu: &mut Emu, step: Step) -> State {
+    match step {
+        Step::CpuInstr => State::CpuInstr { started: false },
+        Step::CpuOver => {
+            let pc = cpu_pc(emu);
+            let instr_len = match bus::read::<DebugCpuAccess>(emu, pc) {
+                // JSR abs, JSR (abs,X)
+                0x20 | 0xFC => 3,
+                // JSL long
+                0x22 => 4,
+                _ => return State::CpuInstr { started: false },
+            };
+            State::CpuOver {
+                return_addr: emu.cpu.regs.code_bank_base()
+                    | emu.cpu.regs.pc.wrapping_add(instr_len) as u32,
+                sp: emu.cpu.regs.sp,
+            }
+        }
+        Step::CpuOut => State::CpuOut {
+            sp: emu.cpu.regs.sp,
+        },
+        Step::SpcInstr => {
+            emu.apu.spc700.debug_break_after_instr = true;
+            emu.apu.spc700.debug_instr_stepped = false;
+            State::SpcInstr
+        }
+        Step::ToPosition { v, h } => State::ToPosition {
+            target: v as u32 * DOTS_PER_SCANLINE + h as u32,
+            last: ppu_position(emu),
+        },
+    }
+}
+
+fn finish(emu: &mut Emu) -> bool {
+    Debugger::stop(emu, StopReason::StepFinished);
+    true
+}
+
+/// Checks whether the PPU position has passed the target since the last check, wrapping around at
+/// the end of the frame.
+fn check_position(emu: &mut Emu, target: u32, last: u32) -> bool {
+    let cur = ppu_position(emu);
+    let reached = if cur >= last {
+        last < target && target <= cur
+    } else {
+        last < target || target <= cur
+    };
+    if reached {
+        return finish(emu);
+    }
+    emu.debugger.step = Some(State::ToPosition { target, last: cur });
+    false
+}
+
+/// Updates the current step's state before executing a 65816 instruction, returning whether
+/// emulation should stop before executing it.
+#[inline]
+pub(crate) fn check_instr(emu: &mut Emu) -> bool {
+    let state = match emu.debugger.step {
+        Some(state) => state,
+        None => return false,
+    };
+    match state {
+        State::CpuInstr { started: false } => {
+            emu.debugger.step = Some(State::CpuInstr { started: true });
+            false
+        }
+        State::CpuInstr { started: true } => finish(emu),
+        State::CpuOver { return_addr, sp } => {
+            if cpu_pc(emu) == return_addr && emu.cpu.regs.sp >= sp {
+                finish(emu)
+            } else {
+                false
+            }
+        }
+        State::CpuOut { sp } => {
+            let pc = cpu_pc(emu);
+            // RTI, RTS, RTL
+            if matches!(bus::read::<DebugCpuAccess>(emu, pc), 0x40 | 0x60 | 0x6B)
+                && emu.cpu.regs.sp >= sp
+            {
+                // Stop right after the return instruction
+                emu.debugger.step = Some(State::CpuInstr { started: true });
+            }
+            false
+        }
+        State::SpcInstr | State::ToPosition { .. } => check_events(emu),
+    }
+}
+
+/// Checks steps that can finish while the 65816 isn't executing instructions (i.e. while it's
+/// halted, waiting for an interrupt or running a DMA transfer).
+pub(crate) fn check_events(emu: &mut Emu) -> bool {
+    match emu.debugger.step {
+        Some(State::SpcInstr) => {
+            if core::mem::take(&mut emu.apu.spc700.debug_instr_stepped) {
+                finish(emu)
+            } else {
+                false
+            }
+        }
+        Some(State::ToPosition { target, last }) => check_position(emu, target, last),
+        _ => false,
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    apu::{spc700::disasm::disassemble_single_without_symbols as disassemble_spc_instr, Apu},
+    cpu::disasm::disassemble_single_without_symbols as disassemble_cpu_instr,
+    emu::Emu,
+};
+use std::{
+    io::{self, Write},
+    ops::RangeInclusive,
+};
+
+/// A trace log destination, writing one line per executed instruction whose address lies in one
+/// of the specified PC ranges (or for every instruction, if no ranges are specified).
+pub struct Log {
+    writer: Box<dyn Write + Send>,
+    pc_ranges: Vec<RangeInclusive<u32>>,
+    error: Option<io::Error>,
+}
+
+impl Log {
+    pub fn new(writer: Box<dyn Write + Send>, pc_ranges: Vec<RangeInclusive<u32>>) -> Self {
+        Log {
+            writer,
+            pc_ranges,
+            error: None,
+        }
+    }
+
+    #[inline]
+    pub fn pc_ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.pc_ranges
+    }
+
+    /// Returns the error that caused logging to stop, if any.
+    #[inline]
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn should_log(&self, pc: u32) -> bool {
+        self.error.is_none()
+            && (self.pc_ranges.is_empty() || self.pc_ranges.iter().any(|range| range.contains(&pc)))
+    }
+
+    fn write_line(&mut self, args: core::fmt::Arguments) {
+        if let Err(err) = self.writer.write_fmt(args) {
+            self.error = Some(err);
+        }
+    }
+}
+
+fn write_flags(line: &mut String, value: u8, names: &[u8; 8]) {
+    for (i, &name) in names.iter().enumerate() {
+        line.push(if value & (0x80 >> i) != 0 {
+            name.to_ascii_uppercase()
+        } else {
+            name
+        } as char);
+    }
+}
+
+/// Logs the 65816 instruction at the current PC in a bsnes-compatible format.
+#[cold]
+pub(crate) fn log_cpu_instr(emu: &mut Emu) {
+    let pc = emu.cpu.regs.code_bank_base() | emu.cpu.regs.pc as u32;
+    let mut log = match emu.debugger.cpu_trace_log.take() {
+        Some(log) => log,
+        None => return,
+    };
+    if log.should_log(pc) {
+        let instr = disassemble_cpu_instr(emu, pc);
+        let regs = &emu.cpu.regs;
+        let mut flags = String::with_capacity(10);
+        // The flag names are kept the same in emulation mode so columns line up when diffing logs
+        write_flags(&mut flags, regs.psw().0, b"nvmxdizc");
+        flags.push(' ');
+        flags.push(if regs.emulation_mode() { 'E' } else { 'e' });
+        log.write_line(format_args!(
+            "{:06x}  {:<22} A:{:04x} X:{:04x} Y:{:04x} S:{:04x} D:{:04x} B:{:02x} {} V:{:3} H:{:4}\n",
+            pc,
+            instr.opcode.to_ascii_lowercase(),
+            regs.a,
+            regs.x,
+            regs.y,
+            regs.sp,
+            regs.direct_page_offset,
+            regs.data_bank(),
+            flags,
+            emu.ppu.counters.v_counter(),
+            emu.ppu.counters.h_dot(emu.schedule.cur_time),
+        ));
+    }
+    emu.debugger.cpu_trace_log = Some(log);
+}
+
+/// Logs the SPC700 instruction at the current PC in a bsnes-compatible format.
+#[cold]
+pub(crate) fn log_spc_instr(apu: &mut Apu) {
+    let pc = apu.spc700.regs.pc;
+    let mut log = match apu.spc700.trace_log.take() {
+        Some(log) => log,
+        None => return,
+    };
+    if log.should_log(pc as u32) {
+        let instr = disassemble_spc_instr(apu, pc);
+        let regs = &apu.spc700.regs;
+        let mut flags = String::with_capacity(8);
+        write_flags(&mut flags, regs.psw().0, b"nvpbhizc");
+        log.write_line(format_args!(
+            "..{:04x}  {:<22} A:{:02x} X:{:02x} Y:{:02x} SP:01{:02x} YA:{:04x} {}\n",
+            pc,
+            instr.opcode.to_ascii_lowercase(),
+            regs.a,
+            regs.x,
+            regs.y,
+            regs.sp,
+            regs.ya(),
+            flags,
+        ));
+    }
+    apu.spc700.trace_log = Some(log);
+}
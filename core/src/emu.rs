@@ -1,6 +1,12 @@
+#[cfg(feature = "debugger")]
+use crate::debugger::{
+    events::{self, EventKind},
+    step, Debugger, StopReason,
+};
 use crate::{
     apu::{dsp, Apu},
     cart::Cart,
+    cheats::{self, Cheats},
     controllers::Controllers,
     cpu::Cpu,
     ppu::Ppu,
@@ -16,6 +22,16 @@ pub struct Emu {
     pub ppu: Ppu,
     pub cart: Cart,
     pub controllers: Controllers,
+    pub cheats: Cheats,
+    #[cfg(feature = "debugger")]
+    pub debugger: Debugger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    FrameFinished,
+    #[cfg(feature = "debugger")]
+    Stopped(StopReason),
 }
 
 impl Emu {
@@ -44,6 +60,9 @@ impl Emu {
             ppu: Ppu::new(model, &mut schedule),
             cart,
             controllers: Controllers::new(&mut schedule),
+            cheats: Cheats::new(),
+            #[cfg(feature = "debugger")]
+            debugger: Debugger::new(),
             schedule,
         };
         emu.soft_reset();
@@ -56,17 +75,26 @@ impl Emu {
         Cpu::soft_reset(self);
     }
 
-    pub fn run_frame(&mut self) {
+    /// Runs the emulator until the end of the current frame, or until the debugger requests to
+    /// stop; in the latter case, calling this again will resume emulation from the same point.
+    pub fn run_frame(&mut self) -> RunOutcome {
+        #[cfg(feature = "debugger")]
+        self.debugger.resume();
         while !self.ppu.frame_finished {
             Cpu::run_until_next_event(self);
             self.schedule.last_poll_time = self.schedule.cur_time;
             while let Some((event, time)) = self.schedule.pop_pending_event() {
                 match event {
                     Event::Ppu(event) => Ppu::handle_event(self, event, time),
-                    Event::HvIrq => self
-                        .ppu
-                        .counters
-                        .handle_hv_irq_triggered(&mut self.cpu.irqs, &mut self.schedule),
+                    Event::HvIrq => {
+                        self.ppu
+                            .counters
+                            .handle_hv_irq_triggered(&mut self.cpu.irqs, &mut self.schedule);
+                        #[cfg(feature = "debugger")]
+                        if self.debugger.events.is_some() {
+                            events::log(self, EventKind::Irq);
+                        }
+                    }
                     Event::Controllers(event) => {
                         self.controllers
                             .handle_event(event, time, &mut self.schedule)
@@ -74,7 +102,30 @@ impl Emu {
                     Event::UpdateApu => self.apu.handle_update(time, &mut self.schedule),
                 }
             }
+            #[cfg(feature = "debugger")]
+            step::check_events(self);
+            #[cfg(feature = "debugger")]
+            if let Some(reason) = self.debugger.stop_reason() {
+                return RunOutcome::Stopped(reason);
+            }
         }
         self.ppu.frame_finished = false;
+        #[cfg(feature = "debugger")]
+        if let Some(log) = &mut self.debugger.events {
+            log.finish_frame();
+        }
+        Cheats::apply_ram_writes(self);
+        RunOutcome::FrameFinished
+    }
+
+    /// Replaces all active cheats with `codes`; codes targeting cart ROM become read
+    /// substitutions, while all others are applied as RAM writes at the end of every frame.
+    pub fn set_cheats(&mut self, codes: &[cheats::Code]) {
+        let (rom_patches, ram_writes) = codes
+            .iter()
+            .copied()
+            .partition::<Vec<_>, _>(|code| self.cart.rom_offset(code.addr).is_some());
+        self.cart.set_rom_patches(&rom_patches);
+        self.cheats.set_ram_writes(ram_writes);
     }
 }
@@ -6,11 +6,16 @@ use serde::{Deserialize, Serialize};
 pub extern crate emu_utils as utils;
 
 pub mod cart;
+pub mod cheats;
 pub mod controllers;
 pub mod cpu;
+#[cfg(feature = "debugger")]
+pub mod debugger;
 pub mod emu;
 pub mod ppu;
 pub mod schedule;
+#[cfg(feature = "disasm")]
+pub mod symbols;
 mod wram;
 pub use wram::Wram;
 pub mod apu;
@@ -1,19 +1,31 @@
 mod bgs_objs_mosaic;
 pub use bgs_objs_mosaic::*;
+#[cfg(feature = "debugger")]
+pub mod capture;
 mod counters;
 pub use counters::*;
+mod hd_mode7;
+pub use hd_mode7::MAX_HD_MODE7_SCALE;
 mod latched_counters;
 pub use latched_counters::*;
+#[cfg(feature = "debugger")]
+pub mod layers;
 mod mode7;
 pub use mode7::*;
 mod oam;
-pub use oam::Oam;
+#[cfg(feature = "debugger")]
+pub use oam::LineObjStats;
+pub use oam::{Oam, Obj};
 pub mod palette;
 mod render;
+pub use render::rgba8_from_rgb5;
+mod threaded;
 pub mod vram;
 mod windows_math;
 pub use windows_math::*;
 
+#[cfg(feature = "debugger")]
+use crate::debugger::events::{self, EventKind};
 use crate::{
     cpu::{bus::AccessType, dma, Irqs},
     emu::Emu,
@@ -26,11 +38,15 @@ use render::ScreenPixel;
 use vram::Vram;
 
 pub const VIEW_WIDTH: usize = 256;
+/// The maximum number of extra columns that can be rendered on each side of the view in
+/// widescreen mode.
+pub const MAX_WIDESCREEN_COLUMNS: usize = 96;
+pub const MAX_VIEW_WIDTH: usize = VIEW_WIDTH + 2 * MAX_WIDESCREEN_COLUMNS;
 
 pub const VIEW_HEIGHT_NTSC: usize = 224;
 pub const VIEW_HEIGHT_PAL: usize = 239;
 
-pub const FB_WIDTH: usize = VIEW_WIDTH << 1;
+pub const FB_WIDTH: usize = MAX_VIEW_WIDTH << 1;
 pub const FB_HEIGHT: usize = VIEW_HEIGHT_PAL << 1;
 
 const DOT_CYCLES: u16 = 4;
@@ -48,6 +64,37 @@ pub struct Framebuffer(pub [u32; FB_WIDTH * FB_HEIGHT]);
 
 unsafe impl Zero for Framebuffer {}
 
+/// A framebuffer with `scale` times the native resolution, whose lines are `FB_WIDTH * scale`
+/// pixels apart.
+pub struct ScaledFramebuffer {
+    scale: usize,
+    pub data: Box<[u32]>,
+}
+
+impl ScaledFramebuffer {
+    pub fn new(scale: usize) -> Self {
+        ScaledFramebuffer {
+            scale,
+            data: vec![0; FB_WIDTH * FB_HEIGHT * scale * scale].into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        FB_WIDTH * self.scale
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        FB_HEIGHT * self.scale
+    }
+}
+
 bitfield_debug! {
     #[derive(Clone, Copy, PartialEq, Eq)]
     pub struct Status77(pub u8) {
@@ -131,17 +178,35 @@ pub struct Ppu {
 
     pub framebuffer: Box<Framebuffer>,
     bg_line_pixels: [Scanline<ScreenPixel, FB_WIDTH>; 4],
-    main_screen_line: Scanline<ScreenPixel, VIEW_WIDTH>,
-    sub_screen_line: Scanline<ScreenPixel, VIEW_WIDTH>,
-    obj_line_pixels: Scanline<ScreenPixel, VIEW_WIDTH>,
-    layer_window_masks: [[Scanline<bool, VIEW_WIDTH>; 2]; 6],
+    main_screen_line: Scanline<ScreenPixel, MAX_VIEW_WIDTH>,
+    sub_screen_line: Scanline<ScreenPixel, MAX_VIEW_WIDTH>,
+    obj_line_pixels: Scanline<ScreenPixel, MAX_VIEW_WIDTH>,
+    layer_window_masks: [[Scanline<bool, MAX_VIEW_WIDTH>; 2]; 6],
     obj_tiles_in_time: u8,
+    #[cfg(feature = "debugger")]
+    pub capture_target: Option<capture::CaptureTarget>,
+    #[cfg(feature = "debugger")]
+    pub capture: Option<Box<capture::LineCapture>>,
+    #[cfg(feature = "debugger")]
+    pub layer_overrides: layers::LayerOverrides,
+    #[cfg(feature = "debugger")]
+    pub layer_capture: Option<Box<layers::LayerCapture>>,
+    /// The OBJ limit stats for each line of the frame, indexed by screen line.
+    #[cfg(feature = "debugger")]
+    pub line_obj_stats: Box<[LineObjStats; VIEW_HEIGHT_PAL]>,
 
     fb_height: usize,
     view_height: usize,
+    widescreen_columns: (u8, u8),
     prev_line_fb_x_shift: bool,
     drawing_fb_x_shift: bool,
     fb_x_shift: bool,
+    dot_accurate: bool,
+    unlimited_objs: bool,
+    dot_render: Option<(u16, u16)>,
+    dot_render_backup: Scanline<u32, FB_WIDTH>,
+    threaded_renderer: Option<threaded::ThreadedRenderer>,
+    hd_mode7: Option<Box<hd_mode7::HdMode7>>,
 
     ppu1_mdr: u8,
     ppu2_mdr: u8,
@@ -213,17 +278,34 @@ impl Ppu {
 
             framebuffer: zeroed_box(),
             bg_line_pixels: [Scanline([ScreenPixel(0); FB_WIDTH]); 4],
-            main_screen_line: Scanline([ScreenPixel(0); VIEW_WIDTH]),
-            sub_screen_line: Scanline([ScreenPixel(0); VIEW_WIDTH]),
-            obj_line_pixels: Scanline([ScreenPixel(0); VIEW_WIDTH]),
-            layer_window_masks: [[Scanline([false; VIEW_WIDTH]); 2]; 6],
+            main_screen_line: Scanline([ScreenPixel(0); MAX_VIEW_WIDTH]),
+            sub_screen_line: Scanline([ScreenPixel(0); MAX_VIEW_WIDTH]),
+            obj_line_pixels: Scanline([ScreenPixel(0); MAX_VIEW_WIDTH]),
+            layer_window_masks: [[Scanline([false; MAX_VIEW_WIDTH]); 2]; 6],
             obj_tiles_in_time: 0,
+            #[cfg(feature = "debugger")]
+            capture_target: None,
+            #[cfg(feature = "debugger")]
+            capture: None,
+            #[cfg(feature = "debugger")]
+            layer_overrides: layers::LayerOverrides::default(),
+            #[cfg(feature = "debugger")]
+            layer_capture: None,
+            #[cfg(feature = "debugger")]
+            line_obj_stats: Box::new([LineObjStats::default(); VIEW_HEIGHT_PAL]),
 
             fb_height: view_height,
             view_height,
+            widescreen_columns: (0, 0),
             prev_line_fb_x_shift: false,
             drawing_fb_x_shift: false,
             fb_x_shift: false,
+            dot_accurate: false,
+            unlimited_objs: false,
+            dot_render: None,
+            dot_render_backup: Scanline([0; FB_WIDTH]),
+            threaded_renderer: None,
+            hd_mode7: None,
 
             ppu1_mdr: 0,
             ppu2_mdr: 0,
@@ -312,7 +394,16 @@ impl Ppu {
                     } else {
                         emu.ppu.counters.v_counter() - 1
                     };
-                    emu.ppu.render_scanline(line);
+                    if emu.ppu.dot_accurate {
+                        emu.ppu.finish_threaded_lines();
+                        emu.ppu.start_scanline();
+                        emu.ppu.dot_render = Some((line, 0));
+                    } else if emu.ppu.threaded_renderer.is_some() {
+                        emu.ppu.start_scanline();
+                        emu.ppu.queue_scanline(line);
+                    } else {
+                        emu.ppu.render_scanline(line);
+                    }
                 }
                 emu.schedule
                     .set_event(event_slots::PPU, schedule::Event::Ppu(Event::StartHBlank));
@@ -323,6 +414,10 @@ impl Ppu {
             // H=274
             Event::StartHBlank => {
                 emu.ppu.hv_status.set_hblank(true);
+                if emu.ppu.dot_render.is_some() {
+                    emu.ppu.render_scanline_until(VIEW_WIDTH as u16);
+                    emu.ppu.dot_render = None;
+                }
                 if emu.ppu.counters.v_counter() < emu.ppu.counters.v_display_end() {
                     emu.schedule.set_event(
                         event_slots::PPU_OTHER,
@@ -383,6 +478,8 @@ impl Ppu {
                     &mut emu.schedule,
                 );
                 if new_v_counter == emu.ppu.counters.v_display_end() {
+                    emu.ppu.finish_threaded_lines();
+                    emu.ppu.finish_hd_mode7_frame();
                     emu.ppu.hv_status.set_vblank(true);
                     emu.ppu.frame_finished = true;
                     emu.schedule.set_event(
@@ -420,6 +517,10 @@ impl Ppu {
                 emu.ppu.nmi_flag.set_nmi_triggered(true);
                 if emu.ppu.vblank_nmi_enabled {
                     emu.cpu.irqs.request_nmi(&mut emu.schedule);
+                    #[cfg(feature = "debugger")]
+                    if emu.debugger.events.is_some() {
+                        events::log(emu, EventKind::Nmi);
+                    }
                 }
                 emu.schedule.set_event(
                     event_slots::PPU_OTHER,
@@ -440,7 +541,33 @@ impl Ppu {
 
     #[inline]
     pub fn fb_width(&self) -> usize {
-        VIEW_WIDTH << self.fb_x_shift as u8
+        self.view_width() << self.fb_x_shift as u8
+    }
+
+    /// Returns the number of pixels per line, including widescreen columns, before hi-res
+    /// doubling.
+    #[inline]
+    pub fn view_width(&self) -> usize {
+        VIEW_WIDTH + self.widescreen_columns.0 as usize + self.widescreen_columns.1 as usize
+    }
+
+    #[inline]
+    pub fn widescreen_columns(&self) -> (u8, u8) {
+        self.widescreen_columns
+    }
+
+    /// Sets the number of extra columns rendered to the left and right of the normal 256-pixel
+    /// view, with BGs continuing past the edges and off-screen OBJs being shown there. Sprite
+    /// limits and the STAT77 flags only take the normal view into account, as on hardware.
+    ///
+    /// Framebuffer lines start with the left columns, so this should only be changed between
+    /// frames.
+    pub fn set_widescreen_columns(&mut self, left: u8, right: u8) {
+        assert!(
+            left as usize <= MAX_WIDESCREEN_COLUMNS && right as usize <= MAX_WIDESCREEN_COLUMNS
+        );
+        self.finish_threaded_lines();
+        self.widescreen_columns = (left, right);
     }
 
     #[inline]
@@ -453,6 +580,51 @@ impl Ppu {
         self.view_height
     }
 
+    #[inline]
+    pub fn dot_accurate(&self) -> bool {
+        self.dot_accurate
+    }
+
+    /// Selects between rendering whole scanlines at the start of HDraw (the default) and catching
+    /// up to the current dot whenever a PPU register is written during HDraw, which is slower but
+    /// shows mid-scanline effects.
+    pub fn set_dot_accurate(&mut self, value: bool) {
+        self.dot_accurate = value;
+    }
+
+    #[inline]
+    pub fn unlimited_objs(&self) -> bool {
+        self.unlimited_objs
+    }
+
+    /// Draws every OBJ on each line instead of stopping at 32 OBJs and 34 OBJ tiles per line,
+    /// reducing flicker in games that cycle through OBJs to work around the limits. The range
+    /// and time over flags are still set as if the limits applied.
+    pub fn set_unlimited_objs(&mut self, value: bool) {
+        self.finish_threaded_lines();
+        self.unlimited_objs = value;
+    }
+
+    /// Renders the current line up to the pixel being output at `time`, if it's being drawn in
+    /// dot-accurate mode; needs to be called before any PPU register write.
+    pub(crate) fn catch_up_dot_render(&mut self, time: Timestamp) {
+        if self.dot_render.is_none() {
+            return;
+        }
+        // The first visible pixel is output around H=22
+        let x = self
+            .counters
+            .h_dot(time)
+            .saturating_sub(22)
+            .min(VIEW_WIDTH as u16);
+        self.render_scanline_until(x);
+    }
+
+    #[inline]
+    pub fn latched_counters(&self) -> &LatchedCounters {
+        &self.latched_counters
+    }
+
     #[inline]
     pub fn ppu1_mdr(&self) -> u8 {
         self.ppu1_mdr
@@ -475,6 +647,8 @@ impl Ppu {
 
     #[inline]
     pub fn read_status77<A: AccessType>(&mut self) -> Status77 {
+        // The time and range over flags are set by lines that might still be rendering
+        self.finish_threaded_lines();
         let result = self.status77.0 | (self.ppu1_mdr & 0x10);
         if A::SIDE_EFFECTS {
             self.ppu1_mdr = result;
@@ -59,6 +59,22 @@ bitfield_debug! {
     }
 }
 
+impl ObjControl {
+    /// Returns the `(width, height)` of small and large OBJs, as shift amounts for 8 pixels.
+    pub fn size_shifts(self) -> [(u8, u8); 2] {
+        [
+            [(0, 0), (1, 1)],
+            [(0, 0), (2, 2)],
+            [(0, 0), (3, 3)],
+            [(1, 1), (2, 2)],
+            [(1, 1), (3, 3)],
+            [(2, 2), (3, 3)],
+            [(1, 2), (2, 3)],
+            [(1, 2), (2, 2)],
+        ][self.size() as usize]
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Bg {
     screen_control: BgScreenControl,
@@ -0,0 +1,168 @@
+use super::{render::ScreenPixel, Ppu, VIEW_WIDTH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureTarget {
+    /// Capture the given screen line (`0` being the first visible line).
+    Line(u16),
+    /// Capture every rendered line, keeping only the most recent one.
+    Latest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Bg1,
+    Bg2,
+    Bg3,
+    Bg4,
+    Obj,
+    Backdrop,
+}
+
+impl Layer {
+    fn from_screen_pixel(pixel: ScreenPixel) -> Self {
+        if pixel.backdrop() {
+            return Layer::Backdrop;
+        }
+        match pixel.color_math_mask() & 0xF {
+            0 => Layer::Obj,
+            mask => {
+                [Layer::Bg1, Layer::Bg2, Layer::Bg3, Layer::Bg4][mask.trailing_zeros() as usize]
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayerPixel {
+    pub color: u16,
+    pub priority: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapturedPixel {
+    pub color: u16,
+    pub layer: Layer,
+}
+
+/// A copy of the intermediate buffers the PPU used to render a single scanline, covering only the
+/// normal 256-pixel view if widescreen columns are enabled.
+#[derive(Clone)]
+pub struct LineCapture {
+    pub line: u16,
+    pub bg_mode: u8,
+    /// Whether BG pixels were rendered at 512 pixels per line; if not, only the first 256 entries
+    /// of `bg_pixels` are valid.
+    pub hi_res: bool,
+    /// Opaque pixels of each BG, before windows and priorities are applied.
+    pub bg_pixels: [[Option<LayerPixel>; VIEW_WIDTH << 1]; 4],
+    /// Opaque OBJ pixels, before windows and priorities are applied; `None` if OBJs weren't
+    /// rendered on this line.
+    pub obj_pixels: Option<[Option<LayerPixel>; VIEW_WIDTH]>,
+    /// For BG1-4 and OBJ, whether the layer is visible (i.e. not masked by windows) on the main
+    /// and sub screens respectively.
+    pub layer_window_masks: [[[bool; VIEW_WIDTH]; 2]; 5],
+    /// Whether the color math window allows color math for each pixel.
+    pub color_math_window: [bool; VIEW_WIDTH],
+    /// Whether each main screen pixel was forced to black by the color math window.
+    pub forced_black: [bool; VIEW_WIDTH],
+    /// Whether color math was actually applied to each pixel.
+    pub color_math_applied: [bool; VIEW_WIDTH],
+    pub main_screen: [CapturedPixel; VIEW_WIDTH],
+    pub sub_screen: [CapturedPixel; VIEW_WIDTH],
+}
+
+impl Ppu {
+    pub(super) fn capture_scanline(&mut self, objs_rendered: bool) {
+        let line = self.counters.v_counter().wrapping_sub(1);
+        match self.capture_target {
+            Some(CaptureTarget::Line(target_line)) if target_line == line => {}
+            Some(CaptureTarget::Latest) => {}
+            _ => return,
+        }
+
+        let color_math_enabled = !self.color_math_disabled();
+        let capture = self.capture.get_or_insert_with(|| {
+            Box::new(LineCapture {
+                line: 0,
+                bg_mode: 0,
+                hi_res: false,
+                bg_pixels: [[None; VIEW_WIDTH << 1]; 4],
+                obj_pixels: None,
+                layer_window_masks: [[[false; VIEW_WIDTH]; 2]; 5],
+                color_math_window: [false; VIEW_WIDTH],
+                forced_black: [false; VIEW_WIDTH],
+                color_math_applied: [false; VIEW_WIDTH],
+                main_screen: [CapturedPixel {
+                    color: 0,
+                    layer: Layer::Backdrop,
+                }; VIEW_WIDTH],
+                sub_screen: [CapturedPixel {
+                    color: 0,
+                    layer: Layer::Backdrop,
+                }; VIEW_WIDTH],
+            })
+        });
+
+        let left_columns = self.widescreen_columns.0 as usize;
+        capture.line = line;
+        capture.bg_mode = self.bg_mode.get();
+        capture.hi_res = self.fb_x_shift;
+
+        for (dst, src) in capture.bg_pixels.iter_mut().zip(&self.bg_line_pixels) {
+            for (dst, src) in dst
+                .iter_mut()
+                .zip(&src.0[left_columns << self.fb_x_shift as u8..])
+            {
+                *dst = (src.bg_priority() & 2 != 0).then(|| LayerPixel {
+                    color: src.rgb(),
+                    priority: src.bg_priority() & 1,
+                });
+            }
+        }
+
+        capture.obj_pixels = objs_rendered.then(|| {
+            let mut pixels = [None; VIEW_WIDTH];
+            for (dst, src) in pixels
+                .iter_mut()
+                .zip(&self.obj_line_pixels.0[left_columns..])
+            {
+                *dst = (src.obj_priority() & 4 != 0).then(|| LayerPixel {
+                    color: src.rgb(),
+                    priority: src.obj_priority() & 3,
+                });
+            }
+            pixels
+        });
+
+        for (dst, src) in capture
+            .layer_window_masks
+            .iter_mut()
+            .zip(&self.layer_window_masks)
+        {
+            dst[0].copy_from_slice(&src[0].0[left_columns..left_columns + VIEW_WIDTH]);
+            dst[1].copy_from_slice(&src[1].0[left_columns..left_columns + VIEW_WIDTH]);
+        }
+
+        capture.color_math_window.copy_from_slice(
+            &self.layer_window_masks[5][0].0[left_columns..left_columns + VIEW_WIDTH],
+        );
+        for (i, (&main, &sub)) in self.main_screen_line.0[left_columns..left_columns + VIEW_WIDTH]
+            .iter()
+            .zip(&self.sub_screen_line.0[left_columns..])
+            .enumerate()
+        {
+            capture.forced_black[i] = main.forced_black();
+            capture.color_math_applied[i] = color_math_enabled
+                && capture.color_math_window[i]
+                && main.color_math_mask() & self.color_math_main_screen_mask != 0;
+            capture.main_screen[i] = CapturedPixel {
+                color: main.rgb(),
+                layer: Layer::from_screen_pixel(main),
+            };
+            capture.sub_screen[i] = CapturedPixel {
+                color: sub.rgb(),
+                layer: Layer::from_screen_pixel(sub),
+            };
+        }
+    }
+}
@@ -69,7 +69,7 @@ impl Counters {
         irqs.set_hv_timer_irq_requested(true, schedule);
     }
 
-    pub(super) fn h_dot(&self, time: Timestamp) -> u16 {
+    pub fn h_dot(&self, time: Timestamp) -> u16 {
         let h_counter_cycles = (time - self.v_counter_last_change_time()) as u16;
         if h_counter_cycles > self.h_end_cycles() {
             // Might have run ahead of the scheduler, hope it's just by a few cycles and use the
@@ -0,0 +1,296 @@
+use super::{
+    render::{
+        blend_pixels, direct_color_from_index, mode7_color_index, rgba8_from_rgb5, ScreenPixel,
+    },
+    vram::Vram,
+    Mode7, Ppu, ScaledFramebuffer, FB_WIDTH, MAX_VIEW_WIDTH, VIEW_HEIGHT_PAL,
+};
+
+pub const MAX_HD_MODE7_SCALE: usize = 8;
+
+/// The state needed to redraw a Mode 7 line at a higher resolution once the frame is done.
+#[derive(Clone)]
+struct HdLine {
+    recorded: bool,
+    fb_line: u16,
+    screen_y: u16,
+    left_columns: u8,
+    view_width: u16,
+    mode7: Mode7,
+    direct_color: bool,
+    main_screen: [ScreenPixel; MAX_VIEW_WIDTH],
+    sub_screen: [ScreenPixel; MAX_VIEW_WIDTH],
+    color_math_window: [bool; MAX_VIEW_WIDTH],
+    color_math_enabled: bool,
+    color_math_main_screen_mask: u8,
+    color_math_subtract: bool,
+    master_brightness: u8,
+    palette: [u16; 0x100],
+}
+
+pub(super) struct HdMode7 {
+    scale: usize,
+    perspective_correction: bool,
+    framebuffer: ScaledFramebuffer,
+    /// Whether any line of the last frame was redrawn at the higher resolution.
+    frame_has_hd_lines: bool,
+    lines: Box<[HdLine]>,
+}
+
+impl HdMode7 {
+    fn new(scale: usize, perspective_correction: bool) -> Self {
+        HdMode7 {
+            scale,
+            perspective_correction,
+            framebuffer: ScaledFramebuffer::new(scale),
+            frame_has_hd_lines: false,
+            lines: vec![
+                HdLine {
+                    recorded: false,
+                    fb_line: 0,
+                    screen_y: 0,
+                    left_columns: 0,
+                    view_width: 0,
+                    mode7: Mode7::new(),
+                    direct_color: false,
+                    main_screen: [ScreenPixel(0); MAX_VIEW_WIDTH],
+                    sub_screen: [ScreenPixel(0); MAX_VIEW_WIDTH],
+                    color_math_window: [false; MAX_VIEW_WIDTH],
+                    color_math_enabled: false,
+                    color_math_main_screen_mask: 0,
+                    color_math_subtract: false,
+                    master_brightness: 0,
+                    palette: [0; 0x100],
+                };
+                VIEW_HEIGHT_PAL
+            ]
+            .into_boxed_slice(),
+        }
+    }
+}
+
+fn sample_mode7(vram: &Vram, line: &HdLine, map_x: i32, map_y: i32, extbg: bool) -> Option<u16> {
+    let color_index = mode7_color_index(vram, line.mode7.control().screen_over(), map_x, map_y)?;
+    if extbg {
+        (color_index & 0x7F != 0).then(|| line.palette[(color_index & 0x7F) as usize])
+    } else if color_index == 0 {
+        None
+    } else if line.direct_color {
+        Some(direct_color_from_index(color_index))
+    } else {
+        Some(line.palette[color_index as usize])
+    }
+}
+
+/// Replaces the color of `pixel` with the high-resolution Mode 7 sample if it came from the
+/// Mode 7 plane; transparent samples keep the native pixel's color, to avoid holes at the edges of
+/// opaque areas.
+fn replace_mode7_pixel(
+    vram: &Vram,
+    line: &HdLine,
+    pixel: ScreenPixel,
+    map_x: i32,
+    map_y: i32,
+) -> ScreenPixel {
+    if pixel.forced_black() || pixel.backdrop() || pixel.color_math_mask() & 3 == 0 {
+        return pixel;
+    }
+    match sample_mode7(vram, line, map_x, map_y, pixel.bg2()) {
+        Some(color) => pixel.with_rgb(color),
+        None => pixel,
+    }
+}
+
+fn draw_line(
+    framebuffer: &mut ScaledFramebuffer,
+    vram: &Vram,
+    line: &HdLine,
+    next_line: Option<&HdLine>,
+    x_repeat: usize,
+) {
+    let scale = framebuffer.scale;
+    let stride = framebuffer.width();
+    let s = scale as i64;
+    let control = line.mode7.control();
+    let origin = line.mode7.origin().map(i64::from);
+    let center = line.mode7.center.map(i64::from);
+
+    for sub_y in 0..s {
+        // Matrix parameters multiplied by `scale`, interpolated towards the next line's if
+        // perspective correction is enabled
+        let params: [i64; 4] = match next_line {
+            Some(next_line) => core::array::from_fn(|i| {
+                line.mode7.params[i] as i64 * s
+                    + (next_line.mode7.params[i] as i64 - line.mode7.params[i] as i64) * sub_y
+            }),
+            None => line.mode7.params.map(|param| param as i64 * s),
+        };
+        let y = line.screen_y as i64 * s + sub_y;
+        let y = if control.y_flip() { 256 * s - 1 - y } else { y };
+
+        // All map coordinates are multiplied by `scale * scale`
+        let base_x = (origin[0] * params[0] + origin[1] * params[1] + (center[0] << 8) * s) * s
+            + y * params[1];
+        let base_y = (origin[0] * params[2] + origin[1] * params[3] + (center[1] << 8) * s) * s
+            + y * params[3];
+
+        let row_start = (line.fb_line as usize * scale + sub_y as usize) * stride;
+        let row_len = line.view_width as usize * scale * x_repeat;
+        let row = &mut framebuffer.data[row_start..row_start + row_len];
+        for (sub_x, dst) in row.chunks_exact_mut(x_repeat).enumerate() {
+            let x = sub_x / scale;
+            // Relative to the left edge of the normal view
+            let view_sub_x = sub_x as i64 - line.left_columns as i64 * s;
+            let flipped_x = if control.x_flip() {
+                256 * s - 1 - view_sub_x
+            } else {
+                view_sub_x
+            };
+            let map_x = (base_x + flipped_x * params[0]).div_euclid(s * s) as i32;
+            let map_y = (base_y + flipped_x * params[2]).div_euclid(s * s) as i32;
+
+            let main = replace_mode7_pixel(vram, line, line.main_screen[x], map_x, map_y);
+            let color = if line.color_math_enabled
+                && line.color_math_window[x]
+                && main.color_math_mask() & line.color_math_main_screen_mask != 0
+            {
+                let sub = replace_mode7_pixel(vram, line, line.sub_screen[x], map_x, map_y);
+                if line.color_math_subtract {
+                    blend_pixels::<false, false, true>(main, sub)
+                } else {
+                    blend_pixels::<false, false, false>(main, sub)
+                }
+            } else {
+                main.rgb() as u32
+            };
+            dst.fill(rgba8_from_rgb5(color, line.master_brightness as u32));
+        }
+    }
+}
+
+impl Ppu {
+    #[inline]
+    pub fn hd_mode7_scale(&self) -> usize {
+        self.hd_mode7.as_ref().map_or(1, |hd_mode7| hd_mode7.scale)
+    }
+
+    #[inline]
+    pub fn hd_mode7_perspective_correction(&self) -> bool {
+        self.hd_mode7
+            .as_ref()
+            .map_or(false, |hd_mode7| hd_mode7.perspective_correction)
+    }
+
+    /// Renders Mode 7 lines at `scale` times the native resolution into `hd_framebuffer`, or
+    /// disables HD Mode 7 if `scale` is 1. With perspective correction, the matrix parameters
+    /// are interpolated between consecutive lines, smoothing out HDMA-driven perspective effects.
+    pub fn set_hd_mode7(&mut self, scale: usize, perspective_correction: bool) {
+        assert!((1..=MAX_HD_MODE7_SCALE).contains(&scale));
+        if scale == 1 {
+            self.hd_mode7 = None;
+            return;
+        }
+        match &mut self.hd_mode7 {
+            Some(hd_mode7) if hd_mode7.scale == scale => {
+                hd_mode7.perspective_correction = perspective_correction;
+            }
+            _ => self.hd_mode7 = Some(Box::new(HdMode7::new(scale, perspective_correction))),
+        }
+    }
+
+    /// Returns the last frame rendered with HD Mode 7, if it's enabled and the frame had any Mode 7
+    /// lines. Lines that don't use Mode 7 are upscaled from the native framebuffer.
+    #[inline]
+    pub fn hd_framebuffer(&self) -> Option<&ScaledFramebuffer> {
+        self.hd_mode7
+            .as_ref()
+            .filter(|hd_mode7| hd_mode7.frame_has_hd_lines)
+            .map(|hd_mode7| &hd_mode7.framebuffer)
+    }
+
+    pub(super) fn record_hd_mode7_line(&mut self, line: u16) {
+        let screen_y = self.counters.v_counter();
+        // Mosaic and hi-res Mode 7 lines are left at the native resolution
+        let eligible = self.bg_mode.get() == 7
+            && !self.drawing_fb_x_shift
+            && (self.bg_mosaic_mask & 3 == 0 || self.mosaic_size == 1);
+        let color_math_enabled = !self.color_math_disabled();
+        let view_width = self.view_width() as u16;
+        let hd_mode7 = match &mut self.hd_mode7 {
+            Some(hd_mode7) => hd_mode7,
+            None => return,
+        };
+        let hd_line = &mut hd_mode7.lines[screen_y as usize - 1];
+        hd_line.recorded = eligible;
+        if !eligible {
+            return;
+        }
+        hd_line.fb_line = line;
+        hd_line.screen_y = screen_y;
+        hd_line.left_columns = self.widescreen_columns.0;
+        hd_line.view_width = view_width;
+        hd_line.mode7 = self.mode7;
+        hd_line.direct_color = self.color_math_control_a.use_direct_color();
+        hd_line.main_screen = self.main_screen_line.0;
+        hd_line.sub_screen = self.sub_screen_line.0;
+        hd_line.color_math_window = self.layer_window_masks[5][0].0;
+        hd_line.color_math_enabled = color_math_enabled;
+        hd_line.color_math_main_screen_mask = self.color_math_main_screen_mask;
+        hd_line.color_math_subtract = self.color_math_control_b.add_subtract();
+        hd_line.master_brightness = self.master_brightness;
+        hd_line.palette = *self.palette.contents;
+    }
+
+    /// Fills `hd_framebuffer` for the frame that just finished, upscaling the native framebuffer
+    /// and then redrawing the recorded Mode 7 lines; frames without any are left to the native
+    /// framebuffer.
+    pub(super) fn finish_hd_mode7_frame(&mut self) {
+        let fb_width = self.fb_width();
+        let hd_mode7 = match &mut self.hd_mode7 {
+            Some(hd_mode7) => hd_mode7,
+            None => return,
+        };
+        let HdMode7 {
+            scale,
+            perspective_correction,
+            framebuffer,
+            frame_has_hd_lines,
+            lines,
+        } = &mut **hd_mode7;
+        *frame_has_hd_lines = lines.iter().any(|line| line.recorded);
+        if !*frame_has_hd_lines {
+            return;
+        }
+        let scale = *scale;
+        let stride = framebuffer.width();
+
+        for y in 0..self.fb_height {
+            let src = &self.framebuffer.0[y * FB_WIDTH..y * FB_WIDTH + fb_width];
+            let row_start = y * scale * stride;
+            let row = &mut framebuffer.data[row_start..row_start + fb_width * scale];
+            for (dst, &pixel) in row.chunks_exact_mut(scale).zip(src) {
+                dst.fill(pixel);
+            }
+            for sub_y in 1..scale {
+                framebuffer.data.copy_within(
+                    row_start..row_start + fb_width * scale,
+                    row_start + sub_y * stride,
+                );
+            }
+        }
+
+        let x_repeat = 1 << self.fb_x_shift as u8;
+        for i in 0..lines.len() {
+            if !lines[i].recorded {
+                continue;
+            }
+            let next_line = lines
+                .get(i + 1)
+                .filter(|next_line| *perspective_correction && next_line.recorded);
+            draw_line(framebuffer, &self.vram, &lines[i], next_line, x_repeat);
+        }
+        for line in lines.iter_mut() {
+            line.recorded = false;
+        }
+    }
+}
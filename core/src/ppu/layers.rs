@@ -0,0 +1,88 @@
+use super::{
+    render::{rgba8_from_rgb5, ScreenPixel},
+    Ppu, FB_HEIGHT, FB_WIDTH,
+};
+
+/// Debug overrides to hide layers or disable color math, applied on top of the game's own layer
+/// settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayerOverrides {
+    /// Mask of BGs (bit 0 being BG1) that won't be shown on either screen.
+    pub hidden_bgs: u8,
+    /// Mask of OBJ priorities (bit 0 being priority 0) that won't be shown on either screen.
+    pub hidden_obj_priorities: u8,
+    /// Whether to show the main and sub screen backdrops as black.
+    pub hide_backdrop: bool,
+    pub disable_color_math: bool,
+}
+
+/// Per-layer RGBA8 buffers, in the same format as the framebuffer and always `FB_WIDTH` pixels
+/// wide (non-hi-res lines are doubled horizontally); transparent pixels have an alpha of 0.
+pub struct LayerCapture {
+    pub bgs: [Box<[u32]>; 4],
+    pub obj: Box<[u32]>,
+}
+
+impl LayerCapture {
+    pub fn new() -> Self {
+        let new_buffer = || vec![0; FB_WIDTH * FB_HEIGHT].into_boxed_slice();
+        LayerCapture {
+            bgs: [new_buffer(), new_buffer(), new_buffer(), new_buffer()],
+            obj: new_buffer(),
+        }
+    }
+}
+
+impl Default for LayerCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rgba8_from_pixel(pixel: ScreenPixel, opaque: bool) -> u32 {
+    if !opaque {
+        return 0;
+    }
+    rgba8_from_rgb5(pixel.rgb() as u32, 16)
+}
+
+impl Ppu {
+    pub(super) fn capture_layers(&mut self, line: u16, objs_rendered: bool) {
+        let capture = match &mut self.layer_capture {
+            Some(capture) => capture,
+            None => return,
+        };
+        let line_start = line as usize * FB_WIDTH;
+
+        // BG line buffers are left untouched for BGs that weren't drawn on this line
+        let bgs_drawn = (self.enabled_main_screen_layers | self.enabled_sub_screen_layers)
+            & [
+                0xF,
+                7,
+                3,
+                3,
+                3,
+                3,
+                1,
+                1 | (self.display_control_1.extbg_enabled() as u8) << 1,
+            ][self.bg_mode.get() as usize];
+        for (i, (buffer, bg_pixels)) in capture.bgs.iter_mut().zip(&self.bg_line_pixels).enumerate()
+        {
+            let dst = &mut buffer[line_start..line_start + FB_WIDTH];
+            if bgs_drawn & 1 << i == 0 {
+                dst.fill(0);
+                continue;
+            }
+            for (x, dst) in dst.iter_mut().enumerate() {
+                let pixel = bg_pixels.0[if self.fb_x_shift { x } else { x >> 1 }];
+                *dst = rgba8_from_pixel(pixel, pixel.bg_priority() & 2 != 0);
+            }
+        }
+
+        let dst = &mut capture.obj[line_start..line_start + FB_WIDTH];
+        for (x, dst) in dst.iter_mut().enumerate() {
+            let pixel = self.obj_line_pixels.0[x >> 1];
+            *dst = rgba8_from_pixel(pixel, objs_rendered && pixel.obj_priority() & 4 != 0);
+        }
+    }
+}
@@ -10,6 +10,7 @@ bitfield_debug! {
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct Mode7 {
     old: u8,
     control: Mode7Control,
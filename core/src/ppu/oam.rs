@@ -21,8 +21,23 @@ pub struct Obj {
     pub attrs: Attrs,
 }
 
+/// How the OBJs on a line fared against the PPU's per-line limits.
+#[cfg(feature = "debugger")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineObjStats {
+    /// The amount of OBJ tiles that took up time, up to the first one past the limit (unless
+    /// sprite limits are disabled).
+    pub tiles: u8,
+    /// Whether more than 32 OBJs were in range.
+    pub range_over: bool,
+    /// Whether more than 34 tiles were fetched.
+    pub time_over: bool,
+}
+
 pub struct Oam {
     pub contents: Box<[Obj; 0x80]>,
+    /// Whether `contents` was written since the render threads last got a copy of it.
+    pub(super) dirty: bool,
     cur_byte_addr: u16,
     reload_addr: u16,
     write_latch: u8,
@@ -43,6 +58,7 @@ impl Oam {
                     attrs: Attrs(0),
                 }; 0x80],
             ),
+            dirty: true,
             cur_byte_addr: 0,
             reload_addr: 0,
             write_latch: 0,
@@ -97,6 +113,11 @@ impl Oam {
     pub fn next_first_sprite(&self) -> u8 {
         self.next_first_sprite
     }
+
+    #[inline]
+    pub(super) fn set_next_first_sprite(&mut self, value: u8) {
+        self.next_first_sprite = value;
+    }
 }
 
 impl Ppu {
@@ -161,6 +182,7 @@ impl Ppu {
             objs[2].attrs.set_large_size(value & 1 << 5 != 0);
             objs[3].x_coord = (objs[3].x_coord & 0xFF) | (value as u16 >> 6 & 1) << 8;
             objs[3].attrs.set_large_size(value & 1 << 7 != 0);
+            self.oam.dirty = true;
         } else if self.oam.cur_byte_addr & 1 != 0 {
             let i = (self.oam.cur_byte_addr >> 2) as usize & 0x7F;
             let obj = &mut self.oam.contents[i];
@@ -173,6 +195,7 @@ impl Ppu {
                 obj.bg_prio = value >> 4 & 3;
                 obj.pal_number = value >> 1 & 7;
             }
+            self.oam.dirty = true;
         }
         self.oam.cur_byte_addr = (self.oam.cur_byte_addr + 1) & 0x3FF;
         self.update_oam_next_first_sprite();
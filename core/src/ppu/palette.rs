@@ -15,6 +15,8 @@ bitfield_debug! {
 
 pub struct Palette {
     pub contents: Box<[u16; 0x100]>,
+    /// Whether `contents` was written since the render threads last got a copy of it.
+    pub(super) dirty: bool,
     write_latch: u8,
     second_access: bool,
     cur_addr: u8,
@@ -24,6 +26,7 @@ impl Palette {
     pub(crate) fn new() -> Self {
         Palette {
             contents: zeroed_box(),
+            dirty: true,
             write_latch: 0,
             second_access: false,
             cur_addr: 0,
@@ -49,6 +52,14 @@ impl Palette {
     pub fn set_word_addr(&mut self, value: u8) {
         self.cur_addr = value;
     }
+
+    /// Overwrites a CGRAM entry without affecting the current address or write latch, for use by
+    /// debuggers.
+    #[inline]
+    pub fn write_color_debug(&mut self, index: u8, color: u16) {
+        self.contents[index as usize] = color & 0x7FFF;
+        self.dirty = true;
+    }
 }
 
 impl Ppu {
@@ -73,6 +84,7 @@ impl Ppu {
         if self.palette.second_access {
             self.palette.contents[self.palette.cur_addr as usize] =
                 (value as u16 & 0x7F) << 8 | self.palette.write_latch as u16;
+            self.palette.dirty = true;
             self.palette.cur_addr = self.palette.cur_addr.wrapping_add(1);
         } else {
             self.palette.write_latch = value;
@@ -1,4 +1,4 @@
-use super::{oam, BgIndex, Ppu, FB_WIDTH, VIEW_WIDTH};
+use super::{oam, vram::Vram, BgIndex, Ppu, FB_WIDTH, VIEW_WIDTH};
 use crate::utils::bitfield_debug;
 
 bitfield_debug! {
@@ -22,12 +22,57 @@ fn r_g_b_from_rgb5(value: u32) -> (u32, u32, u32) {
     (value & 0x1F, value >> 5 & 0x1F, value >> 10 & 0x1F)
 }
 
-fn direct_color_from_index(value: u8) -> u16 {
+pub(super) fn direct_color_from_index(value: u8) -> u16 {
     (value as u16 & 0xC0) << 7 | (value as u16 & 0x38) << 4 | (value as u16 & 7) << 2
 }
 
+/// Converts a BGR555 color to the framebuffer's RGBA8 format, applying the master brightness (a
+/// brightness of 16 leaves the color unchanged).
 #[inline(always)]
-fn blend_pixels<
+pub fn rgba8_from_rgb5(value: u32, brightness: u32) -> u32 {
+    let (r, g, b) = r_g_b_from_rgb5(value);
+    let r = (r * brightness) >> 4;
+    let g = (g * brightness) >> 4;
+    let b = (b * brightness) >> 4;
+    let shifted = r << 3 | g << 11 | b << 19;
+    0xFF00_0000 | shifted | (shifted >> 5 & 0x070707)
+}
+
+/// Looks up the color index of the Mode 7 plane at the given map coordinates, returning `None`
+/// when the pixel is outside of the plane and the screen over mode makes it transparent.
+#[inline(always)]
+pub(super) fn mode7_color_index(
+    vram: &Vram,
+    screen_over_mode: u8,
+    map_x: i32,
+    map_y: i32,
+) -> Option<u8> {
+    let screen_over = map_x as u32 > 0x80 << 11 || map_y as u32 > 0x80 << 11;
+    let tile_index = ((map_y >> 11) as u16 & 0x7F) << 7 | ((map_x >> 11) as u16 & 0x7F);
+    let mut char_index = vram.contents[(tile_index << 1) as usize];
+    match screen_over_mode {
+        0 | 1 => {}
+        2 => {
+            if screen_over {
+                return None;
+            }
+        }
+        _ => {
+            if screen_over {
+                char_index = 0;
+            }
+        }
+    }
+    Some(
+        vram.contents[((char_index as u16) << 7
+            | (map_y as u16 >> 4 & 0x70)
+            | (map_x as u16 >> 7 & 0xE)
+            | 1) as usize],
+    )
+}
+
+#[inline(always)]
+pub(super) fn blend_pixels<
     const MAYBE_DIV2_RESULT: bool,
     const SUB_SCREEN_BG_OBJ_ENABLED: bool,
     const SUB: bool,
@@ -60,51 +105,171 @@ fn blend_pixels<
 }
 
 impl Ppu {
+    /// Returns the mask of layers that aren't hidden by the debugger's layer overrides.
+    #[inline(always)]
+    fn visible_layers(&self) -> u8 {
+        #[cfg(feature = "debugger")]
+        return !self.layer_overrides.hidden_bgs;
+        #[cfg(not(feature = "debugger"))]
+        0xFF
+    }
+
+    /// Returns the mask of OBJ priorities that aren't hidden by the debugger's layer overrides.
+    #[inline(always)]
+    fn visible_obj_priorities(&self) -> u8 {
+        #[cfg(feature = "debugger")]
+        return !self.layer_overrides.hidden_obj_priorities;
+        #[cfg(not(feature = "debugger"))]
+        0xFF
+    }
+
+    #[inline(always)]
+    fn backdrop_color(&self, color: u16) -> u16 {
+        #[cfg(feature = "debugger")]
+        if self.layer_overrides.hide_backdrop {
+            return 0;
+        }
+        color
+    }
+
+    #[inline(always)]
+    pub(super) fn color_math_disabled(&self) -> bool {
+        #[cfg(feature = "debugger")]
+        if self.layer_overrides.disable_color_math {
+            return true;
+        }
+        self.color_math_control_a.color_math_mode() == 3
+    }
+
+    /// Returns the length of the first mosaic block on the line, in units of `x_shift`-shifted
+    /// pixels; it's cut short so that the following ones line up with the left edge of the normal
+    /// view when widescreen columns are shown.
+    #[inline(always)]
+    fn first_mosaic_block_len(&self, mosaic_size: usize, x_shift: u8) -> usize {
+        match ((self.widescreen_columns.0 as usize) << x_shift) % mosaic_size {
+            0 => mosaic_size,
+            len => len,
+        }
+    }
+
+    /// Returns the X coordinate of an OBJ relative to the left edge of the normal view; OBJs
+    /// positioned past the right edge are placed in the right widescreen columns if they fit
+    /// there, and wrap around to the left otherwise.
+    #[inline(always)]
+    fn obj_screen_x(&self, x_coord: u16) -> i16 {
+        if (x_coord as usize) < VIEW_WIDTH + self.widescreen_columns.1 as usize {
+            x_coord as i16
+        } else {
+            x_coord as i16 - 512
+        }
+    }
+
     pub(super) fn render_scanline(&mut self, line: u16) {
+        self.start_scanline();
+        self.render_scanline_pixels(line);
+    }
+
+    pub(super) fn start_scanline(&mut self) {
+        if self.display_control_0.forced_blank() {
+            return;
+        }
+        if self.mosaic_remaining_lines.0 == 0 {
+            self.mosaic_remaining_lines = (self.mosaic_size, self.mosaic_size);
+        }
+        self.mosaic_remaining_lines.0 -= 1;
+    }
+
+    /// Renders pixels `start_x..end_x` of the line being drawn in dot-accurate mode, using the
+    /// current register state.
+    ///
+    /// The whole line is rendered again and the framebuffer pixels outside of the range are then
+    /// restored; this is slow, but only happens when a PPU register is written during HDraw. If
+    /// the line switches to hi-res partway through, the pixels drawn before the switch are
+    /// stretched along with the rest of the line.
+    pub(super) fn render_scanline_until(&mut self, end_x: u16) {
+        let (line, start_x) = match self.dot_render {
+            Some(dot_render) => dot_render,
+            None => return,
+        };
+        if end_x <= start_x {
+            return;
+        }
+
+        let fb_line_start = line as usize * FB_WIDTH;
+        self.dot_render_backup
+            .0
+            .copy_from_slice(&self.framebuffer.0[fb_line_start..fb_line_start + FB_WIDTH]);
+        self.render_scanline_pixels(line);
+
+        // Widescreen columns are drawn along with the first and last pixels of the normal view
+        let view_width = self.view_width();
+        let left_columns = self.widescreen_columns.0 as usize;
+        let fb_x = |x: u16| {
+            let line_x = match x as usize {
+                0 => 0,
+                x if x >= VIEW_WIDTH => view_width,
+                x => x + left_columns,
+            };
+            line_x << self.fb_x_shift as u8
+        };
+        let start_fb_x = fb_x(start_x);
+        let end_fb_x = fb_x(end_x);
+        let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + FB_WIDTH];
+        fb_line[..start_fb_x].copy_from_slice(&self.dot_render_backup.0[..start_fb_x]);
+        fb_line[end_fb_x..].copy_from_slice(&self.dot_render_backup.0[end_fb_x..]);
+
+        self.dot_render = Some((line, end_x));
+    }
+
+    #[cfg(feature = "debugger")]
+    fn set_line_obj_stats(&mut self, stats: oam::LineObjStats) {
+        let screen_line = self.counters.v_counter().wrapping_sub(1) as usize;
+        if let Some(dst) = self.line_obj_stats.get_mut(screen_line) {
+            *dst = stats;
+        }
+    }
+
+    pub(super) fn render_scanline_pixels(&mut self, line: u16) {
+        #[cfg(feature = "debugger")]
+        self.set_line_obj_stats(oam::LineObjStats::default());
+
         if self.display_control_0.forced_blank() {
             let fb_line_start = line as usize * FB_WIDTH;
-            let fb_line_len = VIEW_WIDTH << self.fb_x_shift as u8;
+            let fb_line_len = self.fb_width();
             self.framebuffer.0[fb_line_start..fb_line_start + fb_line_len].fill(0xFF00_0000);
             return;
         }
 
         self.prepare_window_buffers();
 
+        let main_backdrop_color = self.backdrop_color(self.palette.contents[0]);
         self.main_screen_line.0.fill(
             ScreenPixel(0)
-                .with_rgb(self.palette.contents[0])
+                .with_rgb(main_backdrop_color)
                 .with_backdrop(true),
         );
+        let sub_backdrop_color = self.backdrop_color(self.sub_backdrop_color);
         self.sub_screen_line.0.fill(
             ScreenPixel(0)
-                .with_rgb(self.sub_backdrop_color)
+                .with_rgb(sub_backdrop_color)
                 .with_backdrop(true),
         );
 
-        if self.mosaic_remaining_lines.0 == 0 {
-            self.mosaic_remaining_lines = (self.mosaic_size, self.mosaic_size);
-        }
-        self.mosaic_remaining_lines.0 -= 1;
-
-        if (self.enabled_main_screen_layers | self.enabled_sub_screen_layers) & 1 << 4 != 0
-            && self.counters.v_counter() != 0
-        {
+        let objs_rendered =
+            (self.enabled_main_screen_layers | self.enabled_sub_screen_layers) & 1 << 4 != 0
+                && self.counters.v_counter() != 0;
+        if objs_rendered {
             self.obj_line_pixels.0.fill(ScreenPixel(0));
 
-            let obj_size_shifts: [(u8, u8); 2] = [
-                [(0, 0), (1, 1)],
-                [(0, 0), (2, 2)],
-                [(0, 0), (3, 3)],
-                [(1, 1), (2, 2)],
-                [(1, 1), (3, 3)],
-                [(2, 2), (3, 3)],
-                [(1, 2), (2, 3)],
-                [(1, 2), (2, 2)],
-            ][self.obj_control.size() as usize];
+            let obj_size_shifts = self.obj_control.size_shifts();
             let compare_y = (self.counters.v_counter() - 1) as u8;
 
-            let mut line_objs = [(0, 0, 0, 0, 0, 0, oam::Attrs(0)); 32];
+            let view_start_x = -(self.widescreen_columns.0 as i16);
+            let view_end_x = (VIEW_WIDTH + self.widescreen_columns.1 as usize) as i16;
+
+            let mut line_objs = [(0, 0, 0, 0, 0, 0, oam::Attrs(0), false); 128];
             let mut line_objs_len = 0;
+            let mut in_range_objs = 0;
             let mut i = self.oam.next_first_sprite() as usize;
             let last_sprite = i.wrapping_sub(1) & 0x7F;
 
@@ -116,34 +281,46 @@ impl Ppu {
                 if y_in_obj >= 8 << height_shift {
                     continue;
                 }
-                if (VIEW_WIDTH as u16 + 1..513 - (8 << width_shift)).contains(&obj.x_coord) {
+                // As on hardware, only OBJs in range of the normal view count towards the 32 OBJ
+                // limit
+                let in_range =
+                    !(VIEW_WIDTH as u16 + 1..513 - (8 << width_shift)).contains(&obj.x_coord);
+                let x = self.obj_screen_x(obj.x_coord);
+                if !in_range && (x + (8 << width_shift) <= view_start_x || x >= view_end_x) {
                     continue;
                 }
-                if line_objs_len >= 32 {
-                    self.status77.set_range_over(true);
-                    break;
-                } else {
-                    line_objs[line_objs_len] = (
-                        obj.x_coord,
-                        width_shift,
-                        if obj.attrs.y_flip() {
-                            // NOTE: `width_shift` isn't a typo, non-square OBJs aren't flipped as
-                            // expected
-                            y_in_obj ^ ((8 << width_shift) - 1)
-                        } else {
-                            y_in_obj
-                        },
-                        obj.tile_number,
-                        obj.pal_number,
-                        obj.bg_prio,
-                        obj.attrs,
-                    );
-                    line_objs_len += 1;
+                if in_range {
+                    in_range_objs += 1;
+                    if in_range_objs > 32 {
+                        self.status77.set_range_over(true);
+                        if !self.unlimited_objs {
+                            break;
+                        }
+                    }
                 }
+                // Only the first 32 OBJs in range are fetched by hardware and take up time
+                let takes_time = in_range && in_range_objs <= 32;
+                line_objs[line_objs_len] = (
+                    x,
+                    width_shift,
+                    if obj.attrs.y_flip() {
+                        // NOTE: `width_shift` isn't a typo, non-square OBJs aren't flipped as
+                        // expected
+                        y_in_obj ^ ((8 << width_shift) - 1)
+                    } else {
+                        y_in_obj
+                    },
+                    obj.tile_number,
+                    obj.pal_number,
+                    obj.bg_prio,
+                    obj.attrs,
+                    takes_time,
+                );
+                line_objs_len += 1;
             }
 
             self.obj_tiles_in_time = 0;
-            for &(x, width_shift, y_in_obj, tile_number, pal_number, bg_prio, attrs) in
+            for &(x, width_shift, y_in_obj, tile_number, pal_number, bg_prio, attrs, takes_time) in
                 line_objs[..line_objs_len].iter().rev()
             {
                 self.draw_obj(
@@ -154,12 +331,22 @@ impl Ppu {
                     pal_number,
                     bg_prio,
                     attrs,
+                    takes_time,
                 );
                 if self.obj_tiles_in_time > 34 {
                     self.status77.set_time_over(true);
-                    break;
+                    if !self.unlimited_objs {
+                        break;
+                    }
                 }
             }
+
+            #[cfg(feature = "debugger")]
+            self.set_line_obj_stats(oam::LineObjStats {
+                tiles: self.obj_tiles_in_time,
+                range_over: in_range_objs > 32,
+                time_over: self.obj_tiles_in_time > 34,
+            });
         }
 
         [
@@ -191,10 +378,21 @@ impl Ppu {
             _ => {}
         }
 
+        #[cfg(feature = "debugger")]
+        {
+            self.capture_scanline(objs_rendered);
+            self.capture_layers(line, objs_rendered);
+        }
+
+        if self.hd_mode7.is_some() {
+            self.record_hd_mode7_line(line);
+        }
+
         let fb_line_start = line as usize * FB_WIDTH;
-        let fb_line_drawing_len = VIEW_WIDTH << self.drawing_fb_x_shift as u8;
+        let fb_line_drawing_len = self.view_width() << self.drawing_fb_x_shift as u8;
+        let color_math_disabled = self.color_math_disabled();
         let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + fb_line_drawing_len];
-        if self.color_math_control_a.color_math_mode() == 3 {
+        if color_math_disabled {
             if self.drawing_fb_x_shift {
                 for (i, fb_pixels) in fb_line.array_chunks_mut::<2>().enumerate() {
                     fb_pixels[0] = self.sub_screen_line.0[i].rgb() as u32;
@@ -290,35 +488,39 @@ impl Ppu {
 
         let brightness = self.master_brightness as u32;
         for fb_pixel in fb_line {
-            let (r, g, b) = r_g_b_from_rgb5(*fb_pixel);
-            let r = (r * brightness) >> 4;
-            let g = (g * brightness) >> 4;
-            let b = (b * brightness) >> 4;
-            let shifted = r << 3 | g << 11 | b << 19;
-            *fb_pixel = 0xFF00_0000 | shifted | (shifted >> 5 & 0x070707)
+            *fb_pixel = rgba8_from_rgb5(*fb_pixel, brightness);
         }
 
+        self.widen_prev_lines(line);
+
+        if self.fb_x_shift && !self.drawing_fb_x_shift {
+            let fb_line_len = self.fb_width();
+            let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + fb_line_len];
+            for i in (0..fb_line_len).rev() {
+                fb_line[i] = fb_line[i >> 1];
+            }
+        }
+    }
+
+    /// Doubles the lines before `line` horizontally if the framebuffer just switched to 512
+    /// pixels per line.
+    pub(super) fn widen_prev_lines(&mut self, line: u16) {
         if self.fb_x_shift && !self.prev_line_fb_x_shift {
+            let fb_line_len = self.fb_width();
             for fb_line_start in (0..(line as usize) * FB_WIDTH).step_by(FB_WIDTH) {
-                let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + FB_WIDTH];
-                for i in (0..FB_WIDTH).rev() {
+                let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + fb_line_len];
+                for i in (0..fb_line_len).rev() {
                     fb_line[i] = fb_line[i >> 1];
                 }
             }
         }
 
         self.prev_line_fb_x_shift = self.fb_x_shift;
-
-        if self.fb_x_shift && !self.drawing_fb_x_shift {
-            let fb_line = &mut self.framebuffer.0[fb_line_start..fb_line_start + FB_WIDTH];
-            for i in (0..FB_WIDTH).rev() {
-                fb_line[i] = fb_line[i >> 1];
-            }
-        }
     }
 
     fn render_for_bg_mode<const BG_MODE: u8>(&mut self) {
         let layers_enabled = self.enabled_main_screen_layers | self.enabled_sub_screen_layers;
+        let view_width = self.view_width();
 
         static BG_2BPP_POINTERS: [fn(&mut Ppu, BgIndex); 2] =
             [Ppu::draw_bg_text::<2, 0, 0>, Ppu::draw_bg_text::<2, 1, 1>];
@@ -379,6 +581,9 @@ impl Ppu {
             }
         }
 
+        let visible_layers = self.visible_layers();
+        let visible_obj_priorities = self.visible_obj_priorities();
+
         macro_rules! render_layers {
             (
                 $main_screen_layers: ident,
@@ -388,14 +593,14 @@ impl Ppu {
             ) => {
                 #[allow(clippy::unnecessary_operation)]
                 {
-                    let $line = &mut self.main_screen_line.0;
-                    let $layers = self.enabled_main_screen_layers;
+                    let $line = &mut self.main_screen_line.0[..view_width];
+                    let $layers = self.enabled_main_screen_layers & visible_layers;
                     let $screen_i = 0;
                     let $line_pixels_bit0 = self.fb_x_shift as usize;
                     $render;
                     if self.color_math_control_a.sub_screen_bg_obj_enabled() || self.fb_x_shift {
-                        let $line = &mut self.sub_screen_line.0;
-                        let $layers = self.enabled_sub_screen_layers;
+                        let $line = &mut self.sub_screen_line.0[..view_width];
+                        let $layers = self.enabled_sub_screen_layers & visible_layers;
                         let $screen_i = 1;
                         let $line_pixels_bit0 = 0;
                         $render;
@@ -436,6 +641,7 @@ impl Ppu {
                         let color = self.obj_line_pixels.0[i];
                         if self.layer_window_masks[4][$screen_i].0[i]
                             && color.obj_priority() $(& ($prio_mask | 4))* == $prio | 4
+                            && visible_obj_priorities & 1 << (color.obj_priority() & 3) != 0
                         {
                             *dst_pixel = color;
                         }
@@ -564,11 +770,13 @@ impl Ppu {
         }
         y = y.wrapping_add(bg.y_scroll);
         y -= y % mosaic_size as u16;
-        let start_x = bg.x_scroll << self.fb_x_shift as u8;
+        let start_x =
+            bg.x_scroll.wrapping_sub(self.widescreen_columns.0 as u16) << self.fb_x_shift as u8;
 
-        let fb_width = VIEW_WIDTH << self.fb_x_shift as u8;
+        let fb_width = self.fb_width();
         let tile_size_x_shift = 3 + X_SHIFT;
         let tile_size_y_shift = 3 + Y_SHIFT;
+        let tile_size_x_mask = (1 << tile_size_x_shift) - 1;
 
         let common_pal_base = if COLOR_SIZE == 2 && self.bg_mode.get() == 0 {
             bg_index.get() << 5
@@ -599,23 +807,21 @@ impl Ppu {
             )
         };
 
-        let mut tile_data = [0; 65];
-        let mut tiles_len = 0;
+        let mut tile_data = [0; FB_WIDTH / 8 + 1];
         {
+            // With widescreen columns, the line can be wider than the tilemap, so count the tiles
+            // to fetch rather than stopping at the last one's position
             let fetch_x_mask = 0x1F | wide_x_mask;
             let mut fetch_x = start_x >> tile_size_x_shift & fetch_x_mask;
-            let end_fetch_x = (start_x + (fb_width - 1) as u16) >> tile_size_x_shift & fetch_x_mask;
-            loop {
-                tile_data[tiles_len] = self.vram.contents.read_le::<u16>(
+            let tiles_len =
+                (((start_x as usize & tile_size_x_mask) + fb_width - 1) >> tile_size_x_shift) + 1;
+            for tile in &mut tile_data[..tiles_len] {
+                *tile = self.vram.contents.read_le::<u16>(
                     (line_screen_base_words
                         .wrapping_add(fetch_x & 0x1F)
                         .wrapping_add((fetch_x & wide_x_mask) << 5)
                         << 1) as usize,
                 );
-                tiles_len += 1;
-                if tiles_len != 1 && fetch_x == end_fetch_x {
-                    break;
-                }
                 fetch_x = (fetch_x + 1) & fetch_x_mask;
             }
         }
@@ -624,11 +830,11 @@ impl Ppu {
         let y_off_in_tile_row = y & tile_y_mask;
 
         let common_pixel_attrs = ScreenPixel(0).with_color_math_mask(1 << bg_index.get());
-        let tile_size_x_mask = (1 << tile_size_x_shift) - 1;
         let start_x_off_in_tile = start_x as usize & tile_size_x_mask;
 
         let mut first = true;
         let mut mosaic_counter = 1;
+        let mut mosaic_block_len = self.first_mosaic_block_len(mosaic_size, self.fb_x_shift as u8);
         let mut pixel = ScreenPixel(0);
         let mut tile_pixels = [ScreenPixel(0); 16];
         let mut start_tile_x_half = (start_x as usize & tile_size_x_mask) >> 3 & 1;
@@ -703,7 +909,8 @@ impl Ppu {
             }
             mosaic_counter -= 1;
             if mosaic_counter == 0 {
-                mosaic_counter = mosaic_size;
+                mosaic_counter = mosaic_block_len;
+                mosaic_block_len = mosaic_size;
                 pixel = tile_pixels[tiles_x & tile_size_x_mask];
             }
             *line_pixel = pixel;
@@ -720,15 +927,16 @@ impl Ppu {
         let mut screen_y = self.counters.v_counter();
         screen_y -= screen_y % mosaic_size as u16;
 
+        let left_columns = self.widescreen_columns.0 as i16;
         let (flipped_start_x, x_incr, y_incr) = if self.mode7.control().x_flip() {
             (
-                0xFF,
+                0xFF + left_columns,
                 -self.mode7.params[0] as i32,
                 -self.mode7.params[2] as i32,
             )
         } else {
             (
-                0_i16,
+                -left_columns,
                 self.mode7.params[0] as i32,
                 self.mode7.params[2] as i32,
             )
@@ -757,55 +965,43 @@ impl Ppu {
             pixel_attrs.set_bg_priority(2);
         }
         let mut mosaic_counter = 1;
+        let mut mosaic_block_len = self.first_mosaic_block_len(mosaic_size, 0);
         let mut pixel = ScreenPixel(0);
 
-        for line_pixels in self.bg_line_pixels[EXTBG as usize].0
-            [..VIEW_WIDTH << self.fb_x_shift as usize]
+        let fb_width = self.fb_width();
+        for line_pixels in self.bg_line_pixels[EXTBG as usize].0[..fb_width]
             .chunks_exact_mut(self.fb_x_shift as usize + 1)
         {
             mosaic_counter -= 1;
-            #[allow(clippy::never_loop)]
             if mosaic_counter == 0 {
-                mosaic_counter = mosaic_size;
-                pixel = loop {
-                    let screen_over = map_x as u32 > 0x80 << 11 || map_y as u32 > 0x80 << 11;
-                    let tile_index =
-                        ((map_y >> 11) as u16 & 0x7F) << 7 | ((map_x >> 11) as u16 & 0x7F);
-                    let mut char_index = self.vram.contents[(tile_index << 1) as usize];
-                    match self.mode7.control().screen_over() {
-                        0 | 1 => {}
-                        2 => {
-                            if screen_over {
-                                break ScreenPixel(0);
-                            }
-                        }
-                        _ => {
-                            if screen_over {
-                                char_index = 0;
+                mosaic_counter = mosaic_block_len;
+                mosaic_block_len = mosaic_size;
+                pixel = match mode7_color_index(
+                    &self.vram,
+                    self.mode7.control().screen_over(),
+                    map_x,
+                    map_y,
+                ) {
+                    None => ScreenPixel(0),
+                    Some(color_index) => {
+                        if EXTBG {
+                            if color_index & 0x7F == 0 {
+                                ScreenPixel(0)
+                            } else {
+                                pixel_attrs
+                                    .with_bg_priority(2 | (color_index >> 7))
+                                    .with_rgb(self.palette.contents[(color_index & 0x7F) as usize])
                             }
-                        }
-                    }
-                    let color_index = self.vram.contents[((char_index as u16) << 7
-                        | (map_y as u16 >> 4 & 0x70)
-                        | (map_x as u16 >> 7 & 0xE)
-                        | 1) as usize];
-                    break if EXTBG {
-                        if color_index & 0x7F == 0 {
+                        } else if color_index == 0 {
                             ScreenPixel(0)
                         } else {
-                            pixel_attrs
-                                .with_bg_priority(2 | (color_index >> 7))
-                                .with_rgb(self.palette.contents[(color_index & 0x7F) as usize])
+                            pixel_attrs.with_rgb(if self.color_math_control_a.use_direct_color() {
+                                direct_color_from_index(color_index)
+                            } else {
+                                self.palette.contents[color_index as usize]
+                            })
                         }
-                    } else if color_index == 0 {
-                        ScreenPixel(0)
-                    } else {
-                        pixel_attrs.with_rgb(if self.color_math_control_a.use_direct_color() {
-                            direct_color_from_index(color_index)
-                        } else {
-                            self.palette.contents[color_index as usize]
-                        })
-                    };
+                    }
                 };
             }
             line_pixels.fill(pixel);
@@ -817,24 +1013,19 @@ impl Ppu {
     #[allow(clippy::too_many_arguments)]
     fn draw_obj(
         &mut self,
-        obj_start_line_x: u16,
+        obj_screen_x: i16,
         width_shift: u8,
         y_in_obj: u8,
         base_tile_number: u8,
         pal_number: u8,
         bg_prio: u8,
         attrs: oam::Attrs,
+        takes_time: bool,
     ) {
-        let (start_x, end_x, mut line_x) = if obj_start_line_x >= VIEW_WIDTH as u16 {
-            (512 - obj_start_line_x as usize, 8 << width_shift, 0)
-        } else {
-            (
-                0,
-                (obj_start_line_x as usize + (8 << width_shift)).min(VIEW_WIDTH)
-                    - obj_start_line_x as usize,
-                obj_start_line_x as usize,
-            )
-        };
+        let left_columns = self.widescreen_columns.0 as i16;
+        let start_x = (-left_columns - obj_screen_x).max(0);
+        let end_x = (self.view_width() as i16 - left_columns - obj_screen_x).min(8 << width_shift);
+        let start_line_x = (left_columns + obj_screen_x + start_x) as usize;
 
         let char_base_bytes = self
             .obj_char_base_bytes
@@ -859,13 +1050,20 @@ impl Ppu {
             (7, 0)
         };
 
-        for x in start_x..end_x {
-            if x & 7 == 0 || first {
-                first = false;
-                self.obj_tiles_in_time += 1;
-                if self.obj_tiles_in_time > 34 {
+        for (x, line_x) in (start_x..end_x).zip(start_line_x..) {
+            // Only tiles within the normal view take up time, like on hardware
+            let screen_x = obj_screen_x + x;
+            if takes_time
+                && (0..VIEW_WIDTH as i16).contains(&screen_x)
+                && (x & 7 == 0 || screen_x == 0)
+            {
+                self.obj_tiles_in_time = self.obj_tiles_in_time.saturating_add(1);
+                if self.obj_tiles_in_time > 34 && !self.unlimited_objs {
                     return;
                 }
+            }
+            if x & 7 == 0 || first {
+                first = false;
                 let char_base_bytes = char_base_bytes.wrapping_add(
                     (line_base_tile_number.wrapping_add(((x >> 3) ^ tile_x_flip) as u8) as u16)
                         << 5,
@@ -884,7 +1082,6 @@ impl Ppu {
                 let color = self.palette.contents[(pal_base | color_index) as usize];
                 self.obj_line_pixels.0[line_x] = pixel_attrs.with_rgb(color);
             }
-            line_x += 1;
         }
     }
 }
@@ -0,0 +1,403 @@
+use super::{
+    oam::Obj, Bg, BgMode, BgModeControl, ColorMathControlA, ColorMathControlB, Counters,
+    DisplayControl0, DisplayControl1, LayerWin12Area, LayerWin12Areas, LayerWin12Mask,
+    LayerWin12Masks, Mode7, ObjControl, Ppu, Status77, Status78, FB_WIDTH,
+};
+use crate::{schedule::Schedule, Model};
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+};
+
+macro_rules! line_regs {
+    ($($(#[$attr: meta])* $field: ident: $ty: ty),* $(,)?) => {
+        /// The register state the PPU reads while rendering a line, copied at the start of HDraw.
+        struct LineRegs {
+            $($(#[$attr])* $field: $ty,)*
+        }
+
+        impl Ppu {
+            fn line_regs(&self) -> LineRegs {
+                LineRegs {
+                    $($(#[$attr])* $field: self.$field,)*
+                }
+            }
+
+            fn load_line_regs(&mut self, regs: LineRegs) {
+                $(
+                    $(#[$attr])*
+                    {
+                        self.$field = regs.$field;
+                    }
+                )*
+            }
+        }
+    };
+}
+
+line_regs! {
+    #[cfg(feature = "debugger")]
+    layer_overrides: super::layers::LayerOverrides,
+
+    widescreen_columns: (u8, u8),
+    unlimited_objs: bool,
+    drawing_fb_x_shift: bool,
+    fb_x_shift: bool,
+
+    status78: Status78,
+    counters: Counters,
+
+    display_control_0: DisplayControl0,
+    master_brightness: u8,
+    display_control_1: DisplayControl1,
+    enabled_main_screen_layers: u8,
+    enabled_sub_screen_layers: u8,
+
+    mode7: Mode7,
+
+    color_math_control_a: ColorMathControlA,
+    color_math_control_b: ColorMathControlB,
+    color_math_main_screen_mask: u8,
+    sub_backdrop_color: u16,
+
+    window_ranges: [(u8, u8); 2],
+    win12_areas: [LayerWin12Areas; 3],
+    win12_masks: [LayerWin12Masks; 2],
+    win_disabled_layer_masks: [u8; 2],
+    layer_win12_areas: [[LayerWin12Area; 2]; 6],
+    layer_win12_masks: [LayerWin12Mask; 6],
+
+    mosaic_remaining_lines: (u8, u8),
+    mosaic_size: u8,
+    bg_mosaic_mask: u8,
+
+    bgs: [Bg; 4],
+
+    bg_mode_control: BgModeControl,
+    bg_mode: BgMode,
+    bg_tile_size_mask: u8,
+
+    obj_control: ObjControl,
+    obj_char_base_bytes: u16,
+}
+
+struct LineJob {
+    line: u16,
+    regs: LineRegs,
+    next_first_sprite: u8,
+    vram: Arc<[u8]>,
+    oam: Arc<[Obj]>,
+    palette: Arc<[u16]>,
+}
+
+struct LineResult {
+    line: u16,
+    pixels: Vec<u32>,
+    status77: Status77,
+    #[cfg(feature = "debugger")]
+    obj_stats: Option<(usize, super::LineObjStats)>,
+}
+
+fn run_worker(model: Model, jobs: mpsc::Receiver<LineJob>, results: mpsc::Sender<LineResult>) {
+    let mut ppu = Box::new(Ppu::new(model, &mut Schedule::new()));
+    let mut vram: Option<Arc<[u8]>> = None;
+    let mut oam: Option<Arc<[Obj]>> = None;
+    let mut palette: Option<Arc<[u16]>> = None;
+
+    macro_rules! sync_contents {
+        ($cur: expr, $new: expr, $dst: expr) => {
+            if !$cur.as_ref().map_or(false, |cur| Arc::ptr_eq(cur, &$new)) {
+                $dst.copy_from_slice(&$new);
+                $cur = Some($new);
+            }
+        };
+    }
+
+    for job in jobs {
+        ppu.load_line_regs(job.regs);
+        // Previous lines are widened by the emulation thread once they're done
+        ppu.prev_line_fb_x_shift = ppu.fb_x_shift;
+        ppu.status77 = Status77(0);
+        ppu.oam.set_next_first_sprite(job.next_first_sprite);
+        sync_contents!(vram, job.vram, ppu.vram.contents[..]);
+        sync_contents!(oam, job.oam, ppu.oam.contents[..]);
+        sync_contents!(palette, job.palette, ppu.palette.contents[..]);
+
+        ppu.render_scanline_pixels(job.line);
+
+        let fb_line_start = job.line as usize * FB_WIDTH;
+        let fb_line_len = ppu.fb_width();
+        #[cfg(feature = "debugger")]
+        let screen_line = ppu.counters.v_counter().wrapping_sub(1) as usize;
+        let result = LineResult {
+            line: job.line,
+            pixels: ppu.framebuffer.0[fb_line_start..fb_line_start + fb_line_len].to_vec(),
+            status77: ppu.status77,
+            #[cfg(feature = "debugger")]
+            obj_stats: ppu
+                .line_obj_stats
+                .get(screen_line)
+                .map(|stats| (screen_line, *stats)),
+        };
+        if results.send(result).is_err() {
+            break;
+        }
+    }
+}
+
+/// Renders scanlines on a pool of worker threads, each with its own copy of the PPU that gets
+/// loaded with the state at the start of each line it's assigned.
+pub(super) struct ThreadedRenderer {
+    job_txs: Vec<mpsc::Sender<LineJob>>,
+    result_rx: mpsc::Receiver<LineResult>,
+    workers: Vec<thread::JoinHandle<()>>,
+    next_worker: usize,
+    pending_lines: usize,
+    vram: Arc<[u8]>,
+    oam: Arc<[Obj]>,
+    palette: Arc<[u16]>,
+}
+
+impl ThreadedRenderer {
+    fn new(ppu: &Ppu, threads: usize) -> Self {
+        let model = if ppu.status78.pal_console() {
+            Model::Pal
+        } else {
+            Model::Ntsc
+        };
+        let (result_tx, result_rx) = mpsc::channel();
+        let mut job_txs = Vec::with_capacity(threads);
+        let mut workers = Vec::with_capacity(threads);
+        for i in 0..threads {
+            let (job_tx, job_rx) = mpsc::channel();
+            let result_tx = result_tx.clone();
+            job_txs.push(job_tx);
+            workers.push(
+                thread::Builder::new()
+                    .name(format!("PPU renderer {}", i))
+                    .spawn(move || run_worker(model, job_rx, result_tx))
+                    .expect("Couldn't spawn PPU render thread"),
+            );
+        }
+        ThreadedRenderer {
+            job_txs,
+            result_rx,
+            workers,
+            next_worker: 0,
+            pending_lines: 0,
+            vram: Arc::from(&ppu.vram.contents[..]),
+            oam: Arc::from(&ppu.oam.contents[..]),
+            palette: Arc::from(&ppu.palette.contents[..]),
+        }
+    }
+}
+
+impl Drop for ThreadedRenderer {
+    fn drop(&mut self) {
+        self.job_txs.clear();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Ppu {
+    #[inline]
+    pub fn render_threads(&self) -> usize {
+        self.threaded_renderer
+            .as_ref()
+            .map_or(0, |renderer| renderer.workers.len())
+    }
+
+    /// Sets the number of worker threads used to render scanlines, with 0 (the default) rendering
+    /// them on the emulation thread. The framebuffer contents are the same in both cases.
+    pub fn set_render_threads(&mut self, threads: usize) {
+        if threads == self.render_threads() {
+            return;
+        }
+        self.finish_threaded_lines();
+        self.threaded_renderer = None;
+        if threads != 0 {
+            self.threaded_renderer = Some(ThreadedRenderer::new(self, threads));
+        }
+    }
+
+    /// Waits for all lines queued on worker threads and copies them into the framebuffer.
+    pub(super) fn finish_threaded_lines(&mut self) {
+        let renderer = match &mut self.threaded_renderer {
+            Some(renderer) => renderer,
+            None => return,
+        };
+        while renderer.pending_lines != 0 {
+            let result = renderer
+                .result_rx
+                .recv()
+                .expect("PPU render thread exited unexpectedly");
+            renderer.pending_lines -= 1;
+            let fb_line_start = result.line as usize * FB_WIDTH;
+            self.framebuffer.0[fb_line_start..fb_line_start + result.pixels.len()]
+                .copy_from_slice(&result.pixels);
+            if result.status77.range_over() {
+                self.status77.set_range_over(true);
+            }
+            if result.status77.time_over() {
+                self.status77.set_time_over(true);
+            }
+            #[cfg(feature = "debugger")]
+            if let Some((screen_line, stats)) = result.obj_stats {
+                self.line_obj_stats[screen_line] = stats;
+            }
+        }
+    }
+
+    /// Queues `line` to be rendered by a worker thread, after `start_scanline` was called for it.
+    pub(super) fn queue_scanline(&mut self, line: u16) {
+        // Debug captures and HD Mode 7 lines are recorded by the PPU that renders the line, so
+        // render it here
+        #[cfg(feature = "debugger")]
+        let render_here = self.capture_target.is_some() || self.layer_capture.is_some();
+        #[cfg(not(feature = "debugger"))]
+        let render_here = false;
+        if render_here || self.hd_mode7.is_some() {
+            self.finish_threaded_lines();
+            self.render_scanline_pixels(line);
+            return;
+        }
+
+        if !self.display_control_0.forced_blank() {
+            if self.fb_x_shift && !self.prev_line_fb_x_shift {
+                self.finish_threaded_lines();
+            }
+            self.widen_prev_lines(line);
+        }
+
+        let regs = self.line_regs();
+        let next_first_sprite = self.oam.next_first_sprite();
+        let renderer = match &mut self.threaded_renderer {
+            Some(renderer) => renderer,
+            None => return,
+        };
+        if core::mem::take(&mut self.vram.dirty) {
+            renderer.vram = Arc::from(&self.vram.contents[..]);
+        }
+        if core::mem::take(&mut self.oam.dirty) {
+            renderer.oam = Arc::from(&self.oam.contents[..]);
+        }
+        if core::mem::take(&mut self.palette.dirty) {
+            renderer.palette = Arc::from(&self.palette.contents[..]);
+        }
+        let job = LineJob {
+            line,
+            regs,
+            next_first_sprite,
+            vram: Arc::clone(&renderer.vram),
+            oam: Arc::clone(&renderer.oam),
+            palette: Arc::clone(&renderer.palette),
+        };
+        renderer.job_txs[renderer.next_worker]
+            .send(job)
+            .expect("PPU render thread exited unexpectedly");
+        renderer.next_worker = (renderer.next_worker + 1) % renderer.job_txs.len();
+        renderer.pending_lines += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppu::{BgCharControl, BgScreenControl, VIEW_HEIGHT_NTSC};
+
+    /// A xorshift generator, so that the test's "random" PPU state is the same on every run.
+    struct Rng(u32);
+
+    impl Rng {
+        fn next(&mut self) -> u8 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0 as u8
+        }
+    }
+
+    fn write_vram(ppu: &mut Ppu, rng: &mut Rng, word_addr: u16, words: usize) {
+        ppu.vram.set_addr_low(word_addr as u8);
+        ppu.vram.set_addr_high((word_addr >> 8) as u8);
+        for _ in 0..words {
+            ppu.write_vram_low(rng.next());
+            ppu.write_vram_high(rng.next());
+        }
+    }
+
+    fn write_palette(ppu: &mut Ppu, rng: &mut Rng, index: u8, colors: usize) {
+        ppu.palette.set_word_addr(index);
+        for _ in 0..colors * 2 {
+            ppu.write_palette(rng.next());
+        }
+    }
+
+    fn write_oam(ppu: &mut Ppu, rng: &mut Rng) {
+        ppu.oam.set_reload_addr_low(0);
+        ppu.oam.set_reload_addr_high(0);
+        for _ in 0..0x220 {
+            ppu.write_oam(rng.next());
+        }
+    }
+
+    /// Renders a frame of pseudo-random mode 1 tiles and OBJs, with VRAM, CGRAM and OAM being
+    /// rewritten between lines, and returns the visible part of the framebuffer.
+    fn render_frame(threads: usize) -> Vec<u32> {
+        let mut schedule = Schedule::new();
+        let mut ppu = Box::new(Ppu::new(Model::Ntsc, &mut schedule));
+        ppu.set_render_threads(threads);
+        let mut rng = Rng(0x1234_5678);
+
+        ppu.set_display_control_0(DisplayControl0(0x0F));
+        ppu.set_bg_mode_control(BgModeControl(1));
+        for (i, bg) in ppu.bgs.iter_mut().enumerate() {
+            bg.set_screen_control(BgScreenControl((i as u8) << 3));
+        }
+        ppu.set_bg_char_control_12(BgCharControl(0x32));
+        ppu.set_bg_char_control_34(BgCharControl(0x04));
+        ppu.set_obj_control(ObjControl(0x06));
+        ppu.enabled_main_screen_layers = 0x17;
+        ppu.enabled_sub_screen_layers = 0x02;
+        write_vram(&mut ppu, &mut rng, 0, 0x8000);
+        write_palette(&mut ppu, &mut rng, 0, 0x100);
+        write_oam(&mut ppu, &mut rng);
+
+        for v_counter in 1..=VIEW_HEIGHT_NTSC as u16 {
+            if v_counter % 16 == 0 {
+                let (word_addr, index) = (rng.0 as u16, rng.next());
+                write_vram(&mut ppu, &mut rng, word_addr, 0x40);
+                write_palette(&mut ppu, &mut rng, index, 4);
+            }
+            if v_counter % 64 == 0 {
+                write_oam(&mut ppu, &mut rng);
+            }
+            ppu.counters
+                .start_new_line(v_counter, 1364, 0, &mut schedule);
+            if threads == 0 {
+                ppu.render_scanline(v_counter - 1);
+            } else {
+                ppu.start_scanline();
+                ppu.queue_scanline(v_counter - 1);
+            }
+        }
+        ppu.finish_threaded_lines();
+
+        ppu.framebuffer.0[..FB_WIDTH * VIEW_HEIGHT_NTSC].to_vec()
+    }
+
+    #[test]
+    fn threaded_matches_single_threaded() {
+        let expected = render_frame(0);
+        assert!(expected.iter().any(|&pixel| pixel != expected[0]));
+        for threads in [1, 3] {
+            assert!(
+                render_frame(threads) == expected,
+                "{} render threads",
+                threads
+            );
+        }
+    }
+}
@@ -15,6 +15,8 @@ bitfield_debug! {
 
 pub struct Vram {
     pub contents: Box<Bytes<0x1_0000>>,
+    /// Whether `contents` was written since the render threads last got a copy of it.
+    pub(super) dirty: bool,
     increment_control: IncrementControl,
     addr_increment: u8,
     read_latch: u16,
@@ -26,6 +28,7 @@ impl Vram {
     pub(crate) fn new() -> Self {
         Vram {
             contents: zeroed_box(),
+            dirty: true,
             increment_control: IncrementControl(0),
             addr_increment: 1,
             cpu_written_addr: 0,
@@ -134,6 +137,7 @@ impl Ppu {
     pub fn write_vram_low(&mut self, value: u8) {
         let translated_addr = self.vram.translated_cur_word_addr();
         self.vram.contents[(translated_addr << 1) as usize] = value;
+        self.vram.dirty = true;
         if !self.vram.increment_control.incr_after_high_byte_access() {
             self.vram.cur_word_addr = self
                 .vram
@@ -145,6 +149,7 @@ impl Ppu {
     pub fn write_vram_high(&mut self, value: u8) {
         let translated_addr = self.vram.translated_cur_word_addr();
         self.vram.contents[(translated_addr << 1) as usize | 1] = value;
+        self.vram.dirty = true;
         if self.vram.increment_control.incr_after_high_byte_access() {
             self.vram.cur_word_addr = self
                 .vram
@@ -1,4 +1,4 @@
-use super::{Ppu, VIEW_WIDTH};
+use super::{Ppu, MAX_VIEW_WIDTH};
 use crate::utils::bitfield_debug;
 
 bitfield_debug! {
@@ -213,6 +213,9 @@ impl Ppu {
         let layers_disabled_by_windows =
             self.win_disabled_layer_masks[0] | self.win_disabled_layer_masks[1] | 0x20;
         let [(win1_start, win1_end), (win2_start, win2_end)] = self.window_ranges;
+        // Window positions are relative to the normal view, widescreen columns are always outside
+        // of both windows
+        let left_columns = self.widescreen_columns.0 as usize;
 
         for layer_i in 0..6 {
             if layers_enabled & 1 << layer_i == 0 {
@@ -260,14 +263,18 @@ impl Ppu {
                 LayerWin12Area::Inside => {
                     buffers[0].0.fill(true);
                     if win1_end >= win1_start {
-                        buffers[0].0[win1_start as usize..=win1_end as usize].fill(false);
+                        buffers[0].0
+                            [left_columns + win1_start as usize..=left_columns + win1_end as usize]
+                            .fill(false);
                     }
                     win2_buffer_i = 1;
                 }
                 LayerWin12Area::Outside => {
                     buffers[0].0.fill(false);
                     if win1_end >= win1_start {
-                        buffers[0].0[win1_start as usize..=win1_end as usize].fill(true);
+                        buffers[0].0
+                            [left_columns + win1_start as usize..=left_columns + win1_end as usize]
+                            .fill(true);
                     }
                     win2_buffer_i = 1;
                 }
@@ -279,13 +286,17 @@ impl Ppu {
                 LayerWin12Area::Inside => {
                     win2_buffer.0.fill(true);
                     if win2_end >= win2_start {
-                        win2_buffer.0[win2_start as usize..=win2_end as usize].fill(false);
+                        win2_buffer.0
+                            [left_columns + win2_start as usize..=left_columns + win2_end as usize]
+                            .fill(false);
                     }
                 }
                 LayerWin12Area::Outside => {
                     win2_buffer.0.fill(false);
                     if win2_end >= win2_start {
-                        win2_buffer.0[win2_start as usize..=win2_end as usize].fill(true);
+                        win2_buffer.0
+                            [left_columns + win2_start as usize..=left_columns + win2_end as usize]
+                            .fill(true);
                     }
                 }
             }
@@ -295,22 +306,22 @@ impl Ppu {
             {
                 match win12_mask {
                     LayerWin12Mask::Or => {
-                        for i in 0..VIEW_WIDTH {
+                        for i in 0..MAX_VIEW_WIDTH {
                             buffers[0].0[i] &= buffers[1].0[i];
                         }
                     }
                     LayerWin12Mask::And => {
-                        for i in 0..VIEW_WIDTH {
+                        for i in 0..MAX_VIEW_WIDTH {
                             buffers[0].0[i] |= buffers[1].0[i];
                         }
                     }
                     LayerWin12Mask::Xor => {
-                        for i in 0..VIEW_WIDTH {
+                        for i in 0..MAX_VIEW_WIDTH {
                             buffers[0].0[i] = !(buffers[0].0[i] ^ buffers[1].0[i]);
                         }
                     }
                     LayerWin12Mask::Xnor => {
-                        for i in 0..VIEW_WIDTH {
+                        for i in 0..MAX_VIEW_WIDTH {
                             buffers[0].0[i] ^= buffers[1].0[i];
                         }
                     }
@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// A set of labels and comments for a single address space (the 65816's 24-bit one, or the
+/// SPC700's 16-bit one).
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    labels: BTreeMap<u32, String>,
+    comments: BTreeMap<u32, String>,
+    addrs: HashMap<String, u32>,
+}
+
+impl SymbolTable {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.comments.is_empty()
+    }
+
+    pub fn insert_label(&mut self, addr: u32, name: String) {
+        // Keep the first label defined for an address, but allow looking up all of them by name
+        self.addrs.insert(name.clone(), addr);
+        self.labels.entry(addr).or_insert(name);
+    }
+
+    pub fn insert_comment(&mut self, addr: u32, comment: String) {
+        self.comments
+            .entry(addr)
+            .and_modify(|prev| {
+                prev.push_str("; ");
+                prev.push_str(&comment);
+            })
+            .or_insert(comment);
+    }
+
+    /// Merges all symbols from `other` into `self`, with existing labels taking precedence.
+    pub fn extend(&mut self, other: SymbolTable) {
+        for (addr, name) in other.labels {
+            self.labels.entry(addr).or_insert(name);
+        }
+        for (addr, comment) in other.comments {
+            self.insert_comment(addr, comment);
+        }
+        for (name, addr) in other.addrs {
+            self.addrs.entry(name).or_insert(addr);
+        }
+    }
+
+    #[inline]
+    pub fn label(&self, addr: u32) -> Option<&str> {
+        self.labels.get(&addr).map(String::as_str)
+    }
+
+    #[inline]
+    pub fn comment(&self, addr: u32) -> Option<&str> {
+        self.comments.get(&addr).map(String::as_str)
+    }
+
+    #[inline]
+    pub fn addr_of(&self, name: &str) -> Option<u32> {
+        self.addrs.get(name).copied()
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = (u32, &str)> + '_ {
+        self.labels
+            .iter()
+            .map(|(addr, name)| (*addr, name.as_str()))
+    }
+
+    /// Parses a `.sym` file, either in WLA-DX format (`bb:aaaa name` lines in a `[labels]` section,
+    /// also output by asar) or as plain `address name` lines (as output by no$sns, asar and bass);
+    /// a `;` after the name starts a comment for the same address. Unparseable lines are ignored.
+    pub fn parse_sym(content: &str) -> Self {
+        let mut result = SymbolTable::new();
+        let mut in_labels_section = true;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                in_labels_section = section.eq_ignore_ascii_case("labels");
+                continue;
+            }
+            if !in_labels_section {
+                continue;
+            }
+
+            let (line, comment) = match line.split_once(';') {
+                Some((line, comment)) => (line.trim_end(), Some(comment.trim())),
+                None => (line, None),
+            };
+            let (addr, name) = match line.split_once(char::is_whitespace) {
+                Some((addr, name)) => (addr, name.trim()),
+                None => continue,
+            };
+            let addr = match addr.split_once(':') {
+                Some((bank, addr)) => {
+                    match (u8::from_str_radix(bank, 16), u16::from_str_radix(addr, 16)) {
+                        (Ok(bank), Ok(addr)) => (bank as u32) << 16 | addr as u32,
+                        _ => continue,
+                    }
+                }
+                None => match u32::from_str_radix(addr, 16) {
+                    Ok(addr) => addr & 0xFF_FFFF,
+                    Err(_) => continue,
+                },
+            };
+            // WLA-DX emits a few internal labels for anonymous/temporary ones
+            if !name.is_empty() && !name.starts_with(':') {
+                result.insert_label(addr, name.to_string());
+            }
+            if let Some(comment) = comment.filter(|comment| !comment.is_empty()) {
+                result.insert_comment(addr, comment.to_string());
+            }
+        }
+        result
+    }
+
+    /// Parses a ca65/ld65 `.dbg` debug info file, importing all labels (resolved to full 24-bit
+    /// addresses through the segment they're defined in). Unparseable lines are ignored.
+    pub fn parse_ca65_dbg(content: &str) -> Self {
+        fn fields(line: &str) -> HashMap<&str, &str> {
+            line.split(',')
+                .filter_map(|field| field.split_once('='))
+                .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+                .collect()
+        }
+
+        fn parse_num(value: &str) -> Option<u32> {
+            match value.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => value.parse().ok(),
+            }
+        }
+
+        let mut seg_banks = HashMap::new();
+        let mut syms = Vec::new();
+        for line in content.lines() {
+            let (kind, rest) = match line.split_once(char::is_whitespace) {
+                Some(parts) => parts,
+                None => continue,
+            };
+            match kind {
+                "seg" => {
+                    let fields = fields(rest);
+                    if let (Some(id), Some(start)) = (
+                        fields.get("id").and_then(|id| parse_num(id)),
+                        fields.get("start").and_then(|start| parse_num(start)),
+                    ) {
+                        seg_banks.insert(id, start & 0xFF_0000);
+                    }
+                }
+                "sym" => {
+                    let fields = fields(rest);
+                    if fields.get("type") != Some(&"lab") {
+                        continue;
+                    }
+                    if let (Some(name), Some(value)) = (
+                        fields.get("name"),
+                        fields.get("val").and_then(|value| parse_num(value)),
+                    ) {
+                        let seg = fields.get("seg").and_then(|seg| parse_num(seg));
+                        syms.push((name.to_string(), value, seg));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut result = SymbolTable::new();
+        for (name, value, seg) in syms {
+            let addr = if value > 0xFFFF {
+                value & 0xFF_FFFF
+            } else {
+                seg.and_then(|seg| seg_banks.get(&seg).copied())
+                    .unwrap_or(0)
+                    | value
+            };
+            result.insert_label(addr, name);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wla_dx_sym() {
+        let symbols = SymbolTable::parse_sym(
+            "; wla symbolic information file\n\
+             \n\
+             [labels]\n\
+             00:8000 Reset\n\
+             7e:0010 Frame_Counter\n\
+             00:8010 :__tmp_0\n\
+             \n\
+             [definitions]\n\
+             00000010 SPRITE_COUNT\n",
+        );
+        assert_eq!(symbols.label(0x00_8000), Some("Reset"));
+        assert_eq!(symbols.label(0x7E_0010), Some("Frame_Counter"));
+        assert_eq!(symbols.addr_of("Frame_Counter"), Some(0x7E_0010));
+        // Temporary labels and anything outside of `[labels]` are skipped
+        assert_eq!(symbols.label(0x00_8010), None);
+        assert_eq!(symbols.addr_of("SPRITE_COUNT"), None);
+        assert_eq!(symbols.labels().count(), 2);
+    }
+
+    #[test]
+    fn plain_sym() {
+        let symbols = SymbolTable::parse_sym(
+            "# no$sns symbols\n\
+             808000 Reset ; entry point\n\
+             c0ffee Data\n\
+             1808000 Mirror\n\
+             zz Invalid\n\
+             80ffff\n",
+        );
+        assert_eq!(symbols.label(0x80_8000), Some("Reset"));
+        assert_eq!(symbols.comment(0x80_8000), Some("entry point"));
+        assert_eq!(symbols.label(0xC0_FFEE), Some("Data"));
+        assert_eq!(symbols.comment(0xC0_FFEE), None);
+        // Addresses wrap around to 24 bits, and the first label defined for an address is kept
+        assert_eq!(symbols.addr_of("Mirror"), Some(0x80_8000));
+        assert_eq!(symbols.labels().count(), 2);
+    }
+
+    #[test]
+    fn ca65_dbg() {
+        let symbols = SymbolTable::parse_ca65_dbg(
+            "version\tmajor=2,minor=0\n\
+             seg\tid=0,name=\"CODE\",start=0x808000,size=0x0100,addrsize=absolute,type=ro\n\
+             seg\tid=1,name=\"BSS\",start=0x7E0000,size=0x0100,addrsize=absolute,type=rw\n\
+             sym\tid=0,name=\"reset\",addrsize=absolute,scope=0,def=1,val=0x8000,seg=0,type=lab\n\
+             sym\tid=1,name=\"counter\",addrsize=absolute,scope=0,def=2,val=0x10,seg=1,type=lab\n\
+             sym\tid=2,name=\"far_data\",addrsize=far,scope=0,def=3,val=0xC10000,type=lab\n\
+             sym\tid=3,name=\"COUNT\",addrsize=zeropage,scope=0,def=4,val=0x10,type=equ\n",
+        );
+        assert_eq!(symbols.label(0x80_8000), Some("reset"));
+        assert_eq!(symbols.label(0x7E_0010), Some("counter"));
+        assert_eq!(symbols.label(0xC1_0000), Some("far_data"));
+        assert_eq!(symbols.addr_of("COUNT"), None);
+        assert_eq!(symbols.labels().count(), 3);
+    }
+}
@@ -0,0 +1,118 @@
+use crossbeam_channel::{Receiver, Sender};
+use imgui::{Ui, Window};
+use rfd::FileDialog;
+use std::path::PathBuf;
+
+pub type ResultSender = Sender<Result<(), String>>;
+
+pub enum Request {
+    SetEnabled(bool),
+    Reset,
+    Load(PathBuf, ResultSender),
+    Save(PathBuf, ResultSender),
+}
+
+pub struct CdlLogger {
+    enabled: bool,
+    path: Option<PathBuf>,
+    pending_result: Option<(&'static str, Receiver<Result<(), String>>)>,
+    status: Option<Result<String, String>>,
+}
+
+impl CdlLogger {
+    pub fn new() -> Self {
+        CdlLogger {
+            enabled: false,
+            path: None,
+            pending_result: None,
+            status: None,
+        }
+    }
+
+    fn file_request(
+        &mut self,
+        action: &'static str,
+        request: fn(PathBuf, ResultSender) -> Request,
+    ) -> Option<Request> {
+        let path = self.path.clone()?;
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.pending_result = Some((action, rx));
+        Some(request(path, tx))
+    }
+
+    /// Draws the code/data logger window, returning the requests to send to the emulator.
+    pub fn draw(&mut self, ui: &Ui, emu_running: bool, opened: &mut bool) -> Vec<Request> {
+        let mut requests = Vec::new();
+        if !emu_running {
+            self.enabled = false;
+            self.pending_result = None;
+        }
+
+        if let Some((action, rx)) = &self.pending_result {
+            if let Ok(result) = rx.try_recv() {
+                self.status = Some(result.map(|_| format!("{} successfully", action)));
+                self.pending_result = None;
+            }
+        }
+
+        Window::new("Code/data logger")
+            .opened(opened)
+            .always_auto_resize(true)
+            .build(ui, || {
+                if ui.checkbox("Enabled", &mut self.enabled) {
+                    if emu_running {
+                        requests.push(Request::SetEnabled(self.enabled));
+                    } else {
+                        self.enabled = false;
+                    }
+                }
+                ui.same_line();
+                if ui.button("Reset") && self.enabled {
+                    requests.push(Request::Reset);
+                }
+
+                if ui.button("Browse...") {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("Code/data log", &["cdl"])
+                        .save_file()
+                    {
+                        self.path = Some(path);
+                    }
+                }
+                ui.same_line();
+                match &self.path {
+                    Some(path) => ui.text(&path.to_string_lossy()),
+                    None => ui.text_disabled("No file selected"),
+                }
+
+                if ui.button("Load") && emu_running && self.enabled {
+                    requests.extend(self.file_request("Loaded", Request::Load));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Continue logging from a previously saved file");
+                }
+                ui.same_line();
+                if ui.button("Save") && emu_running && self.enabled {
+                    requests.extend(self.file_request("Saved", Request::Save));
+                }
+
+                if !self.enabled {
+                    ui.text_disabled("Enable logging to load or save files");
+                } else if self.path.is_none() {
+                    ui.text_disabled("No file selected");
+                } else {
+                    match &self.status {
+                        Some(Ok(message)) => ui.text(message),
+                        Some(Err(err)) => ui.text_colored([1.0, 0.3, 0.3, 1.0], err),
+                        None => {}
+                    }
+                }
+            });
+
+        if !*opened && self.enabled {
+            self.enabled = false;
+            requests.push(Request::SetEnabled(false));
+        }
+        requests
+    }
+}
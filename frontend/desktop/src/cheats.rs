@@ -0,0 +1,129 @@
+use super::config::Cheat;
+use imgui::{Ui, Window};
+use ness_core::cheats::{decode_list, load_cht, Code};
+use rfd::FileDialog;
+use std::fs;
+
+pub fn enabled_codes(cheats: &[Cheat]) -> Vec<Code> {
+    cheats
+        .iter()
+        .filter(|cheat| cheat.enabled)
+        .filter_map(|cheat| decode_list(&cheat.code).ok())
+        .flatten()
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Editor {
+    new_description: String,
+    new_code: String,
+    new_code_error: Option<String>,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn import_cht(cheats: &mut Vec<Cheat>) -> bool {
+        let path = match FileDialog::new()
+            .add_filter("bsnes cheat file", &["cht"])
+            .pick_file()
+        {
+            Some(path) => path,
+            None => return false,
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => {
+                error!("Couldn't read cheat file", "{}", err);
+                return false;
+            }
+        };
+        match load_cht(&content) {
+            Ok(entries) => {
+                cheats.extend(entries.into_iter().map(|entry| Cheat {
+                    description: entry.description,
+                    code: entry.code,
+                    enabled: entry.enabled,
+                }));
+                true
+            }
+            Err(err) => {
+                error!("Couldn't parse cheat file", "{}", err);
+                false
+            }
+        }
+    }
+
+    /// Draws the cheat list, returning whether it was modified (in which case the emulator's
+    /// active codes need to be updated).
+    pub fn draw(&mut self, ui: &Ui, cheats: &mut Vec<Cheat>, opened: &mut bool) -> bool {
+        let mut changed = false;
+        Window::new("Cheats").opened(opened).build(ui, || {
+            let mut remove = None;
+            for (i, cheat) in cheats.iter_mut().enumerate() {
+                let _id = ui.push_id(i as i32);
+                changed |= ui.checkbox("##enabled", &mut cheat.enabled);
+                ui.same_line();
+                let is_valid = decode_list(&cheat.code).is_ok();
+                if is_valid {
+                    ui.text(&cheat.code);
+                } else {
+                    ui.text_colored([1.0, 0.3, 0.3, 1.0], &cheat.code);
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Invalid code, it will be ignored");
+                    }
+                }
+                ui.same_line();
+                ui.text_disabled(&cheat.description);
+                ui.same_line();
+                if ui.small_button("Remove") {
+                    remove = Some(i);
+                }
+            }
+            if let Some(i) = remove {
+                cheats.remove(i);
+                changed = true;
+            }
+
+            ui.separator();
+
+            ui.input_text("Description", &mut self.new_description).build();
+            if ui
+                .input_text("Code", &mut self.new_code)
+                .hint("Game Genie, PAR or address=value")
+                .build()
+            {
+                self.new_code_error = None;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Multiple codes can be separated with `+`");
+            }
+            if ui.button("Add") {
+                match decode_list(&self.new_code) {
+                    Ok(codes) if !codes.is_empty() => {
+                        cheats.push(Cheat {
+                            description: self.new_description.trim().to_string(),
+                            code: self.new_code.trim().to_string(),
+                            enabled: true,
+                        });
+                        self.new_description.clear();
+                        self.new_code.clear();
+                        changed = true;
+                    }
+                    Ok(_) => self.new_code_error = Some("No code specified".to_string()),
+                    Err(err) => self.new_code_error = Some(err.to_string()),
+                }
+            }
+            ui.same_line();
+            if ui.button("Import .cht...") {
+                changed |= Self::import_cht(cheats);
+            }
+            if let Some(err) = &self.new_code_error {
+                ui.text_colored([1.0, 0.3, 0.3, 1.0], err);
+            }
+        });
+        changed
+    }
+}
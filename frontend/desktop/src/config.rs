@@ -8,6 +8,7 @@ use ness_core::{
     cart::info::header::{Header as CartHeader, Region},
     Model,
 };
+use ness_video_filters::Settings as VideoFilterSettings;
 use saves::{save_path, SavePathConfig};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -37,13 +38,21 @@ pub struct Global {
     pub limit_framerate: bool,
     pub sync_to_audio: bool,
     pub audio_interp_method: audio::InterpMethod,
+    pub dot_accurate_ppu: bool,
+    pub unlimited_sprites: bool,
+    pub widescreen_columns: (u8, u8),
     pub pause_on_launch: bool,
     pub autosave_interval_ms: f32,
 
     pub save_dir_path: PathBuf,
+    pub screenshot_dir_path: PathBuf,
 
     pub audio_volume: f32,
     pub audio_sample_chunk_size: u32,
+    pub ppu_render_threads: usize,
+    pub hd_mode7_scale: usize,
+    pub hd_mode7_perspective_correction: bool,
+    pub video_filters: VideoFilterSettings,
     pub fullscreen_render: bool,
     pub cart_db_path: PathBuf,
     pub board_db_path: PathBuf,
@@ -61,13 +70,21 @@ impl Default for Global {
             limit_framerate: true,
             sync_to_audio: true,
             audio_interp_method: audio::InterpMethod::Nearest,
+            dot_accurate_ppu: false,
+            unlimited_sprites: false,
+            widescreen_columns: (0, 0),
             pause_on_launch: false,
             autosave_interval_ms: 1000.0,
 
             save_dir_path: data_base.join("saves"),
+            screenshot_dir_path: data_base.join("screenshots"),
 
             audio_volume: 1.0,
             audio_sample_chunk_size: 512,
+            ppu_render_threads: 0,
+            hd_mode7_scale: 1,
+            hd_mode7_perspective_correction: true,
+            video_filters: VideoFilterSettings::default(),
             fullscreen_render: true,
             cart_db_path: data_base.join("db/carts.bml"),
             board_db_path: data_base.join("db/boards.bml"),
@@ -85,10 +102,15 @@ pub struct Game {
     pub limit_framerate: Option<bool>,
     pub sync_to_audio: Option<bool>,
     pub audio_interp_method: Option<audio::InterpMethod>,
+    pub dot_accurate_ppu: Option<bool>,
+    pub unlimited_sprites: Option<bool>,
+    pub widescreen_columns: Option<(u8, u8)>,
     pub pause_on_launch: Option<bool>,
     pub autosave_interval_ms: Option<f32>,
 
     pub save_path: Option<SavePathConfig>,
+
+    pub cheats: Vec<Cheat>,
 }
 
 impl Default for Game {
@@ -98,14 +120,27 @@ impl Default for Game {
             limit_framerate: None,
             sync_to_audio: None,
             audio_interp_method: None,
+            dot_accurate_ppu: None,
+            unlimited_sprites: None,
+            widescreen_columns: None,
             pause_on_launch: None,
             autosave_interval_ms: None,
 
             save_path: Some(SavePathConfig::GlobalSingle),
+
+            cheats: Vec::new(),
         }
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Cheat {
+    pub description: String,
+    pub code: String,
+    pub enabled: bool,
+}
+
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
@@ -231,9 +266,16 @@ pub struct LaunchConfig {
     pub limit_framerate: RuntimeModifiable<bool>,
     pub sync_to_audio: RuntimeModifiable<bool>,
     pub audio_interp_method: RuntimeModifiable<audio::InterpMethod>,
+    pub dot_accurate_ppu: RuntimeModifiable<bool>,
+    pub unlimited_sprites: RuntimeModifiable<bool>,
+    pub widescreen_columns: RuntimeModifiable<(u8, u8)>,
     pub pause_on_launch: bool,
     pub autosave_interval_ms: RuntimeModifiable<f32>,
     pub audio_sample_chunk_size: u32,
+    pub ppu_render_threads: usize,
+    pub hd_mode7_scale: usize,
+    pub hd_mode7_perspective_correction: bool,
+    pub video_filters: VideoFilterSettings,
     pub cur_save_path: Option<PathBuf>,
 }
 
@@ -328,6 +370,9 @@ pub fn launch_config(
     let limit_framerate = runtime_modifiable!(limit_framerate);
     let sync_to_audio = runtime_modifiable!(sync_to_audio);
     let audio_interp_method = runtime_modifiable!(audio_interp_method);
+    let dot_accurate_ppu = runtime_modifiable!(dot_accurate_ppu);
+    let unlimited_sprites = runtime_modifiable!(unlimited_sprites);
+    let widescreen_columns = runtime_modifiable!(widescreen_columns);
     let pause_on_launch = plain_setting!(pause_on_launch);
     let autosave_interval_ms = runtime_modifiable!(autosave_interval_ms);
 
@@ -342,9 +387,16 @@ pub fn launch_config(
         limit_framerate,
         sync_to_audio,
         audio_interp_method,
+        dot_accurate_ppu,
+        unlimited_sprites,
+        widescreen_columns,
         pause_on_launch,
         autosave_interval_ms,
         audio_sample_chunk_size: global_config.audio_sample_chunk_size,
+        ppu_render_threads: global_config.ppu_render_threads,
+        hd_mode7_scale: global_config.hd_mode7_scale,
+        hd_mode7_perspective_correction: global_config.hd_mode7_perspective_correction,
+        video_filters: global_config.video_filters,
         cur_save_path,
     })
 }
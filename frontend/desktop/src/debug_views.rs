@@ -12,6 +12,32 @@ mod spc_memory;
 pub use spc_memory::SpcMemory;
 mod spc_disasm;
 pub use spc_disasm::SpcDisasm;
+mod ram_search;
+pub use ram_search::RamSearch;
+mod breakpoints;
+pub use breakpoints::Breakpoints;
+mod call_stack;
+pub use call_stack::CallStack;
+mod profiler;
+pub use profiler::Profiler;
+mod event_viewer;
+pub use event_viewer::EventViewer;
+mod tile_viewer;
+pub use tile_viewer::TileViewer;
+mod tilemap_viewer;
+pub use tilemap_viewer::TilemapViewer;
+mod oam_viewer;
+pub use oam_viewer::OamViewer;
+mod palette_viewer;
+pub use palette_viewer::PaletteViewer;
+mod ppu_state;
+pub use ppu_state::PpuState;
+mod dma_viewer;
+pub use dma_viewer::DmaViewer;
+mod window_viewer;
+pub use window_viewer::WindowViewer;
+mod layer_viewer;
+pub use layer_viewer::LayerViewer;
 
 use super::ui::window::Window;
 use fxhash::FxHashMap;
@@ -21,6 +47,15 @@ use std::collections::hash_map::Entry;
 
 pub type ViewKey = u32;
 
+/// Requests that views can make while rendering that affect other views, handled after all views
+/// have been rendered.
+#[derive(Default)]
+pub struct Actions {
+    pub show_cpu_memory: Option<u32>,
+    pub show_spc_memory: Option<u16>,
+    pub show_cpu_disasm: Option<u32>,
+}
+
 pub trait FrameDataSlot<'a, T> {
     fn insert(self, value: T);
     fn get_or_insert_with(self, f: impl FnOnce() -> T) -> &'a mut T;
@@ -61,6 +96,14 @@ pub trait View {
     fn destroy(self, window: &mut Window);
 
     fn emu_state(&self) -> Self::EmuState;
+    /// Carries out the requests contained in an emulator-side state returned by `render` (such as
+    /// edits made through the view), once as it's received by the emulation thread; returns
+    /// whether emulation needs to be resumed for them.
+    fn apply_emu_state(_emu_state: &mut Self::EmuState, _emu: &mut Emu) -> bool {
+        false
+    }
+    /// Runs once for every frame the emulation thread produces, even while the view is hidden.
+    fn finish_frame(_emu_state: &Self::EmuState, _emu: &mut Emu) {}
     fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
         emu_state: &Self::EmuState,
         emu: &mut Emu,
@@ -78,6 +121,7 @@ pub trait View {
         ui: &imgui::Ui,
         window: &mut Window,
         emu_running: bool,
+        actions: &mut Actions,
     ) -> Option<Self::EmuState>;
 }
 
@@ -134,7 +178,10 @@ macro_rules! declare_structs {
                 }
             }
 
-            pub fn handle_message(&mut self, message: Message) {
+            /// Handles a message sent by the UI thread, returning whether emulation needs to be
+            /// resumed to carry out the requests it contains.
+            pub fn handle_message(&mut self, message: Message, emu: &mut Emu) -> bool {
+                let mut resume = false;
                 match message {
                     $(
                         Message::$s_toggle_updates_message_ident(enabled) => {
@@ -142,7 +189,10 @@ macro_rules! declare_structs {
                                 *view_enabled = enabled;
                             }
                         }
-                        Message::$s_update_emu_state_message_ident(emu_state) => {
+                        Message::$s_update_emu_state_message_ident(mut emu_state) => {
+                            if let Some((emu_state, _)) = &mut emu_state {
+                                resume = <$s_view_ty>::apply_emu_state(emu_state, emu);
+                            }
                             self.$s_view_ident = emu_state;
                         }
                     )*
@@ -153,7 +203,8 @@ macro_rules! declare_structs {
                             }
                         }
                         Message::$i_update_emu_state_message_ident(key, emu_state) => {
-                            if let Some(emu_state) = emu_state {
+                            if let Some(mut emu_state) = emu_state {
+                                resume = <$i_view_ty>::apply_emu_state(&mut emu_state.0, emu);
                                 self.$i_view_ident.insert(key, emu_state);
                             } else {
                                 self.$i_view_ident.remove(&key);
@@ -161,6 +212,21 @@ macro_rules! declare_structs {
                         }
                     )*
                 }
+                self.update_call_stack_tracking(emu);
+                resume
+            }
+
+            pub fn finish_frame(&self, emu: &mut Emu) {
+                $(
+                    if let Some((emu_state, _)) = &self.$s_view_ident {
+                        <$s_view_ty>::finish_frame(emu_state, emu);
+                    }
+                )*
+                $(
+                    for (emu_state, _) in self.$i_view_ident.values() {
+                        <$i_view_ty>::finish_frame(emu_state, emu);
+                    }
+                )*
             }
 
             pub fn prepare_frame_data(
@@ -332,6 +398,7 @@ macro_rules! declare_structs {
                 window: &mut Window,
                 emu_running: bool,
             ) -> impl Iterator<Item = Message> + 'a {
+                let mut actions = Actions::default();
                 $(
                     if let Some((view, visible)) = &mut self.$s_view_ident {
                         let mut opened = true;
@@ -342,7 +409,7 @@ macro_rules! declare_structs {
                             imgui::Window::new(<$s_view_ty>::NAME).opened(&mut opened)
                         ).build(ui, || {
                             *visible = true;
-                            new_emu_state = view.render(ui, window, emu_running);
+                            new_emu_state = view.render(ui, window, emu_running, &mut actions);
                         });
                         if let Some(new_emu_state) = new_emu_state {
                             self.messages.push(Message::$s_update_emu_state_message_ident(
@@ -372,7 +439,7 @@ macro_rules! declare_structs {
                                     .opened(&mut opened),
                             ).build(ui, || {
                                 *visible = true;
-                                new_emu_state = view.render(ui, window, emu_running);
+                                new_emu_state = view.render(ui, window, emu_running, &mut actions);
                             });
                             if let Some(new_emu_state) = new_emu_state {
                                 self.messages.push(Message::$i_update_emu_state_message_ident(
@@ -398,6 +465,7 @@ macro_rules! declare_structs {
                         view.destroy(window);
                     }
                 )*
+                self.handle_actions(actions, window);
                 self.messages.drain(..)
             }
         }
@@ -407,8 +475,74 @@ macro_rules! declare_structs {
 declare_structs!(
     singleton cpu_state, CpuState, ToggleCpuStateUpdates, UpdateCpuStateEmuState;
     singleton spc_state, SpcState, ToggleSpcStateUpdates, UpdateSpcStateEmuState;
+    singleton breakpoints, Breakpoints, ToggleBreakpointsUpdates, UpdateBreakpointsEmuState;
+    singleton call_stack, CallStack, ToggleCallStackUpdates, UpdateCallStackEmuState;
+    singleton profiler, Profiler, ToggleProfilerUpdates, UpdateProfilerEmuState;
+    singleton event_viewer, EventViewer, ToggleEventViewerUpdates, UpdateEventViewerEmuState;
+    singleton tile_viewer, TileViewer, ToggleTileViewerUpdates, UpdateTileViewerEmuState;
+    singleton tilemap_viewer, TilemapViewer, ToggleTilemapViewerUpdates, UpdateTilemapViewerEmuState;
+    singleton oam_viewer, OamViewer, ToggleOamViewerUpdates, UpdateOamViewerEmuState;
+    singleton palette_viewer, PaletteViewer, TogglePaletteViewerUpdates, UpdatePaletteViewerEmuState;
+    singleton ppu_state, PpuState, TogglePpuStateUpdates, UpdatePpuStateEmuState;
+    singleton dma_viewer, DmaViewer, ToggleDmaViewerUpdates, UpdateDmaViewerEmuState;
+    singleton window_viewer, WindowViewer, ToggleWindowViewerUpdates, UpdateWindowViewerEmuState;
+    singleton layer_viewer, LayerViewer, ToggleLayerViewerUpdates, UpdateLayerViewerEmuState;
     instanceable cpu_memory, CpuMemory, ToggleCpuMemoryUpdates, UpdateCpuMemoryEmuState;
     instanceable cpu_disasm, CpuDisasm, ToggleCpuDisasmUpdates, UpdateCpuDisasmEmuState;
     instanceable spc_memory, SpcMemory, ToggleSpcMemoryUpdates, UpdateSpcMemoryEmuState;
     instanceable spc_disasm, SpcDisasm, ToggleSpcDisasmUpdates, UpdateSpcDisasmEmuState;
+    instanceable ram_search, RamSearch, ToggleRamSearchUpdates, UpdateRamSearchEmuState;
 );
+
+impl EmuState {
+    /// Tracks the call stack only while a view that shows it is open, as it slows down emulation.
+    /// Tracking starts from an empty stack, so it will be incomplete until the game returns from
+    /// the routines it was running at that point.
+    pub fn update_call_stack_tracking(&self, emu: &mut Emu) {
+        let enabled = self.call_stack.is_some() || self.profiler.is_some();
+        if enabled != emu.debugger.call_stack.is_some() {
+            emu.debugger.call_stack = enabled.then(ness_core::debugger::call_stack::CallStack::new);
+        }
+    }
+}
+
+macro_rules! show_instanceable_view {
+    (
+        $self: expr,
+        $window: expr,
+        $view_ident: ident,
+        $view_ty: ty,
+        $update_emu_state_message_ident: ident
+    ) => {{
+        if $self.$view_ident.is_empty() {
+            let view = <$view_ty>::new($window);
+            let emu_state = view.emu_state();
+            $self.$view_ident.insert(0, (view, true));
+            $self
+                .messages
+                .push(Message::$update_emu_state_message_ident(
+                    0,
+                    Some((emu_state, true)),
+                ));
+        }
+        let key = *$self.$view_ident.keys().min().unwrap();
+        &mut $self.$view_ident.get_mut(&key).unwrap().0
+    }};
+}
+
+impl UiState {
+    fn handle_actions(&mut self, actions: Actions, window: &mut Window) {
+        if let Some(addr) = actions.show_cpu_memory {
+            show_instanceable_view!(self, window, cpu_memory, CpuMemory, UpdateCpuMemoryEmuState)
+                .show_addr(addr);
+        }
+        if let Some(addr) = actions.show_spc_memory {
+            show_instanceable_view!(self, window, spc_memory, SpcMemory, UpdateSpcMemoryEmuState)
+                .show_addr(addr);
+        }
+        if let Some(addr) = actions.show_cpu_disasm {
+            show_instanceable_view!(self, window, cpu_disasm, CpuDisasm, UpdateCpuDisasmEmuState)
+                .show_addr(addr);
+        }
+    }
+}
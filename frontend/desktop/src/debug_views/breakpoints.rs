@@ -0,0 +1,295 @@
+use super::{Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::StyleColor;
+use ness_core::{
+    debugger::{
+        breakpoints::{Breakpoint, Condition, Kinds},
+        StopReason,
+    },
+    emu::Emu,
+    symbols::SymbolTable,
+};
+use std::sync::Arc;
+
+static KINDS: &[(Kinds, &str, &str)] = &[
+    (Kinds::EXEC, "Exec", "X"),
+    (Kinds::READ, "Read", "R"),
+    (Kinds::WRITE, "Write", "W"),
+    (Kinds::DMA_SRC, "DMA src", "S"),
+    (Kinds::DMA_DST, "DMA dst", "D"),
+];
+
+fn parse_addr(input: &str, symbols: &SymbolTable) -> Option<u32> {
+    let input = input.trim();
+    symbols
+        .addr_of(input)
+        .or_else(|| u32::from_str_radix(input.trim_start_matches('$'), 16).ok())
+        .map(|addr| addr & 0xFF_FFFF)
+}
+
+fn addr_str(addr: u32, symbols: &SymbolTable) -> String {
+    match symbols.label(addr) {
+        Some(label) => format!("{} ({:06X})", label, addr),
+        None => format!("{:06X}", addr),
+    }
+}
+
+fn kinds_str(kinds: Kinds) -> String {
+    KINDS
+        .iter()
+        .map(|&(kind, _, short_name)| {
+            if kinds.contains(kind) {
+                short_name
+            } else {
+                "-"
+            }
+        })
+        .collect()
+}
+
+pub struct Breakpoints {
+    breakpoints: Option<Vec<Breakpoint>>,
+    stop_reason: Option<StopReason>,
+    symbols: Arc<SymbolTable>,
+    new_kinds: Kinds,
+    new_start_addr_input: String,
+    new_end_addr_input: String,
+    new_condition_input: String,
+    new_breakpoint_error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    breakpoints: Option<Vec<Breakpoint>>,
+}
+
+pub struct FrameData {
+    breakpoints: Vec<Breakpoint>,
+    stop_reason: Option<StopReason>,
+    symbols: Arc<SymbolTable>,
+}
+
+impl Breakpoints {
+    fn parse_new_breakpoint(&self) -> Result<Breakpoint, String> {
+        let start_addr = parse_addr(&self.new_start_addr_input, &self.symbols)
+            .ok_or_else(|| "Invalid start address".to_string())?;
+        let end_addr = if self.new_end_addr_input.trim().is_empty() {
+            start_addr
+        } else {
+            parse_addr(&self.new_end_addr_input, &self.symbols)
+                .ok_or_else(|| "Invalid end address".to_string())?
+        };
+        if end_addr < start_addr {
+            return Err("The end address must not be lower than the start address".to_string());
+        }
+        if self.new_kinds.is_empty() {
+            return Err("No breakpoint kinds selected".to_string());
+        }
+        let condition = if self.new_condition_input.trim().is_empty() {
+            None
+        } else {
+            Some(Condition::parse(&self.new_condition_input).map_err(|err| err.to_string())?)
+        };
+        Ok(Breakpoint {
+            kinds: self.new_kinds,
+            start_addr,
+            end_addr,
+            condition,
+            enabled: true,
+        })
+    }
+}
+
+impl View for Breakpoints {
+    const NAME: &'static str = "Breakpoints";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        Breakpoints {
+            breakpoints: None,
+            stop_reason: None,
+            symbols: Arc::new(SymbolTable::new()),
+            new_kinds: Kinds::EXEC,
+            new_start_addr_input: String::new(),
+            new_end_addr_input: String::new(),
+            new_condition_input: String::new(),
+            new_breakpoint_error: None,
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        // Until the view has received the emulator's breakpoints once, keep them unchanged
+        if let Some(breakpoints) = &emu_state.breakpoints {
+            if emu.debugger.breakpoints.list() != &breakpoints[..] {
+                emu.debugger.breakpoints.set_all(breakpoints.clone());
+            }
+        }
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            breakpoints: Vec::new(),
+            stop_reason: None,
+            symbols: Arc::clone(&emu.cpu.symbols),
+        });
+        frame_data.breakpoints.clear();
+        frame_data
+            .breakpoints
+            .extend_from_slice(emu.debugger.breakpoints.list());
+        frame_data.stop_reason = emu.debugger.stop_reason();
+        frame_data.symbols = Arc::clone(&emu.cpu.symbols);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        if self.breakpoints.is_none() {
+            self.breakpoints = Some(frame_data.breakpoints.clone());
+        }
+        self.stop_reason = frame_data.stop_reason;
+        self.symbols = Arc::clone(&frame_data.symbols);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let mut emu_state_changed = false;
+
+        let _mono_font = ui.push_font(window.mono_font);
+
+        match self.stop_reason {
+            Some(StopReason::Breakpoint(hit)) => {
+                let mut text = format!(
+                    "Stopped at breakpoint {} ({} @ {:06X}",
+                    hit.index,
+                    kinds_str(hit.kind),
+                    hit.addr,
+                );
+                if let Some(value) = hit.value {
+                    text += &format!(": {:02X}", value);
+                }
+                text.push(')');
+                ui.text_colored(ui.style_color(StyleColor::PlotLinesHovered), &text);
+            }
+            Some(StopReason::StepFinished) => ui.text("Stopped after a step"),
+            None => ui.text_disabled("Not stopped"),
+        }
+
+        ui.separator();
+
+        let breakpoints = match &mut self.breakpoints {
+            Some(breakpoints) => breakpoints,
+            None => return None,
+        };
+
+        let mut remove = None;
+        for (i, breakpoint) in breakpoints.iter_mut().enumerate() {
+            let _id = ui.push_id(i as i32);
+            emu_state_changed |= ui.checkbox("##enabled", &mut breakpoint.enabled);
+            ui.same_line();
+            let mut text = format!("{}: {} ", i, kinds_str(breakpoint.kinds));
+            if breakpoint.start_addr == breakpoint.end_addr {
+                text += &addr_str(breakpoint.start_addr, &self.symbols);
+            } else {
+                text += &format!(
+                    "{}-{}",
+                    addr_str(breakpoint.start_addr, &self.symbols),
+                    addr_str(breakpoint.end_addr, &self.symbols)
+                );
+            }
+            ui.text(&text);
+            if let Some(condition) = &breakpoint.condition {
+                ui.same_line();
+                ui.text_disabled(&format!("if {}", condition));
+            }
+            ui.same_line();
+            if ui.small_button("Remove") {
+                remove = Some(i);
+            }
+        }
+        if let Some(i) = remove {
+            breakpoints.remove(i);
+            emu_state_changed = true;
+        }
+
+        ui.separator();
+
+        for &(kind, name, _) in KINDS {
+            let mut enabled = self.new_kinds.contains(kind);
+            if ui.checkbox(name, &mut enabled) {
+                self.new_kinds.set(kind, enabled);
+            }
+            ui.same_line();
+        }
+        ui.new_line();
+
+        let addr_width =
+            ui.calc_text_size("0000000000")[0] + ui.clone_style().frame_padding[0] * 2.0;
+        ui.set_next_item_width(addr_width);
+        ui.input_text("##start", &mut self.new_start_addr_input)
+            .hint("Start")
+            .build();
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Address or label");
+        }
+        ui.same_line();
+        ui.text("-");
+        ui.same_line();
+        ui.set_next_item_width(addr_width);
+        ui.input_text("##end", &mut self.new_end_addr_input)
+            .hint("End")
+            .build();
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Address or label");
+        }
+        ui.same_line();
+        ui.set_next_item_width(-1.0);
+        ui.input_text("##condition", &mut self.new_condition_input)
+            .hint("Condition (i.e. a == $10, [$7E0010].w >= 100)")
+            .build();
+
+        if ui.button("Add") {
+            match self.parse_new_breakpoint() {
+                Ok(breakpoint) => {
+                    self.breakpoints
+                        .get_or_insert_with(Vec::new)
+                        .push(breakpoint);
+                    self.new_breakpoint_error = None;
+                    emu_state_changed = true;
+                }
+                Err(err) => self.new_breakpoint_error = Some(err),
+            }
+        }
+        if let Some(err) = &self.new_breakpoint_error {
+            ui.same_line();
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], err);
+        }
+
+        if emu_state_changed {
+            Some(self.emu_state())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::{ChildWindow, Selectable};
+use ness_core::{
+    debugger::call_stack::{Frame, FrameKind},
+    emu::Emu,
+    symbols::SymbolTable,
+};
+use std::sync::Arc;
+
+fn kind_str(kind: FrameKind) -> &'static str {
+    match kind {
+        FrameKind::Jsr => "JSR",
+        FrameKind::Jsl => "JSL",
+        FrameKind::Nmi => "NMI",
+        FrameKind::Irq => "IRQ",
+        FrameKind::Brk => "BRK",
+        FrameKind::Cop => "COP",
+    }
+}
+
+pub fn addr_str(addr: u32, symbols: &SymbolTable) -> String {
+    match symbols.label(addr) {
+        Some(label) => format!("{:06X} {}", addr, label),
+        None => format!("{:06X}", addr),
+    }
+}
+
+pub struct CallStack {
+    frames: Vec<Frame>,
+    pc: u32,
+    symbols: Arc<SymbolTable>,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    frames: Vec<Frame>,
+    pc: u32,
+    symbols: Arc<SymbolTable>,
+}
+
+impl View for CallStack {
+    const NAME: &'static str = "Call stack";
+
+    type FrameData = FrameData;
+    type EmuState = ();
+
+    fn new(_window: &mut Window) -> Self {
+        CallStack {
+            frames: Vec::new(),
+            pc: 0,
+            symbols: Arc::new(SymbolTable::new()),
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {}
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let call_stack = match &emu.debugger.call_stack {
+            Some(call_stack) => call_stack,
+            None => return,
+        };
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            frames: Vec::new(),
+            pc: 0,
+            symbols: Arc::clone(&emu.cpu.symbols),
+        });
+        frame_data.frames.clear();
+        frame_data.frames.extend_from_slice(call_stack.frames());
+        frame_data.pc = emu.cpu.regs.pc as u32 | (emu.cpu.regs.code_bank() as u32) << 16;
+        frame_data.symbols = Arc::clone(&emu.cpu.symbols);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.frames.clear();
+        self.frames.extend_from_slice(&frame_data.frames);
+        self.pc = frame_data.pc;
+        self.symbols = Arc::clone(&frame_data.symbols);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let _mono_font = ui.push_font(window.mono_font);
+
+        ChildWindow::new("##frames")
+            .movable(false)
+            .size([0.0, 0.0])
+            .build(ui, || {
+                if Selectable::new(&format!("PC  {}", addr_str(self.pc, &self.symbols))).build(ui) {
+                    actions.show_cpu_disasm = Some(self.pc);
+                }
+                for (i, frame) in self.frames.iter().enumerate().rev() {
+                    let _id = ui.push_id(i as i32);
+                    if Selectable::new(&format!(
+                        "{} {} (from {:06X})",
+                        kind_str(frame.kind),
+                        addr_str(frame.entry, &self.symbols),
+                        frame.call_site,
+                    ))
+                    .build(ui)
+                    {
+                        actions.show_cpu_disasm = Some(frame.entry);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text("Click to show in the disassembly view");
+                    }
+                }
+                if self.frames.is_empty() {
+                    ui.text_disabled("No routines entered since tracking started");
+                }
+            });
+
+        None
+    }
+}
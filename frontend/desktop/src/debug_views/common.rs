@@ -1,2 +1,4 @@
+pub mod image;
 pub mod memory;
 pub mod regs;
+pub mod step;
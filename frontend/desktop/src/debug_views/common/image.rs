@@ -0,0 +1,82 @@
+use crate::ui::{imgui_wgpu, window::Window};
+use core::num::NonZeroU32;
+use ness_core::ppu::rgba8_from_rgb5;
+use ness_video_filters::encode_png;
+use rfd::FileDialog;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Converts a BGR555 color, as stored in CGRAM, to RGBA8.
+pub fn rgb5_to_rgba8(color: u16) -> [u8; 4] {
+    rgba8_from_rgb5(color as u32, 16).to_le_bytes()
+}
+
+/// Creates an RGBA8 texture for the debug views to draw into, using nearest neighbor filtering so
+/// that pixels stay sharp when zooming in.
+pub fn create_texture(
+    window: &mut Window,
+    label: &str,
+    width: u32,
+    height: u32,
+) -> imgui::TextureId {
+    let texture = window.gfx.imgui.create_texture(
+        &window.gfx.device_state.device,
+        &wgpu::SamplerDescriptor {
+            label: Some(label),
+            ..Default::default()
+        },
+        imgui_wgpu::TextureDescriptor {
+            label: Some(label.to_string()),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            format: Some(
+                if window.gfx.device_state.surf_config.format.describe().srgb {
+                    wgpu::TextureFormat::Rgba8UnormSrgb
+                } else {
+                    wgpu::TextureFormat::Rgba8Unorm
+                },
+            ),
+            ..Default::default()
+        },
+    );
+    window.gfx.imgui.add_texture(texture)
+}
+
+/// Uploads the first `height` rows of `data` to the given texture; each row must span the whole
+/// width of the texture.
+pub fn set_texture_rows(window: &mut Window, id: imgui::TextureId, data: &[u8], height: u32) {
+    window.gfx.imgui.texture_mut(id).set_data(
+        &window.gfx.device_state.queue,
+        data,
+        imgui_wgpu::TextureRange {
+            height: NonZeroU32::new(height),
+            ..imgui_wgpu::TextureRange::default()
+        },
+    );
+}
+
+/// Asks the user for a path to export a PNG image to.
+pub fn pick_png_path(default_name: &str) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("PNG image", &["png"])
+        .set_file_name(default_name)
+        .save_file()
+}
+
+/// Writes a `width` by `height` image, stored in rows of `stride` pixels, to `path` as a PNG file.
+pub fn save_png(
+    path: &Path,
+    pixels: &[u32],
+    stride: usize,
+    width: usize,
+    height: usize,
+) -> Result<(), String> {
+    let data = encode_png(pixels, stride, width, height)
+        .map_err(|err| format!("Couldn't encode PNG image: {}", err))?;
+    fs::write(path, data).map_err(|err| format!("Couldn't write file: {}", err))
+}
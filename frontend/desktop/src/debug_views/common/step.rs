@@ -0,0 +1,111 @@
+use imgui::{Ui, WindowFocusedFlags};
+use ness_core::{
+    debugger::{step::Step, Debugger},
+    emu::Emu,
+};
+use winit::event::VirtualKeyCode;
+
+fn hotkey_pressed(ui: &Ui, key: VirtualKeyCode, shift: bool) -> bool {
+    // imgui-winit-support maps key indices to winit's virtual key codes
+    ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS)
+        && !ui.is_any_item_active()
+        && ui.io().key_shift == shift
+        && ui.is_key_index_pressed(key as i32)
+}
+
+fn step_button(ui: &Ui, label: &str, tooltip: &str, enabled: bool) -> bool {
+    let clicked = ui.button(label);
+    if ui.is_item_hovered() {
+        ui.tooltip_text(tooltip);
+    }
+    clicked && enabled
+}
+
+pub struct CpuStepControls {
+    target_v: i32,
+    target_h: i32,
+}
+
+impl CpuStepControls {
+    pub fn new() -> Self {
+        CpuStepControls {
+            target_v: 0,
+            target_h: 0,
+        }
+    }
+
+    /// Renders the CPU step buttons, returning the step that was requested, if any.
+    pub fn render(&mut self, ui: &Ui, emu_running: bool) -> Option<Step> {
+        let mut step = None;
+        if step_button(ui, "Into", "Step into (F11)", emu_running)
+            || (emu_running && hotkey_pressed(ui, VirtualKeyCode::F11, false))
+        {
+            step = Some(Step::CpuInstr);
+        }
+        ui.same_line();
+        if step_button(ui, "Over", "Step over (F10)", emu_running)
+            || (emu_running && hotkey_pressed(ui, VirtualKeyCode::F10, false))
+        {
+            step = Some(Step::CpuOver);
+        }
+        ui.same_line();
+        if step_button(ui, "Out", "Step out (Shift+F11)", emu_running)
+            || (emu_running && hotkey_pressed(ui, VirtualKeyCode::F11, true))
+        {
+            step = Some(Step::CpuOut);
+        }
+
+        let input_width = ui.calc_text_size("000")[0] + ui.clone_style().frame_padding[0] * 2.0;
+        ui.align_text_to_frame_padding();
+        ui.text("V:");
+        ui.same_line();
+        ui.set_next_item_width(input_width);
+        ui.input_int("##target_v", &mut self.target_v)
+            .step(0)
+            .build();
+        ui.same_line();
+        ui.text("H:");
+        ui.same_line();
+        ui.set_next_item_width(input_width);
+        ui.input_int("##target_h", &mut self.target_h)
+            .step(0)
+            .build();
+        self.target_v = self.target_v.clamp(0, 311);
+        self.target_h = self.target_h.clamp(0, 340);
+        ui.same_line();
+        if step_button(
+            ui,
+            "Run to",
+            "Run until the PPU reaches the given scanline and dot",
+            emu_running,
+        ) {
+            step = Some(Step::ToPosition {
+                v: self.target_v as u16,
+                h: self.target_h as u16,
+            });
+        }
+        step
+    }
+}
+
+/// Renders the SPC700 step button, returning the step that was requested, if any.
+pub fn spc_step_controls(ui: &Ui, emu_running: bool) -> Option<Step> {
+    if step_button(ui, "Step", "Step one SPC700 instruction (F11)", emu_running)
+        || (emu_running && hotkey_pressed(ui, VirtualKeyCode::F11, false))
+    {
+        Some(Step::SpcInstr)
+    } else {
+        None
+    }
+}
+
+/// Starts the step sent along with a view's emulator-side state, if any, returning whether one
+/// was started (and so emulation needs to be resumed).
+pub fn start_requested_step(step: &mut Option<Step>, emu: &mut Emu) -> bool {
+    if let Some(step) = step.take() {
+        Debugger::start_step(emu, step);
+        true
+    } else {
+        false
+    }
+}
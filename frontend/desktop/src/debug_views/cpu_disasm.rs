@@ -1,10 +1,16 @@
-use super::{FrameDataSlot, View};
+use super::{
+    common::step::{start_requested_step, CpuStepControls},
+    Actions, FrameDataSlot, View,
+};
 use crate::ui::window::Window;
 use imgui::{ChildWindow, StyleColor};
 use ness_core::{
     cpu::disasm::{disassemble_count_with_emu_state, Instr},
+    debugger::step::Step,
     emu::Emu,
+    symbols::SymbolTable,
 };
+use std::sync::Arc;
 
 pub struct CpuDisasm {
     start_addr_input: String,
@@ -13,18 +19,29 @@ pub struct CpuDisasm {
     lines: u16,
     pc: u32,
     instrs: Vec<Instr>,
+    symbols: Arc<SymbolTable>,
+    step_controls: CpuStepControls,
+}
+
+impl CpuDisasm {
+    pub fn show_addr(&mut self, addr: u32) {
+        self.start_addr = addr & 0xFF_FFFF;
+        self.start_addr_changed = true;
+    }
 }
 
 #[derive(Clone)]
 pub struct EmuState {
     start_addr: u32,
     lines: u16,
+    step: Option<Step>,
 }
 
 #[derive(Clone)]
 pub struct FrameData {
     pc: u32,
     instrs: Vec<Instr>,
+    symbols: Arc<SymbolTable>,
 }
 
 impl View for CpuDisasm {
@@ -41,6 +58,8 @@ impl View for CpuDisasm {
             lines: 32,
             pc: 0,
             instrs: Vec::new(),
+            symbols: Arc::new(SymbolTable::new()),
+            step_controls: CpuStepControls::new(),
         }
     }
 
@@ -50,9 +69,14 @@ impl View for CpuDisasm {
         EmuState {
             start_addr: 0,
             lines: 32,
+            step: None,
         }
     }
 
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        start_requested_step(&mut emu_state.step, emu)
+    }
+
     fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
         emu_state: &Self::EmuState,
         emu: &mut Emu,
@@ -61,7 +85,9 @@ impl View for CpuDisasm {
         let frame_data = frame_data.get_or_insert_with(|| FrameData {
             pc: 0,
             instrs: Vec::new(),
+            symbols: Arc::clone(&emu.cpu.symbols),
         });
+        frame_data.symbols = Arc::clone(&emu.cpu.symbols);
         frame_data.pc = emu.cpu.regs.pc as u32 | (emu.cpu.regs.code_bank() as u32) << 16;
         frame_data.instrs.clear();
         disassemble_count_with_emu_state(
@@ -76,6 +102,7 @@ impl View for CpuDisasm {
         self.pc = frame_data.pc;
         self.instrs.clear();
         self.instrs.extend_from_slice(&frame_data.instrs);
+        self.symbols = Arc::clone(&frame_data.symbols);
     }
 
     fn customize_window<'a, T: AsRef<str>>(
@@ -90,12 +117,17 @@ impl View for CpuDisasm {
         &mut self,
         ui: &imgui::Ui,
         window: &mut Window,
-        _emu_running: bool,
+        emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
         let mut emu_state_changed = false;
 
         let _mono_font = ui.push_font(window.mono_font);
         let style = ui.clone_style();
+
+        let step = self.step_controls.render(ui, emu_running);
+        ui.separator();
+
         ui.align_text_to_frame_padding();
 
         if ui.button("Disassemble at PC") {
@@ -105,20 +137,27 @@ impl View for CpuDisasm {
 
         ui.same_line();
 
-        ui.set_next_item_width(ui.calc_text_size("000000")[0] + style.frame_padding[0] * 2.0);
+        ui.set_next_item_width(ui.calc_text_size("0000000000")[0] + style.frame_padding[0] * 2.0);
         if ui
             .input_text("##address", &mut self.start_addr_input)
             .auto_select_all(true)
-            .chars_hexadecimal(true)
             .enter_returns_true(true)
-            .no_horizontal_scroll(true)
             .build()
         {
-            if let Ok(addr) = u32::from_str_radix(&self.start_addr_input, 16) {
+            // Accept either a hexadecimal address or a label name
+            let input = self.start_addr_input.trim();
+            if let Some(addr) = self
+                .symbols
+                .addr_of(input)
+                .or_else(|| u32::from_str_radix(input.trim_start_matches('$'), 16).ok())
+            {
                 self.start_addr = addr.clamp(0, 0xFF_FFFF);
                 self.start_addr_changed = true;
             }
         }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Address or label");
+        }
 
         ui.same_line();
 
@@ -165,6 +204,12 @@ impl View for CpuDisasm {
                         comments += &instr.comment;
                     }
 
+                    if let Some(label) = &instr.label {
+                        ui.text_colored(
+                            ui.style_color(StyleColor::PlotLinesHovered),
+                            &format!("{}:", label),
+                        );
+                    }
                     ui.text(&format!("{:06X}: {}", instr.addr, instr.opcode));
                     if !comments.is_empty() {
                         ui.same_line_with_spacing(0.0, 0.0);
@@ -173,10 +218,11 @@ impl View for CpuDisasm {
                 }
             });
 
-        if emu_state_changed {
+        if emu_state_changed || step.is_some() {
             Some(EmuState {
                 start_addr: self.start_addr,
                 lines: self.lines,
+                step,
             })
         } else {
             None
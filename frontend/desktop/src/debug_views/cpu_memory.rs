@@ -1,6 +1,6 @@
 use super::{
     common::memory::{MemoryEditor, RangeInclusive},
-    FrameDataSlot, View,
+    Actions, FrameDataSlot, View,
 };
 use crate::ui::window::Window;
 use ness_core::{cpu::bus, emu::Emu};
@@ -22,6 +22,12 @@ pub struct MemContents {
     data: Vec<u8>,
 }
 
+impl CpuMemory {
+    pub fn show_addr(&mut self, addr: u32) {
+        self.editor.set_selected_addr(addr as u64);
+    }
+}
+
 impl View for CpuMemory {
     const NAME: &'static str = "CPU memory";
 
@@ -90,6 +96,7 @@ impl View for CpuMemory {
         ui: &imgui::Ui,
         window: &mut Window,
         _emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
         let _mono_font = ui.push_font(window.mono_font);
 
@@ -1,10 +1,13 @@
 use super::{
-    common::regs::{bitfield, regs, BitfieldCommand, MaxWidth, RegCommand, RegValue},
-    FrameDataSlot, View,
+    common::{
+        regs::{bitfield, regs, BitfieldCommand, MaxWidth, RegCommand, RegValue},
+        step::{start_requested_step, CpuStepControls},
+    },
+    Actions, FrameDataSlot, View,
 };
 use crate::ui::window::Window;
 use imgui::StyleVar;
-use ness_core::{cpu::regs::Psw, emu::Emu};
+use ness_core::{cpu::regs::Psw, debugger::step::Step, emu::Emu};
 
 #[derive(Clone, Debug)]
 pub struct RegValues {
@@ -22,21 +25,31 @@ pub struct RegValues {
 
 pub struct CpuState {
     reg_values: Option<RegValues>,
+    step_controls: CpuStepControls,
 }
 
 impl View for CpuState {
     const NAME: &'static str = "CPU state";
 
     type FrameData = RegValues;
-    type EmuState = ();
+    type EmuState = Option<Step>;
 
     fn new(_window: &mut Window) -> Self {
-        CpuState { reg_values: None }
+        CpuState {
+            reg_values: None,
+            step_controls: CpuStepControls::new(),
+        }
     }
 
     fn destroy(self, _window: &mut Window) {}
 
-    fn emu_state(&self) -> Self::EmuState {}
+    fn emu_state(&self) -> Self::EmuState {
+        None
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        start_requested_step(emu_state, emu)
+    }
 
     fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
         _emu_state: &Self::EmuState,
@@ -73,8 +86,12 @@ impl View for CpuState {
         &mut self,
         ui: &imgui::Ui,
         window: &mut Window,
-        _emu_running: bool,
+        emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
+        let step = self.step_controls.render(ui, emu_running);
+        ui.separator();
+
         if let Some(reg_values) = self.reg_values.as_mut() {
             let _mono_font = ui.push_font(window.mono_font);
             let _frame_rounding = ui.push_style_var(StyleVar::FrameRounding(0.0));
@@ -157,6 +174,6 @@ impl View for CpuState {
                 ],
             );
         }
-        None
+        step.map(Some)
     }
 }
@@ -0,0 +1,380 @@
+use super::{Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::{ChildWindow, Selectable};
+use ness_core::{
+    cpu::{bus, dma::Channel},
+    emu::Emu,
+};
+
+static TRANSFER_UNITS: [(&[u8], &str); 8] = [
+    (&[0], "p"),
+    (&[0, 1], "p, p+1"),
+    (&[0, 0], "p, p"),
+    (&[0, 0, 1, 1], "p, p, p+1, p+1"),
+    (&[0, 1, 2, 3], "p, p+1, p+2, p+3"),
+    (&[0, 1, 0, 1], "p, p+1, p, p+1"),
+    (&[0, 0], "p, p"),
+    (&[0, 0, 1, 1], "p, p, p+1, p+1"),
+];
+
+#[derive(Clone, Copy, Debug)]
+struct HdmaLine {
+    line: u16,
+    entry_addr: u32,
+    source_addr: u32,
+    values: Option<[u8; 4]>,
+}
+
+fn read_and_advance(emu: &mut Emu, bank: u32, addr: &mut u16) -> u8 {
+    let value = bus::read::<bus::DebugCpuAccess>(emu, bank | *addr as u32);
+    *addr = addr.wrapping_add(1);
+    value
+}
+
+/// Decodes the HDMA table of `channel` from its start address, producing one entry per scanline
+/// until either the table ends or `lines` scanlines have been covered.
+fn decode_hdma_table(emu: &mut Emu, channel: &Channel, lines: u16, result: &mut Vec<HdmaLine>) {
+    result.clear();
+    let control = channel.control();
+    let unit_len = TRANSFER_UNITS[control.transfer_unit() as usize].0.len();
+    let table_bank = (channel.gp_a_bank_h_table_bank as u32) << 16;
+    let mut table_addr = channel.gp_a_addr_h_table_start_addr;
+
+    let mut line = 0;
+    while line < lines {
+        let entry_addr = table_bank | table_addr as u32;
+        let line_counter = read_and_advance(emu, table_bank, &mut table_addr);
+        if line_counter == 0 {
+            break;
+        }
+        let (data_bank, mut data_addr) = if control.h_indirect() {
+            let low = read_and_advance(emu, table_bank, &mut table_addr);
+            let high = read_and_advance(emu, table_bank, &mut table_addr);
+            (
+                (channel.h_indirect_bank as u32) << 16,
+                (high as u16) << 8 | low as u16,
+            )
+        } else {
+            (table_bank, table_addr)
+        };
+
+        let repeat = line_counter & 0x80 != 0;
+        let entry_lines = match line_counter & 0x7F {
+            0 => 128,
+            count => count as u16,
+        };
+        for i in 0..entry_lines.min(lines - line) {
+            let source_addr = data_bank | data_addr as u32;
+            let values = (i == 0 || repeat).then(|| {
+                let mut values = [0; 4];
+                for value in &mut values[..unit_len] {
+                    *value = read_and_advance(emu, data_bank, &mut data_addr);
+                }
+                values
+            });
+            result.push(HdmaLine {
+                line,
+                entry_addr,
+                source_addr,
+                values,
+            });
+            line += 1;
+        }
+
+        if !control.h_indirect() {
+            table_addr = data_addr;
+        }
+    }
+}
+
+pub struct DmaViewer {
+    data: Option<FrameData>,
+    selected: usize,
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    disabled_channels: Option<u8>,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    channels: [Channel; 8],
+    gp_requested: u8,
+    h_enabled: u8,
+    h_frame_enabled: u8,
+    disabled_channels: u8,
+    v_counter: u16,
+    hdma_tables: [Vec<HdmaLine>; 8],
+}
+
+impl DmaViewer {
+    /// Returns the new mask of disabled channels if any were toggled.
+    fn render_channels(&mut self, ui: &imgui::Ui, emu_running: bool) -> Option<u8> {
+        let data = match &mut self.data {
+            Some(data) => data,
+            None => return None,
+        };
+        let mut disabled_channels = None;
+        ui.columns(9, "##channels", true);
+        for name in [
+            "Ch",
+            "On",
+            "Mode",
+            "Dir",
+            "Unit",
+            "B addr",
+            "A addr",
+            "Count/Ind",
+            "HDMA table",
+        ] {
+            ui.text(name);
+            ui.next_column();
+        }
+        ui.separator();
+        for (i, channel) in data.channels.iter().enumerate() {
+            let _id = ui.push_id(i as i32);
+            let control = channel.control();
+            if Selectable::new(&i.to_string())
+                .selected(self.selected == i)
+                .build(ui)
+            {
+                self.selected = i;
+            }
+            ui.next_column();
+
+            let mut enabled = data.disabled_channels & 1 << i == 0;
+            if ui.checkbox("##enabled", &mut enabled) && emu_running {
+                data.disabled_channels ^= 1 << i;
+                disabled_channels = Some(data.disabled_channels);
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Disabled channels skip all their transfers");
+            }
+            ui.next_column();
+
+            let mode = if data.h_enabled & 1 << i != 0 {
+                if control.h_indirect() {
+                    "HDMA (ind)"
+                } else {
+                    "HDMA"
+                }
+            } else if data.gp_requested & 1 << i != 0 {
+                "DMA"
+            } else {
+                "-"
+            };
+            ui.text(mode);
+            ui.next_column();
+            ui.text(if control.direction() { "B->A" } else { "A->B" });
+            ui.next_column();
+            ui.text(&control.transfer_unit().to_string());
+            if ui.is_item_hovered() {
+                ui.tooltip_text(TRANSFER_UNITS[control.transfer_unit() as usize].1);
+            }
+            ui.next_column();
+            ui.text(&format!("$21{:02X}", channel.b_addr));
+            ui.next_column();
+            ui.text(&format!(
+                "{:02X}:{:04X} {}",
+                channel.gp_a_bank_h_table_bank,
+                channel.gp_a_addr_h_table_start_addr,
+                match channel.gp_addr_step() {
+                    1 => "+",
+                    -1 => "-",
+                    _ => " ",
+                }
+            ));
+            ui.next_column();
+            ui.text(&format!(
+                "{:02X}:{:04X}",
+                channel.h_indirect_bank, channel.gp_byte_counter_h_indirect_addr,
+            ));
+            if ui.is_item_hovered() {
+                ui.tooltip_text(
+                    "DMA byte count (0000 means 65536 bytes) or HDMA indirect address and bank",
+                );
+            }
+            ui.next_column();
+            ui.text(&format!(
+                "{:04X} {:02X}{}",
+                channel.h_cur_table_addr,
+                channel.h_line_counter(),
+                if data.h_enabled & 1 << i == 0 {
+                    ""
+                } else if data.h_frame_enabled & 1 << i == 0 {
+                    " (done)"
+                } else if channel.h_do_transfer() {
+                    " (xfer)"
+                } else {
+                    ""
+                },
+            ));
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Current table address and line counter");
+            }
+            ui.next_column();
+        }
+        ui.columns(1, "##channels", false);
+        disabled_channels
+    }
+
+    fn render_hdma_table(&self, ui: &imgui::Ui, data: &FrameData) {
+        let channel = &data.channels[self.selected];
+        let table = &data.hdma_tables[self.selected];
+        if data.h_enabled & 1 << self.selected == 0 {
+            ui.text_disabled("HDMA is not enabled for this channel");
+            return;
+        }
+        if table.is_empty() {
+            ui.text_disabled("The HDMA table is empty");
+            return;
+        }
+
+        let b_offsets = TRANSFER_UNITS[channel.control().transfer_unit() as usize].0;
+        ChildWindow::new("##hdma_table")
+            .size([0.0, 0.0])
+            .build(ui, || {
+                ui.columns(4, "##hdma_columns", true);
+                for name in ["Line", "Entry", "Source", "Writes"] {
+                    ui.text(name);
+                    ui.next_column();
+                }
+                ui.separator();
+                for entry in table {
+                    let color = if entry.line == data.v_counter {
+                        [1.0, 1.0, 0.4, 1.0]
+                    } else if entry.values.is_some() {
+                        [1.0; 4]
+                    } else {
+                        [0.5, 0.5, 0.5, 1.0]
+                    };
+                    ui.text_colored(color, &entry.line.to_string());
+                    ui.next_column();
+                    ui.text_colored(color, &format!("{:06X}", entry.entry_addr));
+                    ui.next_column();
+                    ui.text_colored(color, &format!("{:06X}", entry.source_addr));
+                    ui.next_column();
+                    match entry.values {
+                        Some(values) => {
+                            let writes = b_offsets
+                                .iter()
+                                .zip(values)
+                                .map(|(offset, value)| {
+                                    format!(
+                                        "$21{:02X}={:02X}",
+                                        channel.b_addr.wrapping_add(*offset),
+                                        value
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                                .join(" ");
+                            ui.text_colored(color, &writes);
+                        }
+                        None => ui.text_colored(color, "-"),
+                    }
+                    ui.next_column();
+                }
+                ui.columns(1, "##hdma_columns", false);
+            });
+    }
+}
+
+impl View for DmaViewer {
+    const NAME: &'static str = "DMA channels";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        DmaViewer {
+            data: None,
+            selected: 0,
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState {
+            disabled_channels: None,
+        }
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        if let Some(disabled_channels) = emu_state.disabled_channels.take() {
+            emu.cpu.dmac.disabled_channels = disabled_channels;
+        }
+        false
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let dmac = &emu.cpu.dmac;
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            channels: dmac.channels,
+            gp_requested: 0,
+            h_enabled: 0,
+            h_frame_enabled: 0,
+            disabled_channels: 0,
+            v_counter: 0,
+            hdma_tables: Default::default(),
+        });
+        frame_data.channels = dmac.channels;
+        frame_data.gp_requested = dmac.gp_requested();
+        frame_data.h_enabled = dmac.h_enabled();
+        frame_data.h_frame_enabled = dmac.h_frame_enabled();
+        frame_data.disabled_channels = dmac.disabled_channels;
+        frame_data.v_counter = emu.ppu.counters.v_counter();
+
+        let lines = emu.ppu.counters.v_display_end();
+        for (i, table) in frame_data.hdma_tables.iter_mut().enumerate() {
+            if frame_data.h_enabled & 1 << i == 0 {
+                table.clear();
+                continue;
+            }
+            decode_hdma_table(emu, &frame_data.channels[i], lines, table);
+        }
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.data = Some(frame_data.clone());
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        if self.data.is_none() {
+            ui.text_disabled("No data available");
+            return None;
+        }
+
+        let _mono_font = ui.push_font(window.mono_font);
+        let disabled_channels = self.render_channels(ui, emu_running);
+        ui.separator();
+
+        if let Some(data) = &self.data {
+            ui.text(&format!("HDMA table for channel {}:", self.selected));
+            self.render_hdma_table(ui, data);
+        }
+
+        disabled_channels.map(|disabled_channels| EmuState {
+            disabled_channels: Some(disabled_channels),
+        })
+    }
+}
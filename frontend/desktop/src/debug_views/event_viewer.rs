@@ -0,0 +1,253 @@
+use super::{Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use ness_core::{
+    debugger::events::{self, Event, EventKind},
+    emu::Emu,
+};
+
+const DOTS_PER_LINE: u16 = 341;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Category {
+    BBusWrite,
+    CpuRegWrite,
+    Hdma,
+    Dma,
+    Interrupt,
+}
+
+static CATEGORIES: &[(Category, &str, [f32; 4])] = &[
+    (Category::BBusWrite, "$21xx writes", [0.3, 0.8, 1.0, 1.0]),
+    (Category::CpuRegWrite, "$4xxx writes", [1.0, 0.8, 0.2, 1.0]),
+    (Category::Hdma, "HDMA", [0.4, 1.0, 0.4, 1.0]),
+    (Category::Dma, "DMA", [1.0, 0.4, 1.0, 1.0]),
+    (Category::Interrupt, "NMI/IRQ", [1.0, 0.3, 0.3, 1.0]),
+];
+
+fn category(kind: &EventKind) -> Category {
+    match kind {
+        EventKind::BBusWrite { .. } => Category::BBusWrite,
+        EventKind::CpuRegWrite { .. } => Category::CpuRegWrite,
+        EventKind::Hdma { .. } => Category::Hdma,
+        EventKind::Dma { .. } => Category::Dma,
+        EventKind::Nmi | EventKind::Irq => Category::Interrupt,
+    }
+}
+
+fn describe(event: &Event) -> String {
+    let kind = match event.kind {
+        EventKind::BBusWrite { addr, value } => format!("Write $21{:02X} = ${:02X}", addr, value),
+        EventKind::CpuRegWrite { addr, value } => format!("Write ${:04X} = ${:02X}", addr, value),
+        EventKind::Hdma { channel, b_addr } => {
+            format!("HDMA channel {} -> $21{:02X}", channel, b_addr)
+        }
+        EventKind::Dma {
+            channel,
+            b_addr,
+            end_v,
+            end_h,
+        } => format!(
+            "DMA channel {} <-> $21{:02X}, until V: {} H: {}",
+            channel, b_addr, end_v, end_h
+        ),
+        EventKind::Nmi => "NMI".to_string(),
+        EventKind::Irq => "H/V IRQ".to_string(),
+    };
+    format!(
+        "{}\nV: {} H: {}\nPC: {:06X}",
+        kind, event.v, event.h, event.pc
+    )
+}
+
+pub struct EventViewer {
+    events: Vec<Event>,
+    v_display_end: u16,
+    v_end: u16,
+    shown_categories: Vec<Category>,
+    scale: i32,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    events: Vec<Event>,
+    v_display_end: u16,
+    v_end: u16,
+}
+
+impl View for EventViewer {
+    const NAME: &'static str = "Event viewer";
+
+    type FrameData = FrameData;
+    type EmuState = ();
+
+    fn new(_window: &mut Window) -> Self {
+        EventViewer {
+            events: Vec::new(),
+            v_display_end: 225,
+            v_end: 262,
+            shown_categories: CATEGORIES.iter().map(|&(category, ..)| category).collect(),
+            scale: 2,
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {}
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        // Recording starts once the view is first opened, so the first frame will be empty
+        let log = emu.debugger.events.get_or_insert_with(events::Log::new);
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            events: Vec::new(),
+            v_display_end: 0,
+            v_end: 0,
+        });
+        frame_data.events.clear();
+        frame_data.events.extend_from_slice(log.last_frame());
+        frame_data.v_display_end = emu.ppu.counters.v_display_end();
+        frame_data.v_end = emu.ppu.counters.v_end();
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.events.clear();
+        self.events.extend_from_slice(&frame_data.events);
+        self.v_display_end = frame_data.v_display_end;
+        self.v_end = frame_data.v_end;
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.horizontal_scrollbar(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        _window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        for &(category, name, color) in CATEGORIES {
+            let mut shown = self.shown_categories.contains(&category);
+            let _color = ui.push_style_color(imgui::StyleColor::CheckMark, color);
+            if ui.checkbox(name, &mut shown) {
+                if shown {
+                    self.shown_categories.push(category);
+                } else {
+                    self.shown_categories.retain(|&c| c != category);
+                }
+            }
+            ui.same_line();
+        }
+        ui.set_next_item_width(ui.calc_text_size("0")[0] * 8.0);
+        ui.input_int("Scale", &mut self.scale).build();
+        self.scale = self.scale.clamp(1, 4);
+        ui.separator();
+
+        let scale = self.scale as f32;
+        let lines = self.v_end + 1;
+        let origin = ui.cursor_screen_pos();
+        let size = [DOTS_PER_LINE as f32 * scale, lines as f32 * scale];
+        ui.invisible_button("##grid", size);
+        let hovered = ui.is_item_hovered();
+
+        let draw_list = ui.get_window_draw_list();
+        draw_list
+            .add_rect(
+                origin,
+                [origin[0] + size[0], origin[1] + size[1]],
+                [0.1, 0.1, 0.1, 1.0],
+            )
+            .filled(true)
+            .build();
+        // Shade vblank
+        draw_list
+            .add_rect(
+                [origin[0], origin[1] + self.v_display_end as f32 * scale],
+                [origin[0] + size[0], origin[1] + size[1]],
+                [0.2, 0.2, 0.25, 1.0],
+            )
+            .filled(true)
+            .build();
+
+        let point = |h: u16, v: u16| [origin[0] + h as f32 * scale, origin[1] + v as f32 * scale];
+        let dot_size = scale.max(2.0);
+
+        for event in &self.events {
+            let category = category(&event.kind);
+            if !self.shown_categories.contains(&category) {
+                continue;
+            }
+            let color = CATEGORIES
+                .iter()
+                .find(|&&(c, ..)| c == category)
+                .map(|&(.., color)| color)
+                .unwrap();
+            if let EventKind::Dma { end_v, end_h, .. } = event.kind {
+                // Draw the span of the transfer, wrapping around at the end of each scanline
+                let (mut v, mut h) = (event.v, event.h);
+                while v < end_v && v <= self.v_end {
+                    let start = point(h, v);
+                    draw_list
+                        .add_rect(
+                            start,
+                            [origin[0] + size[0], start[1] + dot_size],
+                            [color[0], color[1], color[2], 0.5],
+                        )
+                        .filled(true)
+                        .build();
+                    v += 1;
+                    h = 0;
+                }
+                if v == end_v {
+                    let start = point(h, v);
+                    let end = point(end_h.max(h), v);
+                    draw_list
+                        .add_rect(
+                            start,
+                            [end[0] + dot_size, end[1] + dot_size],
+                            [color[0], color[1], color[2], 0.5],
+                        )
+                        .filled(true)
+                        .build();
+                }
+            }
+            let start = point(event.h, event.v);
+            draw_list
+                .add_rect(start, [start[0] + dot_size, start[1] + dot_size], color)
+                .filled(true)
+                .build();
+        }
+
+        if hovered {
+            let mouse_pos = ui.io().mouse_pos;
+            let h = ((mouse_pos[0] - origin[0]) / scale) as i32;
+            let v = ((mouse_pos[1] - origin[1]) / scale) as i32;
+            let closest = self
+                .events
+                .iter()
+                .filter(|event| self.shown_categories.contains(&category(&event.kind)))
+                .map(|event| {
+                    let distance = (event.h as i32 - h)
+                        .abs()
+                        .max((event.v as i32 - v).abs() * 2);
+                    (distance, event)
+                })
+                .filter(|&(distance, _)| distance <= 4)
+                .min_by_key(|&(distance, _)| distance);
+            match closest {
+                Some((_, event)) => ui.tooltip_text(describe(event)),
+                None => ui.tooltip_text(format!("V: {} H: {}", v, h)),
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,207 @@
+use super::{
+    common::image::{pick_png_path, save_png},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use ness_core::{
+    emu::Emu,
+    ppu::{
+        layers::{LayerCapture, LayerOverrides},
+        FB_WIDTH,
+    },
+};
+
+const LAYER_NAMES: [&str; 5] = ["BG1", "BG2", "BG3", "BG4", "OBJ"];
+
+#[derive(Clone)]
+pub struct EmuState {
+    overrides: LayerOverrides,
+    capture: bool,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    layers: [Vec<u32>; 5],
+    width: usize,
+    height: usize,
+    hi_res: bool,
+}
+
+pub struct LayerViewer {
+    overrides: LayerOverrides,
+    capture: bool,
+    data: Option<FrameData>,
+    export_status: Option<Result<String, String>>,
+}
+
+impl LayerViewer {
+    fn export(&self, layer: usize) -> Option<Result<String, String>> {
+        let data = self.data.as_ref()?;
+        let path = pick_png_path(&format!("{}.png", LAYER_NAMES[layer].to_ascii_lowercase()))?;
+        // Captured lines are always `FB_WIDTH` pixels wide, with non-hi-res pixels doubled
+        let x_step = if data.hi_res { 1 } else { 2 };
+        let mut pixels = Vec::with_capacity(data.width * data.height);
+        for line in data.layers[layer].chunks_exact(FB_WIDTH) {
+            pixels.extend(line.iter().step_by(x_step).take(data.width));
+        }
+        Some(
+            save_png(&path, &pixels, data.width, data.width, data.height)
+                .map(|_| format!("Exported to {}", path.to_string_lossy())),
+        )
+    }
+}
+
+impl View for LayerViewer {
+    const NAME: &'static str = "Layers";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        LayerViewer {
+            overrides: LayerOverrides::default(),
+            capture: false,
+            data: None,
+            export_status: None,
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState {
+            overrides: self.overrides,
+            capture: self.capture,
+        }
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        emu.ppu.layer_overrides = emu_state.overrides;
+        if !emu_state.capture {
+            emu.ppu.layer_capture = None;
+            return;
+        }
+
+        let width = emu.ppu.fb_width();
+        let height = emu.ppu.fb_height();
+        let hi_res = width != emu.ppu.view_width();
+        let capture = match &emu.ppu.layer_capture {
+            Some(capture) => capture,
+            None => {
+                // Layers will be captured starting from the next frame
+                emu.ppu.layer_capture = Some(Box::new(LayerCapture::new()));
+                return;
+            }
+        };
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            layers: Default::default(),
+            width: 0,
+            height: 0,
+            hi_res: false,
+        });
+        frame_data.width = width;
+        frame_data.height = height;
+        frame_data.hi_res = hi_res;
+        for (dst, src) in frame_data
+            .layers
+            .iter_mut()
+            .zip(capture.bgs.iter().chain([&capture.obj]))
+        {
+            dst.clear();
+            dst.extend_from_slice(&src[..FB_WIDTH * height]);
+        }
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.data = Some(frame_data.clone());
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.always_auto_resize(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        _window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let prev_overrides = self.overrides;
+        let prev_capture = self.capture;
+
+        ui.text("Shown layers:");
+        for (i, name) in LAYER_NAMES[..4].iter().enumerate() {
+            if i != 0 {
+                ui.same_line();
+            }
+            let mut shown = self.overrides.hidden_bgs & 1 << i == 0;
+            if ui.checkbox(name, &mut shown) {
+                self.overrides.hidden_bgs ^= 1 << i;
+            }
+        }
+        for prio in 0..4 {
+            if prio != 0 {
+                ui.same_line();
+            }
+            let mut shown = self.overrides.hidden_obj_priorities & 1 << prio == 0;
+            if ui.checkbox(&format!("OBJ {}", prio), &mut shown) {
+                self.overrides.hidden_obj_priorities ^= 1 << prio;
+            }
+        }
+        let mut backdrop_shown = !self.overrides.hide_backdrop;
+        if ui.checkbox("Backdrop", &mut backdrop_shown) {
+            self.overrides.hide_backdrop = !backdrop_shown;
+        }
+        ui.same_line();
+        let mut color_math_enabled = !self.overrides.disable_color_math;
+        if ui.checkbox("Color math", &mut color_math_enabled) {
+            self.overrides.disable_color_math = !color_math_enabled;
+        }
+        if ui.button("Show all") {
+            self.overrides = LayerOverrides::default();
+        }
+        ui.separator();
+
+        ui.checkbox("Capture layers", &mut self.capture);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Keeps a copy of each layer's pixels, before windows and priorities");
+        }
+        if !self.capture {
+            self.data = None;
+        }
+        if self.data.is_some() {
+            for (i, name) in LAYER_NAMES.iter().enumerate() {
+                if i != 0 {
+                    ui.same_line();
+                }
+                if ui.button(&format!("Export {}...", name)) {
+                    if let Some(status) = self.export(i) {
+                        self.export_status = Some(status);
+                    }
+                }
+            }
+        } else if self.capture {
+            ui.text_disabled("Waiting for the next frame...");
+        }
+        match &self.export_status {
+            Some(Ok(message)) => ui.text(message),
+            Some(Err(err)) => ui.text_colored([1.0, 0.3, 0.3, 1.0], err),
+            None => {}
+        }
+
+        if self.overrides != prev_overrides || self.capture != prev_capture {
+            Some(self.emu_state())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use super::{
+    common::image::{create_texture, rgb5_to_rgba8, set_texture_rows},
+    tile_viewer::{tile_pixel, TileFormat},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use imgui::{ChildWindow, Image, Selectable, TextureId};
+use ness_core::{
+    emu::Emu,
+    ppu::{LineObjStats, Obj, ObjControl, Status77},
+};
+
+const SLOT_SIZE: usize = 64;
+const SLOTS_PER_ROW: usize = 16;
+const ATLAS_WIDTH: usize = SLOT_SIZE * SLOTS_PER_ROW;
+const ATLAS_HEIGHT: usize = SLOT_SIZE * 0x80 / SLOTS_PER_ROW;
+const SCREEN_WIDTH: usize = 256;
+
+/// Returns the X coordinate of the object as a signed value, as coordinates from 256 onwards wrap
+/// around to the left of the screen.
+fn obj_x(obj: &Obj) -> i32 {
+    if obj.x_coord >= 256 {
+        obj.x_coord as i32 - 512
+    } else {
+        obj.x_coord as i32
+    }
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    objs: Vec<Obj>,
+    vram: Vec<u8>,
+    palette: Vec<u16>,
+    obj_control: ObjControl,
+    first_obj: u8,
+    status77: Status77,
+    view_height: usize,
+    line_obj_stats: Vec<LineObjStats>,
+}
+
+impl FrameData {
+    fn obj_size(&self, obj: &Obj) -> (usize, usize) {
+        let (width_shift, height_shift) =
+            self.obj_control.size_shifts()[obj.attrs.large_size() as usize];
+        (8 << width_shift, 8 << height_shift)
+    }
+
+    fn obj_on_line(&self, obj: &Obj, line: usize) -> bool {
+        let (width, height) = self.obj_size(obj);
+        (line as u8).wrapping_sub(obj.y_coord) < height as u8
+            && !(SCREEN_WIDTH as u16 + 1..512 + 1 - width as u16).contains(&obj.x_coord)
+    }
+
+    fn char_base(&self, obj: &Obj) -> usize {
+        let obj_control = self.obj_control;
+        let mut base = (obj_control.char_base_addr() as u16) << 14;
+        if obj.attrs.tile_table() {
+            base = base.wrapping_add((1 + obj_control.obj_0ff_100_gap() as u16) << 13);
+        }
+        base as usize
+    }
+}
+
+pub struct OamViewer {
+    texture_id: TextureId,
+    data: FrameData,
+    pixels: Box<[u8]>,
+    selected: Option<usize>,
+    zoom: i32,
+}
+
+impl OamViewer {
+    fn draw_objs(&mut self, window: &mut Window) {
+        let data = &self.data;
+        for (i, obj) in data.objs.iter().enumerate() {
+            let (width, height) = data.obj_size(obj);
+            let char_base = data.char_base(obj);
+            let slot_x = i % SLOTS_PER_ROW * SLOT_SIZE;
+            let slot_y = i / SLOTS_PER_ROW * SLOT_SIZE;
+            for y in 0..SLOT_SIZE {
+                for x in 0..SLOT_SIZE {
+                    let color = if x < width && y < height {
+                        let src_x = if obj.attrs.x_flip() { width - 1 - x } else { x };
+                        // Non-square OBJs are flipped using their width, like on hardware
+                        let src_y = if obj.attrs.y_flip() {
+                            y ^ (width - 1)
+                        } else {
+                            y
+                        };
+                        let tile = obj
+                            .tile_number
+                            .wrapping_add((src_y >> 3 << 4) as u8)
+                            .wrapping_add((src_x >> 3) as u8);
+                        let index = tile_pixel(
+                            &data.vram,
+                            TileFormat::Bpp4,
+                            (char_base + ((tile as usize) << 5)) & 0xFFFF,
+                            src_x & 7,
+                            src_y & 7,
+                        );
+                        if index == 0 {
+                            [0; 4]
+                        } else {
+                            rgb5_to_rgba8(
+                                data.palette
+                                    [0x80 | (obj.pal_number as usize) << 4 | index as usize],
+                            )
+                        }
+                    } else {
+                        [0; 4]
+                    };
+                    let offset = ((slot_y + y) * ATLAS_WIDTH + slot_x + x) << 2;
+                    self.pixels[offset..offset + 4].copy_from_slice(&color);
+                }
+            }
+        }
+        set_texture_rows(window, self.texture_id, &self.pixels, ATLAS_HEIGHT as u32);
+    }
+
+    fn preview(&self, ui: &imgui::Ui, i: usize, scale: f32) {
+        let (width, height) = self.data.obj_size(&self.data.objs[i]);
+        let uv0 = [
+            (i % SLOTS_PER_ROW * SLOT_SIZE) as f32 / ATLAS_WIDTH as f32,
+            (i / SLOTS_PER_ROW * SLOT_SIZE) as f32 / ATLAS_HEIGHT as f32,
+        ];
+        Image::new(
+            self.texture_id,
+            [width as f32 * scale, height as f32 * scale],
+        )
+        .uv0(uv0)
+        .uv1([
+            uv0[0] + width as f32 / ATLAS_WIDTH as f32,
+            uv0[1] + height as f32 / ATLAS_HEIGHT as f32,
+        ])
+        .build(ui);
+    }
+
+    fn obj_tooltip(&self, ui: &imgui::Ui, i: usize) {
+        let obj = &self.data.objs[i];
+        let (width, height) = self.data.obj_size(obj);
+        ui.tooltip(|| {
+            ui.text(&format!(
+                "OBJ {}\nPosition: {}, {}\nSize: {}x{}\nTile: {}{:02X}\nPalette: {}\nPriority: {}",
+                i,
+                obj_x(obj),
+                obj.y_coord,
+                width,
+                height,
+                obj.attrs.tile_table() as u8,
+                obj.tile_number,
+                obj.pal_number,
+                obj.bg_prio,
+            ));
+            self.preview(ui, i, 2.0);
+        });
+    }
+
+    fn draw_screen(&self, ui: &imgui::Ui) {
+        let zoom = self.zoom as f32;
+        let origin = ui.cursor_screen_pos();
+        let screen_size = [
+            SCREEN_WIDTH as f32 * zoom,
+            self.data.view_height as f32 * zoom,
+        ];
+        let marker_width = 8.0 * zoom;
+        ui.invisible_button("##screen", [screen_size[0] + marker_width, screen_size[1]]);
+        let hovered = ui.is_item_hovered();
+
+        let draw_list = ui.get_window_draw_list();
+        let screen_end = [origin[0] + screen_size[0], origin[1] + screen_size[1]];
+        draw_list
+            .add_rect(origin, screen_end, [0.1, 0.1, 0.1, 1.0])
+            .filled(true)
+            .build();
+
+        // Mark lines where OBJs or tiles get dropped
+        for (line, stats) in self.data.line_obj_stats.iter().enumerate() {
+            let color = if stats.range_over {
+                [1.0, 0.2, 0.2, 1.0]
+            } else if stats.time_over {
+                [1.0, 0.6, 0.2, 1.0]
+            } else {
+                continue;
+            };
+            let y = origin[1] + line as f32 * zoom;
+            draw_list
+                .add_rect(
+                    [screen_end[0], y],
+                    [screen_end[0] + marker_width, y + zoom],
+                    color,
+                )
+                .filled(true)
+                .build();
+        }
+
+        draw_list.with_clip_rect_intersect(origin, screen_end, || {
+            // Draw in reverse order, so that lower-numbered (higher priority) OBJs end up on top
+            for (i, obj) in self.data.objs.iter().enumerate().rev() {
+                let (width, height) = self.data.obj_size(obj);
+                let x = origin[0] + obj_x(obj) as f32 * zoom;
+                for y in [obj.y_coord as i32, obj.y_coord as i32 - 256] {
+                    let y = origin[1] + y as f32 * zoom;
+                    let end = [x + width as f32 * zoom, y + height as f32 * zoom];
+                    let uv0 = [
+                        (i % SLOTS_PER_ROW * SLOT_SIZE) as f32 / ATLAS_WIDTH as f32,
+                        (i / SLOTS_PER_ROW * SLOT_SIZE) as f32 / ATLAS_HEIGHT as f32,
+                    ];
+                    draw_list
+                        .add_image(self.texture_id, [x, y], end)
+                        .uv_min(uv0)
+                        .uv_max([
+                            uv0[0] + width as f32 / ATLAS_WIDTH as f32,
+                            uv0[1] + height as f32 / ATLAS_HEIGHT as f32,
+                        ])
+                        .build();
+                    let color = if self.selected == Some(i) {
+                        [1.0, 1.0, 0.0, 1.0]
+                    } else {
+                        [1.0, 1.0, 1.0, 0.3]
+                    };
+                    draw_list.add_rect([x, y], end, color).build();
+                }
+            }
+        });
+
+        if hovered {
+            let mouse_pos = ui.io().mouse_pos;
+            let line = ((mouse_pos[1] - origin[1]) / zoom) as usize;
+            if let Some(stats) = self.data.line_obj_stats.get(line) {
+                let objs = (0..self.data.objs.len())
+                    .filter(|&i| self.data.obj_on_line(&self.data.objs[i], line))
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>();
+                let mut text = format!(
+                    "Line {}: {} OBJs, {} tiles\nOBJs: {}",
+                    line,
+                    objs.len(),
+                    stats.tiles,
+                    objs.join(", ")
+                );
+                if stats.range_over {
+                    text.push_str("\nMore than 32 OBJs, range over");
+                }
+                if stats.time_over {
+                    text.push_str("\nMore than 34 tiles, time over");
+                }
+                ui.tooltip_text(text);
+            }
+        }
+    }
+}
+
+impl View for OamViewer {
+    const NAME: &'static str = "OAM viewer";
+
+    type FrameData = FrameData;
+    type EmuState = ();
+
+    fn new(window: &mut Window) -> Self {
+        OamViewer {
+            texture_id: create_texture(
+                window,
+                "OAM viewer texture",
+                ATLAS_WIDTH as u32,
+                ATLAS_HEIGHT as u32,
+            ),
+            data: FrameData {
+                objs: Vec::new(),
+                vram: Vec::new(),
+                palette: Vec::new(),
+                obj_control: ObjControl(0),
+                first_obj: 0,
+                status77: Status77(0),
+                view_height: 224,
+                line_obj_stats: Vec::new(),
+            },
+            pixels: vec![0; ATLAS_WIDTH * ATLAS_HEIGHT * 4].into_boxed_slice(),
+            selected: None,
+            zoom: 2,
+        }
+    }
+
+    fn destroy(self, window: &mut Window) {
+        window.gfx.imgui.remove_texture(self.texture_id);
+    }
+
+    fn emu_state(&self) -> Self::EmuState {}
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let ppu = &emu.ppu;
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            objs: Vec::new(),
+            vram: Vec::new(),
+            palette: Vec::new(),
+            obj_control: ObjControl(0),
+            first_obj: 0,
+            status77: Status77(0),
+            view_height: 0,
+            line_obj_stats: Vec::new(),
+        });
+        frame_data.objs.clear();
+        frame_data.objs.extend_from_slice(&ppu.oam.contents[..]);
+        frame_data.vram.clear();
+        frame_data.vram.extend_from_slice(&ppu.vram.contents[..]);
+        frame_data.palette.clear();
+        frame_data
+            .palette
+            .extend_from_slice(&ppu.palette.contents[..]);
+        frame_data.obj_control = ppu.obj_control();
+        frame_data.first_obj = ppu.oam.next_first_sprite();
+        frame_data.status77 = ppu.status77();
+        frame_data.view_height = ppu.view_height();
+        frame_data.line_obj_stats.clear();
+        frame_data
+            .line_obj_stats
+            .extend_from_slice(&ppu.line_obj_stats[..frame_data.view_height]);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, window: &mut Window) {
+        self.data.objs.clear();
+        self.data.objs.extend_from_slice(&frame_data.objs);
+        self.data.vram.clear();
+        self.data.vram.extend_from_slice(&frame_data.vram);
+        self.data.palette.clear();
+        self.data.palette.extend_from_slice(&frame_data.palette);
+        self.data.obj_control = frame_data.obj_control;
+        self.data.first_obj = frame_data.first_obj;
+        self.data.status77 = frame_data.status77;
+        self.data.view_height = frame_data.view_height;
+        self.data.line_obj_stats.clear();
+        self.data
+            .line_obj_stats
+            .extend_from_slice(&frame_data.line_obj_stats);
+        self.draw_objs(window);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        if self.data.objs.is_empty() {
+            ui.text_disabled("No data available");
+            return None;
+        }
+
+        let [small, large] = self
+            .data
+            .obj_control
+            .size_shifts()
+            .map(|(width_shift, height_shift)| (8 << width_shift, 8 << height_shift));
+        ui.text(&format!(
+            "Sizes: {}x{}/{}x{}, first OBJ: {}",
+            small.0, small.1, large.0, large.1, self.data.first_obj
+        ));
+        ui.same_line();
+        if self.data.status77.range_over() {
+            ui.text_colored([1.0, 0.2, 0.2, 1.0], "Range over");
+            ui.same_line();
+        }
+        if self.data.status77.time_over() {
+            ui.text_colored([1.0, 0.6, 0.2, 1.0], "Time over");
+            ui.same_line();
+        }
+        ui.set_next_item_width(ui.calc_text_size("0")[0] * 8.0);
+        ui.input_int("Zoom", &mut self.zoom).build();
+        self.zoom = self.zoom.clamp(1, 4);
+        ui.separator();
+
+        let list_width = ui.calc_text_size("000")[0] * 22.0;
+        ChildWindow::new("##objs")
+            .size([list_width, 0.0])
+            .build(ui, || {
+                let _mono_font = ui.push_font(window.mono_font);
+                ui.columns(7, "##columns", true);
+                for name in ["#", "X, Y", "Size", "Tile", "Pal", "Prio", "Flip"] {
+                    ui.text(name);
+                    ui.next_column();
+                }
+                ui.separator();
+                for (i, obj) in self.data.objs.iter().enumerate() {
+                    let (width, height) = self.data.obj_size(obj);
+                    if Selectable::new(&i.to_string())
+                        .selected(self.selected == Some(i))
+                        .build(ui)
+                    {
+                        self.selected = if self.selected == Some(i) {
+                            None
+                        } else {
+                            Some(i)
+                        };
+                    }
+                    if ui.is_item_hovered() {
+                        self.obj_tooltip(ui, i);
+                    }
+                    ui.next_column();
+                    ui.text(&format!("{}, {}", obj_x(obj), obj.y_coord));
+                    ui.next_column();
+                    ui.text(&format!("{}x{}", width, height));
+                    ui.next_column();
+                    ui.text(&format!(
+                        "{}{:02X}",
+                        obj.attrs.tile_table() as u8,
+                        obj.tile_number
+                    ));
+                    ui.next_column();
+                    ui.text(&obj.pal_number.to_string());
+                    ui.next_column();
+                    ui.text(&obj.bg_prio.to_string());
+                    ui.next_column();
+                    ui.text(&format!(
+                        "{}{}",
+                        if obj.attrs.x_flip() { "X" } else { "-" },
+                        if obj.attrs.y_flip() { "Y" } else { "-" },
+                    ));
+                    ui.next_column();
+                }
+                ui.columns(1, "##columns", false);
+            });
+
+        ui.same_line();
+        ChildWindow::new("##screen")
+            .horizontal_scrollbar(true)
+            .build(ui, || {
+                self.draw_screen(ui);
+                if let Some(i) = self.selected {
+                    ui.separator();
+                    self.preview(ui, i, 4.0);
+                }
+            });
+
+        None
+    }
+}
@@ -0,0 +1,183 @@
+use super::{common::image::rgb5_to_rgba8, Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::{ColorButton, Slider, StyleVar};
+use ness_core::emu::Emu;
+
+fn color_f32(color: u16) -> [f32; 4] {
+    rgb5_to_rgba8(color).map(|component| component as f32 / 255.0)
+}
+
+pub struct PaletteViewer {
+    palette: Vec<u16>,
+    selected: u8,
+    color_input: String,
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    writes: Vec<(u8, u16)>,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    palette: Vec<u16>,
+}
+
+impl PaletteViewer {
+    fn write_color(&mut self, index: u8, color: u16, writes: &mut Vec<(u8, u16)>) {
+        let color = color & 0x7FFF;
+        // Update the local copy right away, so the edit shows up even while emulation is paused
+        self.palette[index as usize] = color;
+        writes.push((index, color));
+    }
+}
+
+impl View for PaletteViewer {
+    const NAME: &'static str = "Palette viewer";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        PaletteViewer {
+            palette: Vec::new(),
+            selected: 0,
+            color_input: String::new(),
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState { writes: Vec::new() }
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        for (index, color) in emu_state.writes.drain(..) {
+            emu.ppu.palette.write_color_debug(index, color);
+        }
+        false
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            palette: Vec::new(),
+        });
+        frame_data.palette.clear();
+        frame_data
+            .palette
+            .extend_from_slice(&emu.ppu.palette.contents[..]);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.palette.clear();
+        self.palette.extend_from_slice(&frame_data.palette);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.always_auto_resize(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        if self.palette.is_empty() {
+            ui.text_disabled("No data available");
+            return None;
+        }
+
+        let swatch_size = ui.frame_height();
+        {
+            let _item_spacing = ui.push_style_var(StyleVar::ItemSpacing([2.0, 2.0]));
+            for i in 0..=255 {
+                if i & 15 != 0 {
+                    ui.same_line();
+                }
+                let color = self.palette[i as usize];
+                if ColorButton::new(&format!("##{}", i), color_f32(color))
+                    .size([swatch_size, swatch_size])
+                    .tooltip(false)
+                    .build(ui)
+                {
+                    self.selected = i;
+                    self.color_input.clear();
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(format!("{:02X}: {:04X}", i, color));
+                }
+                if i == self.selected {
+                    ui.get_window_draw_list()
+                        .add_rect(ui.item_rect_min(), ui.item_rect_max(), [1.0; 4])
+                        .thickness(2.0)
+                        .build();
+                }
+            }
+        }
+        ui.text_disabled("BG palettes: 00-7F, OBJ palettes: 80-FF");
+        ui.separator();
+
+        let index = self.selected;
+        let color = self.palette[index as usize];
+        ColorButton::new("##selected", color_f32(color))
+            .size([swatch_size * 3.0, swatch_size * 3.0])
+            .tooltip(false)
+            .build(ui);
+        ui.same_line();
+        ui.group(|| {
+            let _mono_font = ui.push_font(window.mono_font);
+            ui.text(&format!(
+                "Index: {:02X} (palette {}, color {})",
+                index,
+                index >> 4,
+                index & 15,
+            ));
+            ui.text(&format!("Value: {:04X}", color));
+        });
+
+        if !emu_running {
+            return None;
+        }
+
+        let mut writes = Vec::new();
+        let mut components = [color & 0x1F, color >> 5 & 0x1F, color >> 10 & 0x1F];
+        let mut changed = false;
+        for (component, name) in components.iter_mut().zip(["R", "G", "B"]) {
+            ui.set_next_item_width(swatch_size * 12.0);
+            changed |= Slider::new(name, 0, 31).build(ui, component);
+        }
+        if changed {
+            let [r, g, b] = components;
+            self.write_color(index, r | g << 5 | b << 10, &mut writes);
+        }
+
+        ui.set_next_item_width(swatch_size * 4.0);
+        if ui
+            .input_text("##value", &mut self.color_input)
+            .hint("BGR555")
+            .chars_hexadecimal(true)
+            .enter_returns_true(true)
+            .build()
+        {
+            if let Ok(value) = u16::from_str_radix(&self.color_input, 16) {
+                self.write_color(index, value, &mut writes);
+            }
+            self.color_input.clear();
+        }
+        ui.same_line();
+        ui.text("Set value");
+
+        (!writes.is_empty()).then_some(EmuState { writes })
+    }
+}
@@ -0,0 +1,667 @@
+use super::{
+    common::regs::{bitfield, regs, BitfieldCommand, MaxWidth, RegCommand, RegValue},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use imgui::{CollapsingHeader, StyleVar};
+use ness_core::{
+    emu::Emu,
+    ppu::{
+        BgCharControl, BgModeControl, BgScreenControl, ColorMathControlA, ColorMathControlB,
+        DisplayControl0, DisplayControl1, HvIrqMode, LayerWin12Areas, LayerWin12Masks,
+        Mode7Control, MosaicControl, Status77, Status78,
+    },
+};
+
+/// An edit to the PPU state requested from the PPU state view, applied by the emulation thread.
+#[derive(Clone, Copy, Debug)]
+pub enum PpuEdit {
+    BgMode(u8),
+    DisplayControl0(DisplayControl0),
+    BgScroll {
+        bg: usize,
+        x: u16,
+        y: u16,
+    },
+    ScreenLayers {
+        main: u8,
+        sub: u8,
+    },
+    WindowRange {
+        window: usize,
+        start: u8,
+        end: u8,
+    },
+    SubBackdropColor(u16),
+    Mode7 {
+        params: [i16; 4],
+        scroll: [i16; 2],
+        center: [i16; 2],
+    },
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    edits: Vec<PpuEdit>,
+}
+
+impl PpuEdit {
+    pub fn apply(self, emu: &mut Emu) {
+        let ppu = &mut emu.ppu;
+        match self {
+            PpuEdit::BgMode(bg_mode) => {
+                ppu.set_bg_mode_control(ppu.bg_mode_control().with_bg_mode(bg_mode));
+            }
+            PpuEdit::DisplayControl0(value) => ppu.set_display_control_0(value),
+            PpuEdit::BgScroll { bg, x, y } => {
+                ppu.bgs[bg].x_scroll = x & 0x3FF;
+                ppu.bgs[bg].y_scroll = y & 0x3FF;
+            }
+            PpuEdit::ScreenLayers { main, sub } => {
+                ppu.enabled_main_screen_layers = main & 0x1F;
+                ppu.enabled_sub_screen_layers = sub & 0x1F;
+            }
+            PpuEdit::WindowRange { window, start, end } => {
+                ppu.window_ranges[window] = (start, end);
+            }
+            PpuEdit::SubBackdropColor(color) => ppu.sub_backdrop_color = color & 0x7FFF,
+            PpuEdit::Mode7 {
+                params,
+                scroll,
+                center,
+            } => {
+                ppu.mode7.params = params;
+                // Scroll and center values are 13-bit signed
+                ppu.mode7.scroll = scroll.map(|value| value << 3 >> 3);
+                ppu.mode7.center = center.map(|value| value << 3 >> 3);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PpuRegs {
+    display_control_0: DisplayControl0,
+    display_control_1: DisplayControl1,
+    bg_mode_control: BgModeControl,
+    bg_screen_controls: [BgScreenControl; 4],
+    bg_char_controls: [BgCharControl; 2],
+    bg_scrolls: [(u16, u16); 4],
+    mosaic_control: MosaicControl,
+    window_ranges: [(u8, u8); 2],
+    win12_areas: [LayerWin12Areas; 3],
+    win12_masks: [LayerWin12Masks; 2],
+    win_disabled_layer_masks: [u8; 2],
+    main_screen_layers: u8,
+    sub_screen_layers: u8,
+    color_math_control_a: ColorMathControlA,
+    color_math_control_b: ColorMathControlB,
+    sub_backdrop_color: u16,
+    mode7_control: Mode7Control,
+    mode7_params: [i16; 4],
+    mode7_scroll: [i16; 2],
+    mode7_center: [i16; 2],
+    v_counter: u16,
+    h_dot: u16,
+    v_timer_value: u16,
+    h_timer_value: u16,
+    hv_irq_mode: HvIrqMode,
+    latched_v_counter: u16,
+    latched_h_counter: u16,
+    status77: Status77,
+    status78: Status78,
+}
+
+pub struct PpuState {
+    regs: Option<PpuRegs>,
+}
+
+/// Draws an 8/16-bit hexadecimal input that returns the new value once Enter is pressed.
+fn hex_input(
+    ui: &imgui::Ui,
+    label: &str,
+    value: u16,
+    digits: usize,
+    editable: bool,
+) -> Option<u16> {
+    let mut text = format!("{:0digits$X}", value, digits = digits);
+    ui.set_next_item_width(
+        ui.calc_text_size("0")[0] * (digits + 1) as f32 + ui.clone_style().frame_padding[0] * 2.0,
+    );
+    if ui
+        .input_text(label, &mut text)
+        .chars_hexadecimal(true)
+        .enter_returns_true(true)
+        .read_only(!editable)
+        .build()
+    {
+        u16::from_str_radix(&text, 16).ok()
+    } else {
+        None
+    }
+}
+
+fn layer_checkboxes(ui: &imgui::Ui, ident: &str, value: &mut u8, editable: bool) -> bool {
+    let mut changed = false;
+    for (i, name) in ["BG1", "BG2", "BG3", "BG4", "OBJ"].into_iter().enumerate() {
+        let mut enabled = *value & 1 << i != 0;
+        if i != 0 {
+            ui.same_line();
+        }
+        if ui.checkbox(&format!("{}##{}", name, ident), &mut enabled) && editable {
+            *value ^= 1 << i;
+            changed = true;
+        }
+    }
+    changed
+}
+
+impl PpuState {
+    fn render_display(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        ui.text("INIDISP:");
+        ui.same_line();
+        let mut forced_blank = regs.display_control_0.forced_blank();
+        if ui.checkbox("Forced blank", &mut forced_blank) && editable {
+            regs.display_control_0.set_forced_blank(forced_blank);
+            edits.push(PpuEdit::DisplayControl0(regs.display_control_0));
+        }
+        ui.same_line();
+        let mut brightness = regs.display_control_0.master_brightness() as i32;
+        ui.set_next_item_width(ui.calc_text_size("00")[0] * 6.0);
+        if ui.input_int("Brightness", &mut brightness).build() && editable {
+            regs.display_control_0
+                .set_master_brightness(brightness.clamp(0, 15) as u8);
+            edits.push(PpuEdit::DisplayControl0(regs.display_control_0));
+        }
+
+        ui.text("SETINI:");
+        bitfield(
+            ui,
+            "SETINI",
+            2.0,
+            regs.display_control_1.0 as usize,
+            &[
+                BitfieldCommand::Field("Interlace", 1),
+                BitfieldCommand::Field("OBJ V", 1),
+                BitfieldCommand::Field("Overscan", 1),
+                BitfieldCommand::Field("Pseudo 512", 1),
+                BitfieldCommand::Field("-", 2),
+                BitfieldCommand::Field("EXTBG", 1),
+                BitfieldCommand::Field("Ext sync", 1),
+            ],
+        );
+
+        ui.text(&format!(
+            "STAT77: {:02X} (range over: {}, time over: {})",
+            regs.status77.0,
+            regs.status77.range_over() as u8,
+            regs.status77.time_over() as u8,
+        ));
+        ui.text(&format!(
+            "STAT78: {:02X} (field: {}, PAL: {})",
+            regs.status78.0,
+            regs.status78.interlace_field() as u8,
+            regs.status78.pal_console() as u8,
+        ));
+    }
+
+    fn render_bgs(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        let mut bg_mode = regs.bg_mode_control.bg_mode() as i32;
+        ui.set_next_item_width(ui.calc_text_size("00")[0] * 6.0);
+        if ui.input_int("BG mode", &mut bg_mode).build() && editable {
+            let bg_mode = bg_mode.clamp(0, 7) as u8;
+            regs.bg_mode_control.set_bg_mode(bg_mode);
+            edits.push(PpuEdit::BgMode(bg_mode));
+        }
+        ui.same_line();
+        ui.text(&format!(
+            "BG3 priority: {}",
+            regs.bg_mode_control.bg3_m1_priority() as u8
+        ));
+
+        ui.columns(6, "bgs", true);
+        for name in ["", "Map", "Size", "Chars", "16x16", "Scroll (X, Y)"] {
+            ui.text(name);
+            ui.next_column();
+        }
+        ui.separator();
+        for i in 0..4 {
+            let screen_control = regs.bg_screen_controls[i];
+            let char_control = regs.bg_char_controls[i >> 1];
+            let char_base = if i & 1 == 0 {
+                char_control.bg13_char_base()
+            } else {
+                char_control.bg24_char_base()
+            };
+            ui.align_text_to_frame_padding();
+            ui.text(&format!("BG{}", i + 1));
+            ui.next_column();
+            ui.text(&format!(
+                "{:04X}",
+                (screen_control.screen_base() as u16) << 10
+            ));
+            ui.next_column();
+            ui.text(["32x32", "64x32", "32x64", "64x64"][screen_control.screen_size() as usize]);
+            ui.next_column();
+            ui.text(&format!("{:04X}", (char_base as u16) << 12));
+            ui.next_column();
+            ui.text(&((regs.bg_mode_control.bg_tile_size_mask() >> i & 1).to_string()));
+            ui.next_column();
+            let (x, y) = &mut regs.bg_scrolls[i];
+            let new_x = hex_input(ui, &format!("##bg{}_x", i), *x, 3, editable);
+            ui.same_line();
+            let new_y = hex_input(ui, &format!("##bg{}_y", i), *y, 3, editable);
+            if new_x.is_some() || new_y.is_some() {
+                *x = new_x.unwrap_or(*x) & 0x3FF;
+                *y = new_y.unwrap_or(*y) & 0x3FF;
+                edits.push(PpuEdit::BgScroll {
+                    bg: i,
+                    x: *x,
+                    y: *y,
+                });
+            }
+            ui.next_column();
+        }
+        ui.columns(1, "", false);
+
+        ui.text(&format!(
+            "Mosaic: size {}, enabled on:",
+            regs.mosaic_control.mosaic_size() + 1
+        ));
+        ui.same_line();
+        let mut mosaic_mask = regs.mosaic_control.bg_mask();
+        layer_checkboxes(ui, "mosaic", &mut mosaic_mask, false);
+    }
+
+    fn render_screens(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        ui.text("Main (TM):");
+        ui.same_line();
+        let mut changed = layer_checkboxes(ui, "tm", &mut regs.main_screen_layers, editable);
+        ui.text("Sub (TS): ");
+        ui.same_line();
+        changed |= layer_checkboxes(ui, "ts", &mut regs.sub_screen_layers, editable);
+        if changed {
+            edits.push(PpuEdit::ScreenLayers {
+                main: regs.main_screen_layers,
+                sub: regs.sub_screen_layers,
+            });
+        }
+    }
+
+    fn render_windows(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        for window in 0..2 {
+            ui.align_text_to_frame_padding();
+            ui.text(&format!("Window {}: ", window + 1));
+            ui.same_line();
+            let (start, end) = &mut regs.window_ranges[window];
+            let new_start = hex_input(
+                ui,
+                &format!("##win{}_start", window),
+                *start as u16,
+                2,
+                editable,
+            );
+            ui.same_line();
+            ui.text("-");
+            ui.same_line();
+            let new_end = hex_input(
+                ui,
+                &format!("##win{}_end", window),
+                *end as u16,
+                2,
+                editable,
+            );
+            if new_start.is_some() || new_end.is_some() {
+                *start = new_start.map_or(*start, |value| value as u8);
+                *end = new_end.map_or(*end, |value| value as u8);
+                edits.push(PpuEdit::WindowRange {
+                    window,
+                    start: *start,
+                    end: *end,
+                });
+            }
+        }
+
+        // Each window setting is 2 bits: enable (high) and invert (low)
+        ui.text("W12SEL/W34SEL/WOBJSEL:");
+        for (i, (areas, names)) in regs
+            .win12_areas
+            .iter()
+            .zip([
+                ["BG1 W1", "BG1 W2", "BG2 W1", "BG2 W2"],
+                ["BG3 W1", "BG3 W2", "BG4 W1", "BG4 W2"],
+                ["OBJ W1", "OBJ W2", "Col W1", "Col W2"],
+            ])
+            .enumerate()
+        {
+            bitfield(
+                ui,
+                &format!("win_areas_{}", i),
+                2.0,
+                areas.0 as usize,
+                &[
+                    BitfieldCommand::Field(names[0], 2),
+                    BitfieldCommand::Field(names[1], 2),
+                    BitfieldCommand::Field(names[2], 2),
+                    BitfieldCommand::Field(names[3], 2),
+                ],
+            );
+        }
+
+        const MASK_LOGIC: [&str; 4] = ["OR", "AND", "XOR", "XNOR"];
+        let masks = regs.win12_masks;
+        ui.text(&format!(
+            "Mask logic: BG1 {}, BG2 {}, BG3 {}, BG4 {}, OBJ {}, Color {}",
+            MASK_LOGIC[masks[0].bg1_obj() as usize],
+            MASK_LOGIC[masks[0].bg2_math() as usize],
+            MASK_LOGIC[masks[0].bg3() as usize],
+            MASK_LOGIC[masks[0].bg4() as usize],
+            MASK_LOGIC[masks[1].bg1_obj() as usize],
+            MASK_LOGIC[masks[1].bg2_math() as usize],
+        ));
+
+        ui.text("Main (TMW):");
+        ui.same_line();
+        layer_checkboxes(ui, "tmw", &mut regs.win_disabled_layer_masks[0], false);
+        ui.text("Sub (TSW): ");
+        ui.same_line();
+        layer_checkboxes(ui, "tsw", &mut regs.win_disabled_layer_masks[1], false);
+    }
+
+    fn render_color_math(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        const REGIONS: [&str; 4] = ["never", "outside window", "inside window", "always"];
+        let a = regs.color_math_control_a;
+        ui.text(&format!(
+            "CGWSEL: {:02X} (force black: {}, math: {}, sub screen: {}, direct color: {})",
+            a.0,
+            REGIONS[a.force_main_screen_black() as usize],
+            REGIONS[3 - a.color_math_mode() as usize],
+            if a.sub_screen_bg_obj_enabled() {
+                "layers"
+            } else {
+                "backdrop"
+            },
+            a.use_direct_color() as u8,
+        ));
+
+        let b = regs.color_math_control_b;
+        ui.text(&format!(
+            "CGADSUB: {:02X} ({}{}), enabled on:",
+            b.0,
+            if b.add_subtract() { "subtract" } else { "add" },
+            if b.div2_result() { ", half" } else { "" },
+        ));
+        bitfield(
+            ui,
+            "CGADSUB",
+            2.0,
+            b.main_screen_mask() as usize,
+            &[
+                BitfieldCommand::Field("BG1", 1),
+                BitfieldCommand::Field("BG2", 1),
+                BitfieldCommand::Field("BG3", 1),
+                BitfieldCommand::Field("BG4", 1),
+                BitfieldCommand::Field("OBJ", 1),
+                BitfieldCommand::Field("Back", 1),
+            ],
+        );
+
+        ui.align_text_to_frame_padding();
+        ui.text("Fixed color (COLDATA):");
+        ui.same_line();
+        if let Some(color) = hex_input(ui, "##coldata", regs.sub_backdrop_color, 4, editable) {
+            regs.sub_backdrop_color = color & 0x7FFF;
+            edits.push(PpuEdit::SubBackdropColor(regs.sub_backdrop_color));
+        }
+    }
+
+    fn render_mode7(
+        &mut self,
+        ui: &imgui::Ui,
+        regs: &mut PpuRegs,
+        editable: bool,
+        edits: &mut Vec<PpuEdit>,
+    ) {
+        let control = regs.mode7_control;
+        ui.text(&format!(
+            "M7SEL: {:02X} (flip: {}{}, screen over: {})",
+            control.0,
+            if control.x_flip() { "X" } else { "-" },
+            if control.y_flip() { "Y" } else { "-" },
+            ["wrap", "wrap", "transparent", "tile 0"][control.screen_over() as usize],
+        ));
+
+        let mut changed = false;
+        ui.columns(2, "mode7", false);
+        for (i, name) in ["A", "B", "C", "D"].into_iter().enumerate() {
+            ui.align_text_to_frame_padding();
+            ui.text(&format!("{}: ", name));
+            ui.same_line();
+            if let Some(value) = hex_input(
+                ui,
+                &format!("##m7{}", name),
+                regs.mode7_params[i] as u16,
+                4,
+                editable,
+            ) {
+                regs.mode7_params[i] = value as i16;
+                changed = true;
+            }
+            if i == 1 {
+                ui.next_column();
+            }
+        }
+        ui.columns(1, "", false);
+        for (name, values) in [
+            ("Scroll", &mut regs.mode7_scroll),
+            ("Center", &mut regs.mode7_center),
+        ] {
+            ui.align_text_to_frame_padding();
+            ui.text(&format!("{}: ", name));
+            for (i, value) in values.iter_mut().enumerate() {
+                ui.same_line();
+                if let Some(new_value) = hex_input(
+                    ui,
+                    &format!("##m7{}{}", name, i),
+                    *value as u16 & 0x1FFF,
+                    4,
+                    editable,
+                ) {
+                    *value = (new_value as i16) << 3 >> 3;
+                    changed = true;
+                }
+            }
+            ui.same_line();
+            ui.text(&format!("({}, {})", values[0], values[1]));
+        }
+        if changed {
+            edits.push(PpuEdit::Mode7 {
+                params: regs.mode7_params,
+                scroll: regs.mode7_scroll,
+                center: regs.mode7_center,
+            });
+        }
+    }
+
+    fn render_counters(&mut self, ui: &imgui::Ui, values: &PpuRegs) {
+        regs(
+            ui,
+            MaxWidth::Reg16,
+            2.0,
+            [
+                RegCommand::Reg("V    ", RegValue::Reg16(values.v_counter)),
+                RegCommand::Reg("H    ", RegValue::Reg16(values.h_dot)),
+                RegCommand::Reg("VTIME", RegValue::Reg16(values.v_timer_value)),
+                RegCommand::Reg("HTIME", RegValue::Reg16(values.h_timer_value)),
+                RegCommand::Reg("OPVCT", RegValue::Reg16(values.latched_v_counter)),
+                RegCommand::Reg("OPHCT", RegValue::Reg16(values.latched_h_counter)),
+            ],
+        );
+        ui.text(&format!("H/V IRQ mode: {:?}", values.hv_irq_mode));
+    }
+}
+
+impl View for PpuState {
+    const NAME: &'static str = "PPU state";
+
+    type FrameData = PpuRegs;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        PpuState { regs: None }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState { edits: Vec::new() }
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        for edit in emu_state.edits.drain(..) {
+            edit.apply(emu);
+        }
+        false
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let ppu = &emu.ppu;
+        frame_data.insert(PpuRegs {
+            display_control_0: ppu.display_control_0(),
+            display_control_1: ppu.display_control_1(),
+            bg_mode_control: ppu.bg_mode_control(),
+            bg_screen_controls: [0, 1, 2, 3].map(|i| ppu.bgs[i].screen_control()),
+            bg_char_controls: [ppu.bg_char_control_12(), ppu.bg_char_control_34()],
+            bg_scrolls: [0, 1, 2, 3].map(|i| (ppu.bgs[i].x_scroll(), ppu.bgs[i].y_scroll())),
+            mosaic_control: ppu.mosaic_control(),
+            window_ranges: ppu.window_ranges,
+            win12_areas: [
+                ppu.win12_areas_bg_12(),
+                ppu.win12_areas_bg_34(),
+                ppu.win12_areas_obj_math(),
+            ],
+            win12_masks: [ppu.win12_masks_bgs(), ppu.win12_masks_obj_math()],
+            win_disabled_layer_masks: ppu.win_disabled_layer_masks,
+            main_screen_layers: ppu.enabled_main_screen_layers,
+            sub_screen_layers: ppu.enabled_sub_screen_layers,
+            color_math_control_a: ppu.color_math_control_a(),
+            color_math_control_b: ppu.color_math_control_b(),
+            sub_backdrop_color: ppu.sub_backdrop_color,
+            mode7_control: ppu.mode7.control(),
+            mode7_params: ppu.mode7.params,
+            mode7_scroll: ppu.mode7.scroll,
+            mode7_center: ppu.mode7.center,
+            v_counter: ppu.counters.v_counter(),
+            h_dot: ppu.counters.h_dot(emu.schedule.cur_time()),
+            v_timer_value: ppu.counters.v_timer_value(),
+            h_timer_value: ppu.counters.h_timer_value(),
+            hv_irq_mode: ppu.counters.hv_irq_mode(),
+            latched_v_counter: ppu.latched_counters().v_counter(),
+            latched_h_counter: ppu.latched_counters().h_counter(),
+            status77: ppu.status77(),
+            status78: ppu.status78(),
+        });
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.regs = Some(frame_data.clone());
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let mut regs = match self.regs.take() {
+            Some(regs) => regs,
+            None => return None,
+        };
+        let _mono_font = ui.push_font(window.mono_font);
+        let _frame_rounding = ui.push_style_var(StyleVar::FrameRounding(0.0));
+        let mut edits = Vec::new();
+
+        if CollapsingHeader::new("Display")
+            .default_open(true)
+            .build(ui)
+        {
+            self.render_display(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Backgrounds")
+            .default_open(true)
+            .build(ui)
+        {
+            self.render_bgs(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Screen designation")
+            .default_open(true)
+            .build(ui)
+        {
+            self.render_screens(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Windows").build(ui) {
+            self.render_windows(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Color math").build(ui) {
+            self.render_color_math(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Mode 7").build(ui) {
+            self.render_mode7(ui, &mut regs, emu_running, &mut edits);
+        }
+        if CollapsingHeader::new("Counters")
+            .default_open(true)
+            .build(ui)
+        {
+            self.render_counters(ui, &regs);
+        }
+        if !emu_running {
+            ui.text_disabled("Editing is only available while a game is running");
+        }
+
+        self.regs = Some(regs);
+        (!edits.is_empty()).then_some(EmuState { edits })
+    }
+}
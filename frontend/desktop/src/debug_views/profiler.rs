@@ -0,0 +1,196 @@
+use super::{call_stack::addr_str, Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::{ChildWindow, Selectable};
+use ness_core::{debugger::call_stack::RoutineStats, emu::Emu, symbols::SymbolTable};
+use std::{cmp::Ordering, sync::Arc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SortColumn {
+    Routine,
+    Calls,
+    Inclusive,
+    Exclusive,
+}
+
+static COLUMNS: &[(SortColumn, &str)] = &[
+    (SortColumn::Routine, "Routine"),
+    (SortColumn::Calls, "Calls"),
+    (SortColumn::Inclusive, "Inclusive cycles"),
+    (SortColumn::Exclusive, "Exclusive cycles"),
+];
+
+pub struct Profiler {
+    routines: Vec<(u32, RoutineStats)>,
+    symbols: Arc<SymbolTable>,
+    sort_column: SortColumn,
+    sort_descending: bool,
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    reset: bool,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    routines: Vec<(u32, RoutineStats)>,
+    symbols: Arc<SymbolTable>,
+}
+
+impl Profiler {
+    fn sort(&mut self) {
+        let (sort_column, sort_descending) = (self.sort_column, self.sort_descending);
+        self.routines.sort_unstable_by(|(a_addr, a), (b_addr, b)| {
+            let ordering = match sort_column {
+                SortColumn::Routine => Ordering::Equal,
+                SortColumn::Calls => a.calls.cmp(&b.calls),
+                SortColumn::Inclusive => a.inclusive_cycles.cmp(&b.inclusive_cycles),
+                SortColumn::Exclusive => a.exclusive_cycles.cmp(&b.exclusive_cycles),
+            }
+            .then(a_addr.cmp(b_addr));
+            if sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+impl View for Profiler {
+    const NAME: &'static str = "Profiler";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        Profiler {
+            routines: Vec::new(),
+            symbols: Arc::new(SymbolTable::new()),
+            sort_column: SortColumn::Exclusive,
+            sort_descending: true,
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState { reset: false }
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        if core::mem::take(&mut emu_state.reset) {
+            if let Some(call_stack) = &mut emu.debugger.call_stack {
+                call_stack.profiler.reset();
+            }
+        }
+        false
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let call_stack = match &emu.debugger.call_stack {
+            Some(call_stack) => call_stack,
+            None => return,
+        };
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            routines: Vec::new(),
+            symbols: Arc::clone(&emu.cpu.symbols),
+        });
+        frame_data.routines.clear();
+        frame_data
+            .routines
+            .extend(call_stack.profiler.routines().iter().map(|(k, v)| (*k, *v)));
+        frame_data.symbols = Arc::clone(&emu.cpu.symbols);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        self.routines.clear();
+        self.routines.extend_from_slice(&frame_data.routines);
+        self.symbols = Arc::clone(&frame_data.symbols);
+        self.sort();
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        emu_running: bool,
+        actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let reset = ui.button("Reset") && emu_running;
+        ui.same_line();
+        ui.text_disabled("Cycles are counted in master clock cycles once each routine returns");
+        ui.separator();
+
+        let _mono_font = ui.push_font(window.mono_font);
+
+        let total_cycles = self
+            .routines
+            .iter()
+            .map(|(_, stats)| stats.exclusive_cycles)
+            .sum::<u64>()
+            .max(1);
+
+        ChildWindow::new("##routines")
+            .movable(false)
+            .size([0.0, 0.0])
+            .build(ui, || {
+                ui.columns(COLUMNS.len() as i32, "##columns", true);
+                for &(column, name) in COLUMNS {
+                    let label = if column == self.sort_column {
+                        format!("{} {}", name, if self.sort_descending { "v" } else { "^" })
+                    } else {
+                        name.to_string()
+                    };
+                    if Selectable::new(&label).build(ui) {
+                        if self.sort_column == column {
+                            self.sort_descending = !self.sort_descending;
+                        } else {
+                            self.sort_column = column;
+                            self.sort_descending = column != SortColumn::Routine;
+                        }
+                        self.sort();
+                    }
+                    ui.next_column();
+                }
+                ui.separator();
+
+                for (i, (addr, stats)) in self.routines.iter().enumerate() {
+                    let _id = ui.push_id(i as i32);
+                    if Selectable::new(&addr_str(*addr, &self.symbols)).build(ui) {
+                        actions.show_cpu_disasm = Some(*addr);
+                    }
+                    ui.next_column();
+                    ui.text(&stats.calls.to_string());
+                    ui.next_column();
+                    ui.text(&format!(
+                        "{} ({:.1}%)",
+                        stats.inclusive_cycles,
+                        stats.inclusive_cycles as f64 * 100.0 / total_cycles as f64
+                    ));
+                    ui.next_column();
+                    ui.text(&format!(
+                        "{} ({:.1}%)",
+                        stats.exclusive_cycles,
+                        stats.exclusive_cycles as f64 * 100.0 / total_cycles as f64
+                    ));
+                    ui.next_column();
+                }
+                ui.columns(1, "##columns", false);
+            });
+
+        reset.then_some(EmuState { reset: true })
+    }
+}
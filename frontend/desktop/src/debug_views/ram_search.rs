@@ -0,0 +1,442 @@
+use super::{Actions, FrameDataSlot, View};
+use crate::ui::window::Window;
+use imgui::{ChildWindow, ListClipper, Selectable};
+use ness_core::emu::Emu;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Wram,
+    Sram,
+    SpcRam,
+}
+
+impl Region {
+    const ALL: [Region; 3] = [Region::Wram, Region::Sram, Region::SpcRam];
+    const NAMES: [&'static str; 3] = ["WRAM", "SRAM", "SPC700 RAM"];
+
+    fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+
+    fn contents(self, emu: &Emu) -> &[u8] {
+        match self {
+            Region::Wram => &emu.wram.contents[..],
+            Region::Sram => &emu.cart.ram()[..],
+            Region::SpcRam => &emu.apu.spc700.memory[..],
+        }
+    }
+
+    fn write(self, emu: &mut Emu, addr: usize, value: u8) {
+        match self {
+            Region::Wram => emu.wram.contents[addr] = value,
+            Region::Sram => emu.cart.modify_ram(|ram| ram[addr] = value),
+            Region::SpcRam => emu.apu.spc700.memory[addr] = value,
+        }
+    }
+
+    fn addr_digits(self) -> usize {
+        match self {
+            Region::Wram | Region::Sram => 6,
+            Region::SpcRam => 4,
+        }
+    }
+
+    /// Returns the address that should be displayed for the given offset in the region; for WRAM
+    /// it's the CPU bus address (in banks 0x7E-0x7F).
+    fn display_addr(self, offset: u32) -> u32 {
+        match self {
+            Region::Wram => 0x7E_0000 | offset,
+            Region::Sram | Region::SpcRam => offset,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interp {
+    Unsigned,
+    Signed,
+    Bcd,
+}
+
+impl Interp {
+    const ALL: [Interp; 3] = [Interp::Unsigned, Interp::Signed, Interp::Bcd];
+    const NAMES: [&'static str; 3] = ["Unsigned", "Signed", "BCD"];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Filter {
+    Equal,
+    Changed,
+    Increased,
+    Decreased,
+    EqualTo,
+}
+
+impl Filter {
+    const ALL: [Filter; 5] = [
+        Filter::Equal,
+        Filter::Changed,
+        Filter::Increased,
+        Filter::Decreased,
+        Filter::EqualTo,
+    ];
+    const NAMES: [&'static str; 5] = [
+        "Unchanged",
+        "Changed",
+        "Increased",
+        "Decreased",
+        "Equal to value",
+    ];
+}
+
+fn read_raw(data: &[u8], offset: u32, size: u8) -> Option<u32> {
+    let bytes = data.get(offset as usize..offset as usize + size as usize)?;
+    Some(
+        bytes
+            .iter()
+            .rev()
+            .fold(0, |acc, byte| acc << 8 | *byte as u32),
+    )
+}
+
+fn interpret(raw: u32, size: u8, interp: Interp) -> i64 {
+    match interp {
+        Interp::Unsigned => raw as i64,
+        Interp::Signed => {
+            let shift = 32 - size as u32 * 8;
+            ((raw << shift) as i32 >> shift) as i64
+        }
+        Interp::Bcd => {
+            let mut result = 0;
+            for i in (0..size as u32 * 2).rev() {
+                result = result * 10 + (raw >> (i * 4) & 0xF) as i64;
+            }
+            result
+        }
+    }
+}
+
+fn parse_value(input: &str, size: u8, interp: Interp) -> Option<i64> {
+    let input = input.trim();
+    if let Some(hex) = input.strip_prefix("0x").or_else(|| input.strip_prefix('$')) {
+        // Hex input is the raw value in memory, which gets interpreted like the searched values
+        let raw = u32::from_str_radix(hex, 16).ok()?;
+        if (raw as u64) >> (size as u32 * 8) != 0 {
+            return None;
+        }
+        return Some(interpret(raw, size, interp));
+    }
+    match interp {
+        Interp::Signed => input.parse().ok(),
+        Interp::Unsigned | Interp::Bcd => input.parse::<u32>().ok().map(|v| v as i64),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pin {
+    region: Region,
+    offset: u32,
+    size: u8,
+    raw_value: u32,
+}
+
+pub struct RamSearch {
+    region: Region,
+    size: u8,
+    interp: Interp,
+    filter: Filter,
+    value_input: String,
+    data: Vec<u8>,
+    snapshot: Vec<u8>,
+    candidates: Option<Vec<u32>>,
+    pins: Vec<Pin>,
+}
+
+#[derive(Clone)]
+pub struct EmuState {
+    region: Region,
+    pins: Vec<Pin>,
+}
+
+pub struct FrameData {
+    region: Region,
+    data: Vec<u8>,
+}
+
+impl RamSearch {
+    fn reset(&mut self) {
+        self.snapshot.clear();
+        self.snapshot.extend_from_slice(&self.data);
+        self.candidates = None;
+    }
+
+    fn apply_filter(&mut self, value: Option<i64>) {
+        let (size, interp, filter) = (self.size, self.interp, self.filter);
+        let (data, snapshot) = (&self.data, &self.snapshot);
+        let matches = |offset: u32| {
+            let cur = match read_raw(data, offset, size) {
+                Some(raw) => interpret(raw, size, interp),
+                None => return false,
+            };
+            let prev = match read_raw(snapshot, offset, size) {
+                Some(raw) => interpret(raw, size, interp),
+                None => return false,
+            };
+            match filter {
+                Filter::Equal => cur == prev,
+                Filter::Changed => cur != prev,
+                Filter::Increased => cur > prev,
+                Filter::Decreased => cur < prev,
+                Filter::EqualTo => Some(cur) == value,
+            }
+        };
+        self.candidates = Some(match self.candidates.take() {
+            Some(mut candidates) => {
+                candidates.retain(|offset| matches(*offset));
+                candidates
+            }
+            None => (0..data.len() as u32)
+                .filter(|offset| matches(*offset))
+                .collect(),
+        });
+        self.snapshot.clear();
+        self.snapshot.extend_from_slice(&self.data);
+    }
+}
+
+impl View for RamSearch {
+    const NAME: &'static str = "RAM search";
+
+    type FrameData = FrameData;
+    type EmuState = EmuState;
+
+    fn new(_window: &mut Window) -> Self {
+        RamSearch {
+            region: Region::Wram,
+            size: 1,
+            interp: Interp::Unsigned,
+            filter: Filter::Equal,
+            value_input: String::new(),
+            data: Vec::new(),
+            snapshot: Vec::new(),
+            candidates: None,
+            pins: Vec::new(),
+        }
+    }
+
+    fn destroy(self, _window: &mut Window) {}
+
+    fn emu_state(&self) -> Self::EmuState {
+        EmuState {
+            region: self.region,
+            pins: self.pins.clone(),
+        }
+    }
+
+    fn finish_frame(emu_state: &Self::EmuState, emu: &mut Emu) {
+        for pin in &emu_state.pins {
+            for i in 0..pin.size as u32 {
+                let offset = (pin.offset + i) as usize;
+                let value = (pin.raw_value >> (i * 8)) as u8;
+                if matches!(pin.region.contents(emu).get(offset), Some(prev) if *prev != value) {
+                    pin.region.write(emu, offset, value);
+                }
+            }
+        }
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            region: emu_state.region,
+            data: Vec::new(),
+        });
+        frame_data.region = emu_state.region;
+        frame_data.data.clear();
+        frame_data
+            .data
+            .extend_from_slice(emu_state.region.contents(emu));
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, _window: &mut Window) {
+        if frame_data.region != self.region {
+            return;
+        }
+        let first_data = self.data.is_empty();
+        self.data.clear();
+        self.data.extend_from_slice(&frame_data.data);
+        if first_data {
+            self.reset();
+        }
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let mut emu_state_changed = false;
+
+        let mut region_index = self.region as usize;
+        ui.set_next_item_width(ui.calc_text_size("SPC700 RAM")[0] * 2.0);
+        if ui.combo_simple_string("Region", &mut region_index, &Region::NAMES) {
+            self.region = Region::ALL[region_index];
+            self.data.clear();
+            self.snapshot.clear();
+            self.candidates = None;
+            emu_state_changed = true;
+        }
+
+        ui.same_line();
+        let mut size_index = self.size as usize - 1;
+        ui.set_next_item_width(ui.calc_text_size("24-bit")[0] * 2.0);
+        if ui.combo_simple_string("Size", &mut size_index, &["8-bit", "16-bit", "24-bit"]) {
+            self.size = size_index as u8 + 1;
+        }
+
+        ui.same_line();
+        let mut interp_index = self.interp as usize;
+        ui.set_next_item_width(ui.calc_text_size("Unsigned")[0] * 2.0);
+        if ui.combo_simple_string("Type", &mut interp_index, &Interp::NAMES) {
+            self.interp = Interp::ALL[interp_index];
+        }
+
+        let mut filter_index = self.filter as usize;
+        ui.set_next_item_width(ui.calc_text_size("Equal to value")[0] * 1.5);
+        if ui.combo_simple_string("##filter", &mut filter_index, &Filter::NAMES) {
+            self.filter = Filter::ALL[filter_index];
+        }
+        if self.filter == Filter::EqualTo {
+            ui.same_line();
+            ui.set_next_item_width(ui.calc_text_size("00000000")[0] * 1.5);
+            ui.input_text("##value", &mut self.value_input)
+                .hint("Value")
+                .build();
+        }
+
+        ui.same_line();
+        let value = parse_value(&self.value_input, self.size, self.interp);
+        let can_filter =
+            !self.data.is_empty() && (self.filter != Filter::EqualTo || value.is_some());
+        if ui.button("Filter") && can_filter {
+            self.apply_filter(value);
+        }
+        ui.same_line();
+        if ui.button("Reset") {
+            self.reset();
+        }
+
+        ui.same_line();
+        match &self.candidates {
+            Some(candidates) => ui.text(&format!("{} results", candidates.len())),
+            None => ui.text_disabled("No filters applied"),
+        }
+
+        let _mono_font = ui.push_font(window.mono_font);
+
+        if !self.pins.is_empty() {
+            ui.separator();
+            ui.text("Pinned:");
+            let mut remove = None;
+            for (i, pin) in self.pins.iter().enumerate() {
+                let _id = ui.push_id(i as i32);
+                if ui.small_button("Unpin") {
+                    remove = Some(i);
+                }
+                ui.same_line();
+                ui.text(&format!(
+                    "{} {:0addr_digits$X} = {}",
+                    pin.region.name(),
+                    pin.region.display_addr(pin.offset),
+                    interpret(pin.raw_value, pin.size, self.interp),
+                    addr_digits = pin.region.addr_digits(),
+                ));
+            }
+            if let Some(i) = remove {
+                self.pins.remove(i);
+                emu_state_changed = true;
+            }
+        }
+
+        ui.separator();
+
+        let candidates_len = match &self.candidates {
+            Some(candidates) => candidates.len(),
+            None => self.data.len().saturating_sub(self.size as usize - 1),
+        };
+        ChildWindow::new("##results")
+            .movable(false)
+            .size([0.0, 0.0])
+            .build(ui, || {
+                let mut clipper = ListClipper::new(candidates_len as i32).begin(ui);
+                while clipper.step() {
+                    for i in clipper.display_start()..clipper.display_end() {
+                        let offset = match &self.candidates {
+                            Some(candidates) => candidates[i as usize],
+                            None => i as u32,
+                        };
+                        let (cur, prev) = match (
+                            read_raw(&self.data, offset, self.size),
+                            read_raw(&self.snapshot, offset, self.size),
+                        ) {
+                            (Some(cur), Some(prev)) => (cur, prev),
+                            _ => continue,
+                        };
+
+                        let _id = ui.push_id(i);
+                        if ui.small_button("Pin") {
+                            self.pins.push(Pin {
+                                region: self.region,
+                                offset,
+                                size: self.size,
+                                raw_value: cur,
+                            });
+                            emu_state_changed = true;
+                        }
+                        ui.same_line();
+                        if Selectable::new(&format!(
+                            "{:0addr_digits$X}: {:<11} (was {})",
+                            self.region.display_addr(offset),
+                            interpret(cur, self.size, self.interp),
+                            interpret(prev, self.size, self.interp),
+                            addr_digits = self.region.addr_digits(),
+                        ))
+                        .build(ui)
+                        {
+                            match self.region {
+                                Region::Wram => {
+                                    actions.show_cpu_memory = Some(0x7E_0000 | offset);
+                                }
+                                Region::SpcRam => {
+                                    actions.show_spc_memory = Some(offset as u16);
+                                }
+                                Region::Sram => {}
+                            }
+                        }
+                        if self.region != Region::Sram && ui.is_item_hovered() {
+                            ui.tooltip_text("Click to show in the memory viewer");
+                        }
+                    }
+                }
+            });
+
+        if emu_state_changed {
+            Some(self.emu_state())
+        } else {
+            None
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use super::{FrameDataSlot, View};
+use super::{
+    common::step::{spc_step_controls, start_requested_step},
+    Actions, FrameDataSlot, View,
+};
 use crate::ui::window::Window;
 use imgui::{ChildWindow, StyleColor};
 use ness_core::{
     apu::spc700::disasm::{disassemble_count_with_apu_state, Instr},
+    debugger::step::Step,
     emu::Emu,
+    symbols::SymbolTable,
 };
+use std::sync::Arc;
 
 pub struct SpcDisasm {
     start_addr_input: String,
@@ -13,18 +19,21 @@ pub struct SpcDisasm {
     lines: u16,
     pc: u16,
     instrs: Vec<Instr>,
+    symbols: Arc<SymbolTable>,
 }
 
 #[derive(Clone)]
 pub struct EmuState {
     start_addr: u16,
     lines: u16,
+    step: Option<Step>,
 }
 
 #[derive(Clone)]
 pub struct FrameData {
     pc: u16,
     instrs: Vec<Instr>,
+    symbols: Arc<SymbolTable>,
 }
 
 impl View for SpcDisasm {
@@ -41,6 +50,7 @@ impl View for SpcDisasm {
             lines: 32,
             pc: 0,
             instrs: Vec::new(),
+            symbols: Arc::new(SymbolTable::new()),
         }
     }
 
@@ -50,9 +60,14 @@ impl View for SpcDisasm {
         EmuState {
             start_addr: 0,
             lines: 32,
+            step: None,
         }
     }
 
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        start_requested_step(&mut emu_state.step, emu)
+    }
+
     fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
         emu_state: &Self::EmuState,
         emu: &mut Emu,
@@ -61,8 +76,10 @@ impl View for SpcDisasm {
         let frame_data = frame_data.get_or_insert_with(|| FrameData {
             pc: 0,
             instrs: Vec::new(),
+            symbols: Arc::clone(&emu.apu.spc700.symbols),
         });
         frame_data.pc = emu.apu.spc700.regs.pc;
+        frame_data.symbols = Arc::clone(&emu.apu.spc700.symbols);
         frame_data.instrs.clear();
         disassemble_count_with_apu_state(
             &mut emu.apu,
@@ -76,6 +93,7 @@ impl View for SpcDisasm {
         self.pc = frame_data.pc;
         self.instrs.clear();
         self.instrs.extend_from_slice(&frame_data.instrs);
+        self.symbols = Arc::clone(&frame_data.symbols);
     }
 
     fn customize_window<'a, T: AsRef<str>>(
@@ -90,12 +108,17 @@ impl View for SpcDisasm {
         &mut self,
         ui: &imgui::Ui,
         window: &mut Window,
-        _emu_running: bool,
+        emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
         let mut emu_state_changed = false;
 
         let _mono_font = ui.push_font(window.mono_font);
         let style = ui.clone_style();
+
+        let step = spc_step_controls(ui, emu_running);
+        ui.separator();
+
         ui.align_text_to_frame_padding();
 
         if ui.button("Disassemble at PC") {
@@ -105,20 +128,28 @@ impl View for SpcDisasm {
 
         ui.same_line();
 
-        ui.set_next_item_width(ui.calc_text_size("0000")[0] + style.frame_padding[0] * 2.0);
+        ui.set_next_item_width(ui.calc_text_size("0000000000")[0] + style.frame_padding[0] * 2.0);
         if ui
             .input_text("##address", &mut self.start_addr_input)
             .auto_select_all(true)
-            .chars_hexadecimal(true)
             .enter_returns_true(true)
-            .no_horizontal_scroll(true)
             .build()
         {
-            if let Ok(addr) = u16::from_str_radix(&self.start_addr_input, 16) {
-                self.start_addr = addr.clamp(0, 0xFFFF);
+            // Accept either a hexadecimal address or a label name
+            let input = self.start_addr_input.trim();
+            if let Some(addr) = self
+                .symbols
+                .addr_of(input)
+                .map(|addr| addr as u16)
+                .or_else(|| u16::from_str_radix(input.trim_start_matches('$'), 16).ok())
+            {
+                self.start_addr = addr;
                 self.start_addr_changed = true;
             }
         }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Address or label");
+        }
 
         ui.same_line();
 
@@ -149,22 +180,41 @@ impl View for SpcDisasm {
             .movable(false)
             .size([0.0, 0.0])
             .build(ui, || {
+                let mut comments = String::new();
                 for instr in &self.instrs {
-                    ui.text(&format!("{:06X}: {}", instr.addr, instr.opcode));
+                    comments.clear();
                     if !instr.op_addr.is_empty() {
-                        ui.same_line_with_spacing(0.0, 0.0);
+                        comments += " ; ";
+                        comments += &instr.op_addr;
+                    }
+                    if !instr.comment.is_empty() {
+                        if comments.is_empty() {
+                            comments += " ; ";
+                        } else {
+                            comments += ", ";
+                        }
+                        comments += &instr.comment;
+                    }
+
+                    if let Some(label) = &instr.label {
                         ui.text_colored(
-                            ui.style_color(StyleColor::TextDisabled),
-                            &format!(" ; {}", instr.op_addr),
+                            ui.style_color(StyleColor::PlotLinesHovered),
+                            &format!("{}:", label),
                         );
                     }
+                    ui.text(&format!("{:06X}: {}", instr.addr, instr.opcode));
+                    if !comments.is_empty() {
+                        ui.same_line_with_spacing(0.0, 0.0);
+                        ui.text_colored(ui.style_color(StyleColor::TextDisabled), &comments);
+                    }
                 }
             });
 
-        if emu_state_changed {
+        if emu_state_changed || step.is_some() {
             Some(EmuState {
                 start_addr: self.start_addr,
                 lines: self.lines,
+                step,
             })
         } else {
             None
@@ -1,6 +1,6 @@
 use super::{
     common::memory::{MemoryEditor, RangeInclusive},
-    FrameDataSlot, View,
+    Actions, FrameDataSlot, View,
 };
 use crate::ui::window::Window;
 use ness_core::{apu::spc700::bus, emu::Emu};
@@ -22,6 +22,12 @@ pub struct MemContents {
     data: Vec<u8>,
 }
 
+impl SpcMemory {
+    pub fn show_addr(&mut self, addr: u16) {
+        self.editor.set_selected_addr(addr as u64);
+    }
+}
+
 impl View for SpcMemory {
     const NAME: &'static str = "SPC700 memory";
 
@@ -90,6 +96,7 @@ impl View for SpcMemory {
         ui: &imgui::Ui,
         window: &mut Window,
         _emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
         let _mono_font = ui.push_font(window.mono_font);
 
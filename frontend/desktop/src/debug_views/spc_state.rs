@@ -1,10 +1,13 @@
 use super::{
-    common::regs::{bitfield, regs, BitfieldCommand, MaxWidth, RegCommand, RegValue},
-    FrameDataSlot, View,
+    common::{
+        regs::{bitfield, regs, BitfieldCommand, MaxWidth, RegCommand, RegValue},
+        step::{spc_step_controls, start_requested_step},
+    },
+    Actions, FrameDataSlot, View,
 };
 use crate::ui::window::Window;
 use imgui::StyleVar;
-use ness_core::{apu::spc700::regs::Psw, emu::Emu};
+use ness_core::{apu::spc700::regs::Psw, debugger::step::Step, emu::Emu};
 
 #[derive(Clone, Debug)]
 pub struct RegValues {
@@ -24,7 +27,7 @@ impl View for SpcState {
     const NAME: &'static str = "SPC700 state";
 
     type FrameData = RegValues;
-    type EmuState = ();
+    type EmuState = Option<Step>;
 
     fn new(_window: &mut Window) -> Self {
         SpcState { reg_values: None }
@@ -32,7 +35,13 @@ impl View for SpcState {
 
     fn destroy(self, _window: &mut Window) {}
 
-    fn emu_state(&self) -> Self::EmuState {}
+    fn emu_state(&self) -> Self::EmuState {
+        None
+    }
+
+    fn apply_emu_state(emu_state: &mut Self::EmuState, emu: &mut Emu) -> bool {
+        start_requested_step(emu_state, emu)
+    }
 
     fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
         _emu_state: &Self::EmuState,
@@ -65,8 +74,12 @@ impl View for SpcState {
         &mut self,
         ui: &imgui::Ui,
         window: &mut Window,
-        _emu_running: bool,
+        emu_running: bool,
+        _actions: &mut Actions,
     ) -> Option<Self::EmuState> {
+        let step = spc_step_controls(ui, emu_running);
+        ui.separator();
+
         if let Some(reg_values) = self.reg_values.as_mut() {
             let _mono_font = ui.push_font(window.mono_font);
             let _frame_rounding = ui.push_style_var(StyleVar::FrameRounding(0.0));
@@ -118,6 +131,6 @@ impl View for SpcState {
                 ],
             );
         }
-        None
+        step.map(Some)
     }
 }
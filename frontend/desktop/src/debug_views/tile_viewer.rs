@@ -0,0 +1,300 @@
+use super::{
+    common::image::{create_texture, pick_png_path, rgb5_to_rgba8, save_png, set_texture_rows},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use imgui::{Image, TextureId};
+use ness_core::emu::Emu;
+
+const TILES_PER_ROW: usize = 16;
+const SHEET_WIDTH: usize = TILES_PER_ROW * 8;
+const MAX_SHEET_HEIGHT: usize = 0x1_0000 / (16 * TILES_PER_ROW) * 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileFormat {
+    Bpp2,
+    Bpp4,
+    Bpp8,
+    Mode7,
+}
+
+impl TileFormat {
+    const ALL: [TileFormat; 4] = [
+        TileFormat::Bpp2,
+        TileFormat::Bpp4,
+        TileFormat::Bpp8,
+        TileFormat::Mode7,
+    ];
+    const NAMES: [&'static str; 4] = ["2bpp", "4bpp", "8bpp", "Mode 7"];
+
+    pub fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+
+    pub fn bytes_per_tile(self) -> usize {
+        match self {
+            TileFormat::Bpp2 => 16,
+            TileFormat::Bpp4 => 32,
+            TileFormat::Bpp8 => 64,
+            // Mode 7 tiles only use the high byte of each VRAM word
+            TileFormat::Mode7 => 128,
+        }
+    }
+
+    pub fn colors(self) -> usize {
+        match self {
+            TileFormat::Bpp2 => 4,
+            TileFormat::Bpp4 => 16,
+            TileFormat::Bpp8 | TileFormat::Mode7 => 256,
+        }
+    }
+
+    fn tiles(self) -> usize {
+        match self {
+            TileFormat::Mode7 => 256,
+            _ => 0x1_0000 / self.bytes_per_tile(),
+        }
+    }
+}
+
+/// Returns the color index of the pixel at `(x, y)` in the tile starting at byte address
+/// `tile_addr` in VRAM.
+pub fn tile_pixel(vram: &[u8], format: TileFormat, tile_addr: usize, x: usize, y: usize) -> u8 {
+    if format == TileFormat::Mode7 {
+        return vram[(tile_addr + (y << 4 | x << 1) + 1) & 0xFFFF];
+    }
+    let shift = 7 - x;
+    let mut result = 0;
+    for plane_pair in 0..format.bytes_per_tile() >> 4 {
+        let addr = (tile_addr + (plane_pair << 4 | y << 1)) & 0xFFFE;
+        let low = vram[addr] >> shift & 1;
+        let high = vram[addr | 1] >> shift & 1;
+        result |= (low | high << 1) << (plane_pair << 1);
+    }
+    result
+}
+
+pub struct TileViewer {
+    texture_id: TextureId,
+    vram: Vec<u8>,
+    palette: Vec<u16>,
+    format: TileFormat,
+    palette_index: i32,
+    grayscale: bool,
+    zoom: i32,
+    pixels: Box<[u8]>,
+    sheet_height: usize,
+    export_status: Option<Result<String, String>>,
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    vram: Vec<u8>,
+    palette: Vec<u16>,
+}
+
+impl TileViewer {
+    fn palettes(&self) -> usize {
+        256 / self.format.colors()
+    }
+
+    fn redraw(&mut self, window: &mut Window) {
+        if self.vram.is_empty() {
+            return;
+        }
+        let colors = self.format.colors();
+        let palette_base = self.palette_index as usize * colors;
+        let bytes_per_tile = self.format.bytes_per_tile();
+        let tiles = self.format.tiles();
+        self.sheet_height = tiles / TILES_PER_ROW * 8;
+
+        for tile in 0..tiles {
+            let tile_x = tile % TILES_PER_ROW * 8;
+            let tile_y = tile / TILES_PER_ROW * 8;
+            let tile_addr = tile * bytes_per_tile;
+            for y in 0..8 {
+                for x in 0..8 {
+                    let index = tile_pixel(&self.vram, self.format, tile_addr, x, y) as usize;
+                    let color = if self.grayscale {
+                        let value = (index * 0xFF / (colors - 1)) as u8;
+                        [value, value, value, 0xFF]
+                    } else {
+                        rgb5_to_rgba8(self.palette[(palette_base + index) & 0xFF])
+                    };
+                    let offset = ((tile_y + y) * SHEET_WIDTH + tile_x + x) << 2;
+                    self.pixels[offset..offset + 4].copy_from_slice(&color);
+                }
+            }
+        }
+
+        set_texture_rows(
+            window,
+            self.texture_id,
+            &self.pixels,
+            self.sheet_height as u32,
+        );
+    }
+
+    fn export(&self) -> Option<Result<String, String>> {
+        let path = pick_png_path("tiles.png")?;
+        let len = SHEET_WIDTH * self.sheet_height * 4;
+        let pixels = self.pixels[..len]
+            .chunks_exact(4)
+            .map(|pixel| u32::from_le_bytes(pixel.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        Some(
+            save_png(&path, &pixels, SHEET_WIDTH, SHEET_WIDTH, self.sheet_height)
+                .map(|_| format!("Exported to {}", path.to_string_lossy())),
+        )
+    }
+}
+
+impl View for TileViewer {
+    const NAME: &'static str = "Tile viewer";
+
+    type FrameData = FrameData;
+    type EmuState = ();
+
+    fn new(window: &mut Window) -> Self {
+        TileViewer {
+            texture_id: create_texture(
+                window,
+                "tile viewer texture",
+                SHEET_WIDTH as u32,
+                MAX_SHEET_HEIGHT as u32,
+            ),
+            vram: Vec::new(),
+            palette: Vec::new(),
+            format: TileFormat::Bpp4,
+            palette_index: 0,
+            grayscale: false,
+            zoom: 2,
+            pixels: vec![0; SHEET_WIDTH * MAX_SHEET_HEIGHT * 4].into_boxed_slice(),
+            sheet_height: 0,
+            export_status: None,
+        }
+    }
+
+    fn destroy(self, window: &mut Window) {
+        window.gfx.imgui.remove_texture(self.texture_id);
+    }
+
+    fn emu_state(&self) -> Self::EmuState {}
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            vram: Vec::new(),
+            palette: Vec::new(),
+        });
+        frame_data.vram.clear();
+        frame_data
+            .vram
+            .extend_from_slice(&emu.ppu.vram.contents[..]);
+        frame_data.palette.clear();
+        frame_data
+            .palette
+            .extend_from_slice(&emu.ppu.palette.contents[..]);
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, window: &mut Window) {
+        self.vram.clear();
+        self.vram.extend_from_slice(&frame_data.vram);
+        self.palette.clear();
+        self.palette.extend_from_slice(&frame_data.palette);
+        self.redraw(window);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.horizontal_scrollbar(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let mut needs_redraw = false;
+
+        let mut format_index = self.format as usize;
+        ui.set_next_item_width(ui.calc_text_size("Mode 7")[0] * 2.0);
+        if ui.combo_simple_string("Format", &mut format_index, &TileFormat::NAMES) {
+            self.format = TileFormat::ALL[format_index];
+            needs_redraw = true;
+        }
+
+        ui.same_line();
+        ui.set_next_item_width(ui.calc_text_size("00")[0] * 6.0);
+        if ui.input_int("Palette", &mut self.palette_index).build() {
+            needs_redraw = true;
+        }
+        self.palette_index = self.palette_index.clamp(0, self.palettes() as i32 - 1);
+        if ui.is_item_hovered() && self.format.colors() == 16 {
+            ui.tooltip_text("Palettes 8-15 are used by sprites");
+        }
+
+        ui.same_line();
+        needs_redraw |= ui.checkbox("Grayscale", &mut self.grayscale);
+
+        ui.same_line();
+        ui.set_next_item_width(ui.calc_text_size("0")[0] * 8.0);
+        ui.input_int("Zoom", &mut self.zoom).build();
+        self.zoom = self.zoom.clamp(1, 8);
+
+        ui.same_line();
+        if ui.button("Export PNG...") && !self.vram.is_empty() {
+            if let Some(status) = self.export() {
+                self.export_status = Some(status);
+            }
+        }
+        match &self.export_status {
+            Some(Ok(message)) => ui.text(message),
+            Some(Err(err)) => ui.text_colored([1.0, 0.3, 0.3, 1.0], err),
+            None => {}
+        }
+        ui.separator();
+
+        if needs_redraw {
+            self.redraw(window);
+        }
+
+        if self.vram.is_empty() {
+            ui.text_disabled("No data available");
+            return None;
+        }
+
+        let zoom = self.zoom as f32;
+        let origin = ui.cursor_screen_pos();
+        Image::new(
+            self.texture_id,
+            [SHEET_WIDTH as f32 * zoom, self.sheet_height as f32 * zoom],
+        )
+        .uv1([1.0, self.sheet_height as f32 / MAX_SHEET_HEIGHT as f32])
+        .build(ui);
+
+        if ui.is_item_hovered() {
+            let mouse_pos = ui.io().mouse_pos;
+            let x = ((mouse_pos[0] - origin[0]) / zoom) as usize / 8;
+            let y = ((mouse_pos[1] - origin[1]) / zoom) as usize / 8;
+            let tile = y * TILES_PER_ROW + x.min(TILES_PER_ROW - 1);
+            let addr = tile * self.format.bytes_per_tile();
+            ui.tooltip_text(format!(
+                "Tile: {:03X}\nVRAM address: {:04X} (word {:04X})",
+                tile,
+                addr,
+                addr >> 1
+            ));
+        }
+
+        None
+    }
+}
@@ -0,0 +1,530 @@
+use super::{
+    common::image::{create_texture, rgb5_to_rgba8, set_texture_rows},
+    tile_viewer::{tile_pixel, TileFormat},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use imgui::{Image, TextureId};
+use ness_core::{emu::Emu, ppu::BgScreenControl};
+
+const MAX_SIZE: usize = 1024;
+
+/// Returns the tile format used by the given background layer in a BG mode, or `None` if the layer
+/// isn't available in it.
+fn layer_format(bg_mode: u8, bg: usize) -> Option<TileFormat> {
+    match (bg_mode, bg) {
+        (0, _) | (1, 2) | (4, 1) | (5, 1) => Some(TileFormat::Bpp2),
+        (1 | 2, 0 | 1) | (3, 1) | (5 | 6, 0) => Some(TileFormat::Bpp4),
+        (3 | 4, 0) => Some(TileFormat::Bpp8),
+        (7, 0) => Some(TileFormat::Mode7),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BgState {
+    screen_control: BgScreenControl,
+    char_base_bytes: u16,
+    large_tiles: bool,
+    x_scroll: u16,
+    y_scroll: u16,
+}
+
+impl BgState {
+    const fn new() -> Self {
+        BgState {
+            screen_control: BgScreenControl(0),
+            char_base_bytes: 0,
+            large_tiles: false,
+            x_scroll: 0,
+            y_scroll: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Mode7State {
+    params: [i16; 4],
+    scroll: [i16; 2],
+    center: [i16; 2],
+}
+
+impl Mode7State {
+    /// Maps a screen position to a position in the Mode 7 plane, ignoring flipping.
+    fn transform(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let [a, b, c, d] = self.params.map(i32::from);
+        let [center_x, center_y] = self.center.map(i32::from);
+        let x = x + self.scroll[0] as i32 - center_x;
+        let y = y + self.scroll[1] as i32 - center_y;
+        (
+            ((a * x + b * y) >> 8) + center_x,
+            ((c * x + d * y) >> 8) + center_y,
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct FrameData {
+    vram: Vec<u8>,
+    palette: Vec<u16>,
+    bg_mode: u8,
+    bgs: [BgState; 4],
+    mode7: Mode7State,
+    view_height: usize,
+}
+
+struct TileInfo {
+    map_word_addr: u16,
+    tile: u16,
+    char_addr: usize,
+    palette: Option<u8>,
+    priority: bool,
+    x_flip: bool,
+    y_flip: bool,
+}
+
+pub struct TilemapViewer {
+    texture_id: TextureId,
+    data: FrameData,
+    bg: usize,
+    show_viewport: bool,
+    zoom: i32,
+    pixels: Box<[u8]>,
+    size: (usize, usize),
+}
+
+impl TilemapViewer {
+    fn read_vram_word(&self, word_addr: u16) -> u16 {
+        let addr = ((word_addr & 0x7FFF) << 1) as usize;
+        u16::from_le_bytes([self.data.vram[addr], self.data.vram[addr + 1]])
+    }
+
+    fn tile_dims(&self) -> (usize, usize) {
+        let bg = &self.data.bgs[self.bg];
+        let height = if bg.large_tiles { 16 } else { 8 };
+        // Modes 5 and 6 always use 16-pixel-wide tiles, as they're hi-res
+        let width = if matches!(self.data.bg_mode, 5 | 6) {
+            16
+        } else {
+            height
+        };
+        (width, height)
+    }
+
+    fn map_dims(&self) -> (usize, usize) {
+        let screen_size = self.data.bgs[self.bg].screen_control.screen_size();
+        (
+            if screen_size & 1 != 0 { 64 } else { 32 },
+            if screen_size & 2 != 0 { 64 } else { 32 },
+        )
+    }
+
+    fn tile_info(&self, format: TileFormat, tile_x: usize, tile_y: usize) -> TileInfo {
+        if format == TileFormat::Mode7 {
+            let map_word_addr = (tile_y << 7 | tile_x) as u16;
+            let tile = self.data.vram[(map_word_addr as usize) << 1] as u16;
+            return TileInfo {
+                map_word_addr,
+                tile,
+                char_addr: (tile as usize) << 7,
+                palette: None,
+                priority: false,
+                x_flip: false,
+                y_flip: false,
+            };
+        }
+
+        let bg = &self.data.bgs[self.bg];
+        let (map_width, _) = self.map_dims();
+        let screen_index = tile_x / 32 + tile_y / 32 * (map_width / 32);
+        let map_word_addr = ((bg.screen_control.screen_base() as u16) << 10)
+            .wrapping_add((screen_index << 10 | (tile_y & 31) << 5 | (tile_x & 31)) as u16);
+        let entry = self.read_vram_word(map_word_addr);
+        let tile = entry & 0x3FF;
+        TileInfo {
+            map_word_addr,
+            tile,
+            char_addr: bg.char_base_bytes as usize + tile as usize * format.bytes_per_tile(),
+            palette: if format == TileFormat::Bpp8 {
+                None
+            } else {
+                Some((entry >> 10 & 7) as u8)
+            },
+            priority: entry & 1 << 13 != 0,
+            x_flip: entry & 1 << 14 != 0,
+            y_flip: entry & 1 << 15 != 0,
+        }
+    }
+
+    fn redraw(&mut self, window: &mut Window) {
+        let format = match layer_format(self.data.bg_mode, self.bg) {
+            Some(format) => format,
+            None => {
+                self.size = (0, 0);
+                return;
+            }
+        };
+        if self.data.vram.is_empty() {
+            return;
+        }
+        let backdrop = rgb5_to_rgba8(self.data.palette[0]);
+
+        if format == TileFormat::Mode7 {
+            self.size = (MAX_SIZE, MAX_SIZE);
+            for tile_y in 0..128 {
+                for tile_x in 0..128 {
+                    let info = self.tile_info(format, tile_x, tile_y);
+                    for y in 0..8 {
+                        for x in 0..8 {
+                            let index = tile_pixel(&self.data.vram, format, info.char_addr, x, y);
+                            let color = if index == 0 {
+                                backdrop
+                            } else {
+                                rgb5_to_rgba8(self.data.palette[index as usize])
+                            };
+                            let offset = ((tile_y << 3 | y) * MAX_SIZE + (tile_x << 3 | x)) << 2;
+                            self.pixels[offset..offset + 4].copy_from_slice(&color);
+                        }
+                    }
+                }
+            }
+        } else {
+            let (tile_width, tile_height) = self.tile_dims();
+            let (map_width, map_height) = self.map_dims();
+            self.size = (map_width * tile_width, map_height * tile_height);
+            let bytes_per_tile = format.bytes_per_tile();
+            // In mode 0, each layer has its own set of 8 palettes
+            let palette_offset = if self.data.bg_mode == 0 {
+                self.bg << 5
+            } else {
+                0
+            };
+
+            for tile_y in 0..map_height {
+                for tile_x in 0..map_width {
+                    let info = self.tile_info(format, tile_x, tile_y);
+                    let palette_base =
+                        palette_offset + info.palette.unwrap_or(0) as usize * format.colors();
+                    for y in 0..tile_height {
+                        let src_y = if info.y_flip { tile_height - 1 - y } else { y };
+                        for x in 0..tile_width {
+                            let src_x = if info.x_flip { tile_width - 1 - x } else { x };
+                            let tile =
+                                (info.tile as usize + (src_x >> 3) + (src_y >> 3 << 4)) & 0x3FF;
+                            let index = tile_pixel(
+                                &self.data.vram,
+                                format,
+                                self.data.bgs[self.bg].char_base_bytes as usize
+                                    + tile * bytes_per_tile,
+                                src_x & 7,
+                                src_y & 7,
+                            );
+                            let color = if index == 0 {
+                                backdrop
+                            } else {
+                                rgb5_to_rgba8(
+                                    self.data.palette[(palette_base + index as usize) & 0xFF],
+                                )
+                            };
+                            let offset =
+                                ((tile_y * tile_height + y) * MAX_SIZE + tile_x * tile_width + x)
+                                    << 2;
+                            self.pixels[offset..offset + 4].copy_from_slice(&color);
+                        }
+                    }
+                }
+            }
+        }
+
+        set_texture_rows(window, self.texture_id, &self.pixels, self.size.1 as u32);
+    }
+
+    fn draw_viewport(&self, ui: &imgui::Ui, format: TileFormat, origin: [f32; 2], zoom: f32) {
+        let draw_list = ui.get_window_draw_list();
+        let color = [1.0, 1.0, 0.0, 1.0];
+        let point = |(x, y): (i32, i32)| [origin[0] + x as f32 * zoom, origin[1] + y as f32 * zoom];
+        let (width, height) = (self.size.0 as i32, self.size.1 as i32);
+        let view_height = self.data.view_height as i32;
+
+        draw_list.with_clip_rect_intersect(
+            origin,
+            [
+                origin[0] + width as f32 * zoom,
+                origin[1] + height as f32 * zoom,
+            ],
+            || {
+                if format == TileFormat::Mode7 {
+                    let corners = [(0, 0), (256, 0), (256, view_height), (0, view_height)]
+                        .map(|corner| point(self.data.mode7.transform(corner)));
+                    for i in 0..4 {
+                        draw_list
+                            .add_line(corners[i], corners[(i + 1) & 3], color)
+                            .thickness(2.0)
+                            .build();
+                    }
+                    return;
+                }
+
+                let bg = &self.data.bgs[self.bg];
+                let view_width = if matches!(self.data.bg_mode, 5 | 6) {
+                    512
+                } else {
+                    256
+                };
+                let x = bg.x_scroll as i32 % width;
+                let y = bg.y_scroll as i32 % height;
+                // Draw wrapped copies of the viewport too, in case it crosses the edges of the
+                // tilemap
+                for x in [x, x - width] {
+                    for y in [y, y - height] {
+                        draw_list
+                            .add_rect(
+                                point((x, y)),
+                                point((x + view_width, y + view_height)),
+                                color,
+                            )
+                            .thickness(2.0)
+                            .build();
+                    }
+                }
+            },
+        );
+    }
+}
+
+impl View for TilemapViewer {
+    const NAME: &'static str = "Tilemap viewer";
+
+    type FrameData = FrameData;
+    type EmuState = ();
+
+    fn new(window: &mut Window) -> Self {
+        TilemapViewer {
+            texture_id: create_texture(
+                window,
+                "tilemap viewer texture",
+                MAX_SIZE as u32,
+                MAX_SIZE as u32,
+            ),
+            data: FrameData {
+                vram: Vec::new(),
+                palette: Vec::new(),
+                bg_mode: 0,
+                bgs: [BgState::new(); 4],
+                mode7: Mode7State {
+                    params: [0; 4],
+                    scroll: [0; 2],
+                    center: [0; 2],
+                },
+                view_height: 224,
+            },
+            bg: 0,
+            show_viewport: true,
+            zoom: 1,
+            pixels: vec![0; MAX_SIZE * MAX_SIZE * 4].into_boxed_slice(),
+            size: (0, 0),
+        }
+    }
+
+    fn destroy(self, window: &mut Window) {
+        window.gfx.imgui.remove_texture(self.texture_id);
+    }
+
+    fn emu_state(&self) -> Self::EmuState {}
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        _emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        let ppu = &emu.ppu;
+        let frame_data = frame_data.get_or_insert_with(|| FrameData {
+            vram: Vec::new(),
+            palette: Vec::new(),
+            bg_mode: 0,
+            bgs: [BgState::new(); 4],
+            mode7: Mode7State {
+                params: [0; 4],
+                scroll: [0; 2],
+                center: [0; 2],
+            },
+            view_height: 0,
+        });
+        frame_data.vram.clear();
+        frame_data.vram.extend_from_slice(&ppu.vram.contents[..]);
+        frame_data.palette.clear();
+        frame_data
+            .palette
+            .extend_from_slice(&ppu.palette.contents[..]);
+
+        let bg_mode_control = ppu.bg_mode_control();
+        frame_data.bg_mode = bg_mode_control.bg_mode();
+        let char_bases = [
+            ppu.bg_char_control_12().bg13_char_base(),
+            ppu.bg_char_control_12().bg24_char_base(),
+            ppu.bg_char_control_34().bg13_char_base(),
+            ppu.bg_char_control_34().bg24_char_base(),
+        ];
+        for (i, bg_state) in frame_data.bgs.iter_mut().enumerate() {
+            let bg = &ppu.bgs[i];
+            *bg_state = BgState {
+                screen_control: bg.screen_control(),
+                char_base_bytes: (char_bases[i] as u16) << 13,
+                large_tiles: bg_mode_control.bg_tile_size_mask() & 1 << i != 0,
+                x_scroll: bg.x_scroll(),
+                y_scroll: bg.y_scroll(),
+            };
+        }
+        frame_data.mode7 = Mode7State {
+            params: ppu.mode7.params,
+            scroll: ppu.mode7.scroll,
+            center: ppu.mode7.center,
+        };
+        frame_data.view_height = ppu.view_height();
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, window: &mut Window) {
+        self.data.vram.clear();
+        self.data.vram.extend_from_slice(&frame_data.vram);
+        self.data.palette.clear();
+        self.data.palette.extend_from_slice(&frame_data.palette);
+        self.data.bg_mode = frame_data.bg_mode;
+        self.data.bgs = frame_data.bgs;
+        self.data.mode7 = frame_data.mode7;
+        self.data.view_height = frame_data.view_height;
+        self.redraw(window);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.horizontal_scrollbar(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        for i in 0..4 {
+            if ui.radio_button_bool(&format!("BG{}", i + 1), self.bg == i) {
+                self.bg = i;
+                self.redraw(window);
+            }
+            ui.same_line();
+        }
+        ui.checkbox("Show viewport", &mut self.show_viewport);
+        ui.same_line();
+        ui.set_next_item_width(ui.calc_text_size("0")[0] * 8.0);
+        ui.input_int("Zoom", &mut self.zoom).build();
+        self.zoom = self.zoom.clamp(1, 4);
+
+        let format = match layer_format(self.data.bg_mode, self.bg) {
+            Some(format) => format,
+            None => {
+                ui.separator();
+                ui.text_disabled(&format!(
+                    "BG{} is not used in mode {}",
+                    self.bg + 1,
+                    self.data.bg_mode
+                ));
+                return None;
+            }
+        };
+        let bg = &self.data.bgs[self.bg];
+        if format == TileFormat::Mode7 {
+            let mode7 = &self.data.mode7;
+            ui.text(&format!(
+                "Mode 7, matrix: {:04X} {:04X} {:04X} {:04X}, scroll: {}, {}, center: {}, {}",
+                mode7.params[0],
+                mode7.params[1],
+                mode7.params[2],
+                mode7.params[3],
+                mode7.scroll[0],
+                mode7.scroll[1],
+                mode7.center[0],
+                mode7.center[1],
+            ));
+        } else {
+            let (tile_width, tile_height) = self.tile_dims();
+            let (map_width, map_height) = self.map_dims();
+            ui.text(&format!(
+                "Mode {}, {}, map: {}x{} at {:04X}, tiles: {}x{} at {:04X}, scroll: {}, {}",
+                self.data.bg_mode,
+                format.name(),
+                map_width,
+                map_height,
+                (bg.screen_control.screen_base() as u16) << 10,
+                tile_width,
+                tile_height,
+                bg.char_base_bytes >> 1,
+                bg.x_scroll,
+                bg.y_scroll,
+            ));
+        }
+        ui.separator();
+
+        if self.data.vram.is_empty() {
+            ui.text_disabled("No data available");
+            return None;
+        }
+
+        let zoom = self.zoom as f32;
+        let origin = ui.cursor_screen_pos();
+        Image::new(
+            self.texture_id,
+            [self.size.0 as f32 * zoom, self.size.1 as f32 * zoom],
+        )
+        .uv1([
+            self.size.0 as f32 / MAX_SIZE as f32,
+            self.size.1 as f32 / MAX_SIZE as f32,
+        ])
+        .build(ui);
+        let hovered = ui.is_item_hovered();
+
+        if self.show_viewport {
+            self.draw_viewport(ui, format, origin, zoom);
+        }
+
+        if hovered {
+            let mouse_pos = ui.io().mouse_pos;
+            let x = ((mouse_pos[0] - origin[0]) / zoom) as usize;
+            let y = ((mouse_pos[1] - origin[1]) / zoom) as usize;
+            let (tile_width, tile_height) = if format == TileFormat::Mode7 {
+                (8, 8)
+            } else {
+                self.tile_dims()
+            };
+            let (tile_x, tile_y) = (x / tile_width, y / tile_height);
+            let info = self.tile_info(format, tile_x, tile_y);
+            let mut text = format!(
+                "Position: {}, {} (tile {}, {})\nMap address: {:04X}\nTile: {:03X} at {:04X}",
+                x,
+                y,
+                tile_x,
+                tile_y,
+                info.map_word_addr,
+                info.tile,
+                (info.char_addr & 0xFFFF) >> 1,
+            );
+            if let Some(palette) = info.palette {
+                text.push_str(&format!("\nPalette: {}", palette));
+            }
+            if format != TileFormat::Mode7 {
+                text.push_str(&format!(
+                    "\nPriority: {}\nFlip: {}{}",
+                    info.priority as u8,
+                    if info.x_flip { "X" } else { "-" },
+                    if info.y_flip { "Y" } else { "-" },
+                ));
+            }
+            ui.tooltip_text(text);
+        }
+
+        None
+    }
+}
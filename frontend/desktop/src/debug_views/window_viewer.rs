@@ -0,0 +1,335 @@
+use super::{
+    common::image::{create_texture, rgb5_to_rgba8, set_texture_rows},
+    Actions, FrameDataSlot, View,
+};
+use crate::ui::window::Window;
+use imgui::{Image, TextureId};
+use ness_core::{
+    emu::Emu,
+    ppu::{
+        capture::{CaptureTarget, CapturedPixel, Layer, LayerPixel, LineCapture},
+        VIEW_WIDTH,
+    },
+};
+
+/// The width of the captured BG lines, which have 512 pixels when hi-res.
+const CAPTURE_WIDTH: usize = VIEW_WIDTH << 1;
+
+const TEXTURE_HEIGHT: usize = 32;
+
+const TRANSPARENT_COLOR: [u8; 4] = [0x20, 0x20, 0x20, 0xFF];
+const MASKED_COLOR: [u8; 4] = [0, 0, 0, 0xFF];
+
+static LAYERS: [(Layer, &str, [u8; 4]); 6] = [
+    (Layer::Bg1, "BG1", [0xFF, 0x50, 0x50, 0xFF]),
+    (Layer::Bg2, "BG2", [0x50, 0xFF, 0x50, 0xFF]),
+    (Layer::Bg3, "BG3", [0x50, 0x80, 0xFF, 0xFF]),
+    (Layer::Bg4, "BG4", [0xFF, 0xE0, 0x40, 0xFF]),
+    (Layer::Obj, "OBJ", [0xFF, 0x50, 0xFF, 0xFF]),
+    (Layer::Backdrop, "Backdrop", [0x80, 0x80, 0x80, 0xFF]),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Strip {
+    Bg(usize),
+    Obj,
+    MainWindow(usize),
+    SubWindow(usize),
+    ColorMathWindow,
+    ForcedBlack,
+    ScreenSource { sub: bool },
+    Screen { sub: bool },
+    ColorMathApplied,
+}
+
+static STRIPS: [(Strip, &str); 22] = [
+    (Strip::Bg(0), "BG1"),
+    (Strip::Bg(1), "BG2"),
+    (Strip::Bg(2), "BG3"),
+    (Strip::Bg(3), "BG4"),
+    (Strip::Obj, "OBJ"),
+    (Strip::MainWindow(0), "BG1 main win"),
+    (Strip::MainWindow(1), "BG2 main win"),
+    (Strip::MainWindow(2), "BG3 main win"),
+    (Strip::MainWindow(3), "BG4 main win"),
+    (Strip::MainWindow(4), "OBJ main win"),
+    (Strip::SubWindow(0), "BG1 sub win"),
+    (Strip::SubWindow(1), "BG2 sub win"),
+    (Strip::SubWindow(2), "BG3 sub win"),
+    (Strip::SubWindow(3), "BG4 sub win"),
+    (Strip::SubWindow(4), "OBJ sub win"),
+    (Strip::ColorMathWindow, "Math window"),
+    (Strip::ForcedBlack, "Forced black"),
+    (Strip::ScreenSource { sub: false }, "Main source"),
+    (Strip::Screen { sub: false }, "Main screen"),
+    (Strip::ScreenSource { sub: true }, "Sub source"),
+    (Strip::Screen { sub: true }, "Sub screen"),
+    (Strip::ColorMathApplied, "Math applied"),
+];
+
+fn layer_info(layer: Layer) -> (&'static str, [u8; 4]) {
+    let (_, name, color) = LAYERS.iter().find(|(l, ..)| *l == layer).unwrap();
+    (name, *color)
+}
+
+fn flag_color(value: bool, color: [u8; 4]) -> [u8; 4] {
+    if value {
+        color
+    } else {
+        MASKED_COLOR
+    }
+}
+
+fn layer_pixel_color(pixel: Option<LayerPixel>) -> [u8; 4] {
+    pixel.map_or(TRANSPARENT_COLOR, |pixel| rgb5_to_rgba8(pixel.color))
+}
+
+pub struct WindowViewer {
+    texture_id: TextureId,
+    pixels: Box<[u8]>,
+    capture: Option<Box<LineCapture>>,
+    target: CaptureTarget,
+    zoom: i32,
+}
+
+impl WindowViewer {
+    fn strip_pixel(capture: &LineCapture, strip: Strip, fb_x: usize) -> [u8; 4] {
+        let x = fb_x >> 1;
+        match strip {
+            Strip::Bg(i) => {
+                let bg_x = if capture.hi_res { fb_x } else { x };
+                layer_pixel_color(capture.bg_pixels[i][bg_x])
+            }
+            Strip::Obj => capture
+                .obj_pixels
+                .map_or(TRANSPARENT_COLOR, |pixels| layer_pixel_color(pixels[x])),
+            Strip::MainWindow(layer) => {
+                flag_color(capture.layer_window_masks[layer][0][x], LAYERS[layer].2)
+            }
+            Strip::SubWindow(layer) => {
+                flag_color(capture.layer_window_masks[layer][1][x], LAYERS[layer].2)
+            }
+            Strip::ColorMathWindow => {
+                flag_color(capture.color_math_window[x], [0xFF, 0xFF, 0xFF, 0xFF])
+            }
+            Strip::ForcedBlack => flag_color(capture.forced_black[x], [0xFF, 0xFF, 0xFF, 0xFF]),
+            Strip::ScreenSource { sub } => {
+                let pixel = if sub {
+                    capture.sub_screen[x]
+                } else {
+                    capture.main_screen[x]
+                };
+                layer_info(pixel.layer).1
+            }
+            Strip::Screen { sub } => {
+                let pixel = if sub {
+                    capture.sub_screen[x]
+                } else {
+                    capture.main_screen[x]
+                };
+                rgb5_to_rgba8(pixel.color)
+            }
+            Strip::ColorMathApplied => {
+                flag_color(capture.color_math_applied[x], [0xFF, 0xFF, 0xFF, 0xFF])
+            }
+        }
+    }
+
+    fn redraw(&mut self, window: &mut Window) {
+        let capture = match &self.capture {
+            Some(capture) => capture,
+            None => return,
+        };
+        for (row, &(strip, _)) in STRIPS.iter().enumerate() {
+            for fb_x in 0..CAPTURE_WIDTH {
+                let offset = (row * CAPTURE_WIDTH + fb_x) << 2;
+                self.pixels[offset..offset + 4]
+                    .copy_from_slice(&Self::strip_pixel(capture, strip, fb_x));
+            }
+        }
+        set_texture_rows(window, self.texture_id, &self.pixels, STRIPS.len() as u32);
+    }
+
+    fn strip_tooltip(capture: &LineCapture, strip: Strip, fb_x: usize) -> String {
+        let x = fb_x >> 1;
+        let layer_pixel = |pixel: Option<LayerPixel>| match pixel {
+            Some(pixel) => format!("Color: {:04X}, priority: {}", pixel.color, pixel.priority),
+            None => "Transparent".to_string(),
+        };
+        let screen_pixel = |pixel: CapturedPixel| {
+            format!(
+                "Layer: {}\nColor: {:04X}",
+                layer_info(pixel.layer).0,
+                pixel.color
+            )
+        };
+        let flag = |value: bool| if value { "Yes" } else { "No" }.to_string();
+        let info = match strip {
+            Strip::Bg(i) => {
+                if capture.hi_res {
+                    format!(
+                        "Hi-res X: {}\n{}",
+                        fb_x,
+                        layer_pixel(capture.bg_pixels[i][fb_x])
+                    )
+                } else {
+                    layer_pixel(capture.bg_pixels[i][x])
+                }
+            }
+            Strip::Obj => match &capture.obj_pixels {
+                Some(pixels) => layer_pixel(pixels[x]),
+                None => "OBJs weren't rendered on this line".to_string(),
+            },
+            Strip::MainWindow(layer) => {
+                format!("Visible: {}", flag(capture.layer_window_masks[layer][0][x]))
+            }
+            Strip::SubWindow(layer) => {
+                format!("Visible: {}", flag(capture.layer_window_masks[layer][1][x]))
+            }
+            Strip::ColorMathWindow => {
+                format!("Color math allowed: {}", flag(capture.color_math_window[x]))
+            }
+            Strip::ForcedBlack => flag(capture.forced_black[x]),
+            Strip::ScreenSource { sub: false } | Strip::Screen { sub: false } => {
+                screen_pixel(capture.main_screen[x])
+            }
+            Strip::ScreenSource { sub: true } | Strip::Screen { sub: true } => {
+                screen_pixel(capture.sub_screen[x])
+            }
+            Strip::ColorMathApplied => flag(capture.color_math_applied[x]),
+        };
+        format!("X: {}\n{}", x, info)
+    }
+}
+
+impl View for WindowViewer {
+    const NAME: &'static str = "Window/color math viewer";
+
+    type FrameData = Box<LineCapture>;
+    type EmuState = CaptureTarget;
+
+    fn new(window: &mut Window) -> Self {
+        WindowViewer {
+            texture_id: create_texture(
+                window,
+                "window viewer texture",
+                CAPTURE_WIDTH as u32,
+                TEXTURE_HEIGHT as u32,
+            ),
+            pixels: vec![0; CAPTURE_WIDTH * TEXTURE_HEIGHT * 4].into_boxed_slice(),
+            capture: None,
+            target: CaptureTarget::Line(0),
+            zoom: 3,
+        }
+    }
+
+    fn destroy(self, window: &mut Window) {
+        window.gfx.imgui.remove_texture(self.texture_id);
+    }
+
+    fn emu_state(&self) -> Self::EmuState {
+        self.target
+    }
+
+    fn prepare_frame_data<'a, S: FrameDataSlot<'a, Self::FrameData>>(
+        emu_state: &Self::EmuState,
+        emu: &mut Emu,
+        frame_data: S,
+    ) {
+        emu.ppu.capture_target = Some(*emu_state);
+        if let Some(capture) = &emu.ppu.capture {
+            frame_data.insert(capture.clone());
+        }
+    }
+
+    fn update_from_frame_data(&mut self, frame_data: &Self::FrameData, window: &mut Window) {
+        self.capture = Some(frame_data.clone());
+        self.redraw(window);
+    }
+
+    fn customize_window<'a, T: AsRef<str>>(
+        &mut self,
+        _ui: &imgui::Ui,
+        window: imgui::Window<'a, T>,
+    ) -> imgui::Window<'a, T> {
+        window.horizontal_scrollbar(true)
+    }
+
+    fn render(
+        &mut self,
+        ui: &imgui::Ui,
+        window: &mut Window,
+        _emu_running: bool,
+        _actions: &mut Actions,
+    ) -> Option<Self::EmuState> {
+        let mut new_target = None;
+
+        let mut latest = self.target == CaptureTarget::Latest;
+        if ui.checkbox("Latest rendered line", &mut latest) {
+            new_target = Some(if latest {
+                CaptureTarget::Latest
+            } else {
+                CaptureTarget::Line(self.capture.as_ref().map_or(0, |capture| capture.line))
+            });
+        }
+        if let CaptureTarget::Line(line) = self.target {
+            ui.same_line();
+            let mut line = line as i32;
+            ui.set_next_item_width(ui.calc_text_size("000")[0] * 5.0);
+            if ui.input_int("Line", &mut line).build() {
+                new_target = Some(CaptureTarget::Line(line.clamp(0, 238) as u16));
+            }
+        }
+        ui.same_line();
+        ui.set_next_item_width(ui.calc_text_size("0")[0] * 8.0);
+        ui.input_int("Zoom", &mut self.zoom).build();
+        self.zoom = self.zoom.clamp(1, 4);
+
+        if let Some(target) = new_target {
+            self.target = target;
+        }
+
+        let capture = match &self.capture {
+            Some(capture) => capture,
+            None => {
+                ui.text_disabled("No line captured yet");
+                return new_target;
+            }
+        };
+        ui.text(&format!(
+            "Line {}, BG mode {}{}",
+            capture.line,
+            capture.bg_mode,
+            if capture.hi_res { ", hi-res" } else { "" }
+        ));
+        ui.separator();
+
+        let _mono_font = ui.push_font(window.mono_font);
+        let label_width = ui.calc_text_size("Forced black")[0] + ui.clone_style().item_spacing[0];
+        let strip_height = ui.text_line_height();
+        let width = VIEW_WIDTH as f32 * self.zoom as f32;
+        for (row, &(strip, name)) in STRIPS.iter().enumerate() {
+            if matches!(
+                strip,
+                Strip::MainWindow(0) | Strip::ColorMathWindow | Strip::ScreenSource { sub: false }
+            ) {
+                ui.separator();
+            }
+            ui.text(name);
+            ui.same_line_with_pos(label_width);
+            let origin = ui.cursor_screen_pos();
+            Image::new(self.texture_id, [width, strip_height])
+                .uv0([0.0, row as f32 / TEXTURE_HEIGHT as f32])
+                .uv1([1.0, (row + 1) as f32 / TEXTURE_HEIGHT as f32])
+                .build(ui);
+            if ui.is_item_hovered() {
+                let fb_x = (((ui.io().mouse_pos[0] - origin[0]) / width * CAPTURE_WIDTH as f32)
+                    as usize)
+                    .min(CAPTURE_WIDTH - 1);
+                ui.tooltip_text(Self::strip_tooltip(capture, strip, fb_x));
+            }
+        }
+
+        new_target
+    }
+}
@@ -1,7 +1,23 @@
-#[cfg(feature = "debug-views")]
-use super::debug_views;
 use super::{audio, config::LaunchConfig, input, triple_buffer, FrameData};
-use ness_core::{apu::dsp::DummyBackend as DummyAudioBackend, cart::Cart, emu::Emu, Model};
+#[cfg(feature = "debug-views")]
+use super::{cdl_logger, debug_views, trace_logger};
+use ness_core::{
+    apu::dsp::DummyBackend as DummyAudioBackend,
+    cart::Cart,
+    cheats::Code as CheatCode,
+    emu::Emu,
+    ppu::{FB_WIDTH, MAX_HD_MODE7_SCALE, MAX_WIDESCREEN_COLUMNS},
+    Model,
+};
+#[cfg(feature = "debug-views")]
+use ness_core::{
+    debugger::{cdl, trace::Log as TraceLog},
+    emu::RunOutcome,
+    symbols::SymbolTable,
+};
+use ness_video_filters::{
+    Filters as VideoFilters, Frame as VideoFrame, Settings as VideoFilterSettings,
+};
 use parking_lot::RwLock;
 use std::{
     fs, hint,
@@ -24,8 +40,26 @@ pub enum Message {
     UpdateSavePath(Option<PathBuf>),
     UpdateAudioSampleChunkSize(u32),
     UpdateAudioSync(bool),
+    UpdateDotAccuratePpu(bool),
+    UpdateUnlimitedSprites(bool),
+    UpdateWidescreenColumns(u8, u8),
+    UpdateHdMode7 {
+        scale: usize,
+        perspective_correction: bool,
+    },
+    UpdateVideoFilters(VideoFilterSettings),
+    UpdateCheats(Vec<CheatCode>),
     #[cfg(feature = "debug-views")]
     DebugViews(debug_views::Message),
+    #[cfg(feature = "debug-views")]
+    UpdateTraceLog(trace_logger::Processor, Option<TraceLog>),
+    #[cfg(feature = "debug-views")]
+    UpdateCdl(cdl_logger::Request),
+    #[cfg(feature = "debug-views")]
+    UpdateSymbols {
+        cpu: Arc<SymbolTable>,
+        spc: Arc<SymbolTable>,
+    },
     SoftReset,
     HardReset,
     Stop,
@@ -51,6 +85,19 @@ pub(super) fn main(
         #[cfg(feature = "log")]
         &logger,
     );
+    emu.ppu.set_dot_accurate(config.dot_accurate_ppu.value);
+    emu.ppu.set_unlimited_objs(config.unlimited_sprites.value);
+    let (left_columns, right_columns) = config.widescreen_columns.value;
+    emu.ppu.set_widescreen_columns(
+        left_columns.min(MAX_WIDESCREEN_COLUMNS as u8),
+        right_columns.min(MAX_WIDESCREEN_COLUMNS as u8),
+    );
+    emu.ppu.set_render_threads(config.ppu_render_threads);
+    emu.ppu.set_hd_mode7(
+        config.hd_mode7_scale.clamp(1, MAX_HD_MODE7_SCALE),
+        config.hd_mode7_perspective_correction,
+    );
+    let mut video_filters = VideoFilters::new(config.video_filters);
 
     let frame_interval = match config.model {
         Model::Ntsc => Duration::from_nanos(1_000_000_000 / 60),
@@ -64,6 +111,7 @@ pub(super) fn main(
     let mut fps = 0.0;
 
     let mut cur_save_path = config.cur_save_path;
+    let mut cheat_codes = Vec::new();
     let mut last_save_flush_time = last_frame_time;
 
     macro_rules! save {
@@ -110,9 +158,91 @@ pub(super) fn main(
                     }
                 }
 
+                Message::UpdateDotAccuratePpu(dot_accurate) => {
+                    emu.ppu.set_dot_accurate(dot_accurate);
+                }
+
+                Message::UpdateUnlimitedSprites(unlimited_sprites) => {
+                    emu.ppu.set_unlimited_objs(unlimited_sprites);
+                }
+
+                Message::UpdateWidescreenColumns(left, right) => {
+                    emu.ppu.set_widescreen_columns(left, right);
+                }
+
+                Message::UpdateHdMode7 {
+                    scale,
+                    perspective_correction,
+                } => {
+                    emu.ppu.set_hd_mode7(scale, perspective_correction);
+                }
+
+                Message::UpdateVideoFilters(settings) => {
+                    video_filters.set_settings(settings);
+                }
+
+                Message::UpdateCheats(codes) => {
+                    emu.set_cheats(&codes);
+                    cheat_codes = codes;
+                }
+
                 #[cfg(feature = "debug-views")]
                 Message::DebugViews(message) => {
-                    debug_views.handle_message(message);
+                    // Steps requested by the views only run while emulation is playing
+                    if debug_views.handle_message(message, &mut emu) {
+                        shared_state.playing.store(true, Ordering::Relaxed);
+                    }
+                }
+
+                #[cfg(feature = "debug-views")]
+                Message::UpdateTraceLog(processor, log) => match processor {
+                    trace_logger::Processor::Cpu => emu.debugger.cpu_trace_log = log,
+                    trace_logger::Processor::Spc => emu.apu.spc700.trace_log = log,
+                },
+
+                #[cfg(feature = "debug-views")]
+                Message::UpdateCdl(request) => match request {
+                    cdl_logger::Request::SetEnabled(enabled) => {
+                        if !enabled {
+                            emu.debugger.cdl = None;
+                        } else if emu.debugger.cdl.is_none() {
+                            emu.debugger.cdl = Some(cdl::Log::new(emu.cart.rom().len()));
+                        }
+                    }
+                    cdl_logger::Request::Reset => {
+                        if let Some(log) = &mut emu.debugger.cdl {
+                            log.reset();
+                        }
+                    }
+                    cdl_logger::Request::Load(path, result_tx) => {
+                        let result = fs::read(&path)
+                            .map_err(|err| format!("Couldn't read CDL file: {}", err))
+                            .and_then(|data| {
+                                cdl::Log::load(&emu.cart.rom()[..], &data).ok_or_else(|| {
+                                    "The CDL file doesn't match the current ROM".to_string()
+                                })
+                            })
+                            .map(|log| emu.debugger.cdl = Some(log));
+                        let _ = result_tx.send(result);
+                    }
+                    cdl_logger::Request::Save(path, result_tx) => {
+                        let result = match &emu.debugger.cdl {
+                            Some(log) => {
+                                let mut data = Vec::with_capacity(log.len() + 9);
+                                log.save(&emu.cart.rom()[..], &mut data)
+                                    .and_then(|_| fs::write(&path, data))
+                                    .map_err(|err| format!("Couldn't save CDL file: {}", err))
+                            }
+                            None => Err("Code/data logging is disabled".to_string()),
+                        };
+                        let _ = result_tx.send(result);
+                    }
+                },
+
+                #[cfg(feature = "debug-views")]
+                Message::UpdateSymbols { cpu, spc } => {
+                    emu.cpu.symbols = cpu;
+                    emu.apu.spc700.symbols = spc;
                 }
 
                 Message::SoftReset => {
@@ -124,6 +254,26 @@ pub(super) fn main(
                         save!(save_path);
                     }
 
+                    #[cfg(feature = "debug-views")]
+                    let trace_logs = (
+                        emu.debugger.cpu_trace_log.take(),
+                        emu.apu.spc700.trace_log.take(),
+                    );
+                    #[cfg(feature = "debug-views")]
+                    let cdl = emu.debugger.cdl.take();
+                    #[cfg(feature = "debug-views")]
+                    let symbols = (
+                        Arc::clone(&emu.cpu.symbols),
+                        Arc::clone(&emu.apu.spc700.symbols),
+                    );
+                    let dot_accurate = emu.ppu.dot_accurate();
+                    let unlimited_objs = emu.ppu.unlimited_objs();
+                    let widescreen_columns = emu.ppu.widescreen_columns();
+                    let render_threads = emu.ppu.render_threads();
+                    let hd_mode7 = (
+                        emu.ppu.hd_mode7_scale(),
+                        emu.ppu.hd_mode7_perspective_correction(),
+                    );
                     emu = Emu::new(
                         config.model,
                         cart.clone(),
@@ -137,6 +287,20 @@ pub(super) fn main(
                         #[cfg(feature = "log")]
                         &logger,
                     );
+                    emu.ppu.set_dot_accurate(dot_accurate);
+                    emu.ppu.set_unlimited_objs(unlimited_objs);
+                    emu.ppu
+                        .set_widescreen_columns(widescreen_columns.0, widescreen_columns.1);
+                    emu.ppu.set_render_threads(render_threads);
+                    emu.ppu.set_hd_mode7(hd_mode7.0, hd_mode7.1);
+                    emu.set_cheats(&cheat_codes);
+                    #[cfg(feature = "debug-views")]
+                    {
+                        (emu.debugger.cpu_trace_log, emu.apu.spc700.trace_log) = trace_logs;
+                        (emu.cpu.symbols, emu.apu.spc700.symbols) = symbols;
+                        emu.debugger.cdl = cdl;
+                        debug_views.update_call_stack_tracking(&mut emu);
+                    }
                 }
 
                 Message::Stop => {
@@ -150,12 +314,80 @@ pub(super) fn main(
         let frame = frame_tx.start();
 
         if playing {
+            #[cfg(feature = "debug-views")]
+            if let RunOutcome::Stopped(_) = emu.run_frame() {
+                shared_state.playing.store(false, Ordering::Relaxed);
+            }
+            #[cfg(not(feature = "debug-views"))]
             emu.run_frame();
         }
-        frame.fb.0.copy_from_slice(&emu.ppu.framebuffer.0);
+        #[cfg(feature = "debug-views")]
+        debug_views.finish_frame(&mut emu);
         frame.view_height = emu.ppu.view_height();
+        frame.view_width = emu.ppu.view_width();
         frame.fb_width = emu.ppu.fb_width();
         frame.fb_height = emu.ppu.fb_height();
+        // Only the part of each line that's in use is copied, as the framebuffer is wide enough
+        // for hi-res lines with the maximum widescreen columns
+        for (dst, src) in frame
+            .fb
+            .0
+            .chunks_exact_mut(FB_WIDTH)
+            .zip(emu.ppu.framebuffer.0.chunks_exact(FB_WIDTH))
+            .take(frame.fb_height)
+        {
+            dst[..frame.fb_width].copy_from_slice(&src[..frame.fb_width]);
+        }
+        let hi_res = frame.fb_width != frame.view_width;
+        let interlace_field =
+            (frame.fb_height != frame.view_height).then_some(emu.ppu.status78().interlace_field());
+        let source_frame = match emu.ppu.hd_framebuffer() {
+            Some(hd_fb) => VideoFrame {
+                pixels: &hd_fb.data,
+                stride: hd_fb.width(),
+                width: frame.fb_width * hd_fb.scale(),
+                height: frame.fb_height * hd_fb.scale(),
+                hi_res,
+                interlace_field,
+                scale: hd_fb.scale(),
+            },
+            None => VideoFrame {
+                pixels: &emu.ppu.framebuffer.0,
+                stride: FB_WIDTH,
+                width: frame.fb_width,
+                height: frame.fb_height,
+                hi_res,
+                interlace_field,
+                scale: 1,
+            },
+        };
+        let output_frame = if video_filters.settings().is_enabled() {
+            Some(video_filters.apply(source_frame))
+        } else {
+            (source_frame.scale != 1).then_some(source_frame)
+        };
+        frame.output_fb.clear();
+        match output_frame {
+            Some(output_frame) => {
+                frame.fb_scale = output_frame.scale;
+                frame.output_width = output_frame.width;
+                frame.output_height = output_frame.height;
+                let stride = FB_WIDTH * output_frame.scale;
+                frame.output_fb.resize(stride * output_frame.height, 0);
+                for (dst, src) in frame
+                    .output_fb
+                    .chunks_exact_mut(stride)
+                    .zip(output_frame.pixels.chunks(output_frame.stride))
+                {
+                    dst[..output_frame.width].copy_from_slice(&src[..output_frame.width]);
+                }
+            }
+            None => {
+                frame.fb_scale = 1;
+                frame.output_width = frame.fb_width;
+                frame.output_height = frame.fb_height;
+            }
+        }
 
         #[cfg(feature = "debug-views")]
         debug_views.prepare_frame_data(&mut emu, &mut frame.debug);
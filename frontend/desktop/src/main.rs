@@ -4,10 +4,18 @@
 mod utils;
 
 mod audio;
+#[cfg(feature = "debug-views")]
+mod cdl_logger;
+mod cheats;
 mod config;
 #[cfg(feature = "debug-views")]
 mod debug_views;
 mod input;
+mod screenshot;
+#[cfg(feature = "debug-views")]
+mod symbols;
+#[cfg(feature = "debug-views")]
+mod trace_logger;
 mod triple_buffer;
 
 mod emu;
@@ -18,9 +26,16 @@ use std::panic;
 
 struct FrameData {
     fb: Box<Framebuffer>,
+    /// The HD Mode 7 and/or filtered frame, laid out like `fb` but `fb_scale` times larger in each
+    /// dimension, with an `output_width` by `output_height` image; if empty, `fb` is shown as is.
+    output_fb: Vec<u32>,
+    fb_scale: usize,
+    output_width: usize,
+    output_height: usize,
     view_height: usize,
     fb_width: usize,
     fb_height: usize,
+    view_width: usize,
     fps: f64,
     #[cfg(feature = "debug-views")]
     debug: debug_views::FrameData,
@@ -30,9 +45,14 @@ impl Default for FrameData {
     fn default() -> Self {
         FrameData {
             fb: zeroed_box(),
+            output_fb: Vec::new(),
+            fb_scale: 1,
+            output_width: 0,
+            output_height: 0,
             fb_width: 0,
             fb_height: 0,
             view_height: 0,
+            view_width: 0,
             fps: 0.0,
             #[cfg(feature = "debug-views")]
             debug: debug_views::FrameData::new(),
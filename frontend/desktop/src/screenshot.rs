@@ -0,0 +1,79 @@
+use super::{utils::timestamp, FrameData};
+use ness_core::ppu::FB_WIDTH;
+use ness_video_filters::encode_png;
+use std::{fs, path::Path, thread};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// The PPU framebuffer at its internal resolution.
+    Native,
+    /// The frame as it's displayed, after HD Mode 7 and video filters, but before being scaled to
+    /// the window.
+    Output,
+}
+
+/// Encodes the requested variant of `frame` and writes it to `dir` on a separate thread, naming
+/// it after the game title and the current time.
+pub fn save(
+    frame: &FrameData,
+    kind: Kind,
+    dir: &Path,
+    game_title: &str,
+    #[cfg(feature = "log")] logger: slog::Logger,
+) {
+    let (pixels, stride, width, height) = match kind {
+        Kind::Output if !frame.output_fb.is_empty() => (
+            frame.output_fb.clone(),
+            FB_WIDTH * frame.fb_scale,
+            frame.output_width,
+            frame.output_height,
+        ),
+        _ => (
+            frame.fb.0[..FB_WIDTH * frame.fb_height].to_vec(),
+            FB_WIDTH,
+            frame.fb_width,
+            frame.fb_height,
+        ),
+    };
+
+    let file_title: String = game_title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            _ => c,
+        })
+        .collect();
+    let path = dir.join(format!(
+        "{} {}{}.png",
+        file_title,
+        timestamp(),
+        if kind == Kind::Native {
+            " (native)"
+        } else {
+            ""
+        },
+    ));
+    let dir = dir.to_path_buf();
+
+    thread::Builder::new()
+        .name("screenshot writer".to_string())
+        .spawn(move || {
+            let result = encode_png(&pixels, stride, width, height)
+                .map_err(|err| format!("Couldn't encode PNG image: {}", err))
+                .and_then(|data| {
+                    fs::create_dir_all(&dir)
+                        .and_then(|_| fs::write(&path, data))
+                        .map_err(|err| format!("Couldn't write file: {}", err))
+                });
+            match result {
+                Ok(()) => {
+                    #[cfg(feature = "log")]
+                    slog::info!(logger, "Saved screenshot to {}", path.display());
+                }
+                Err(err) => {
+                    error!("Couldn't save screenshot", "{}", err);
+                }
+            }
+        })
+        .expect("Couldn't spawn screenshot writer thread");
+}
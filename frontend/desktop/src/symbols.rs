@@ -0,0 +1,39 @@
+use ness_core::symbols::SymbolTable;
+use std::{fs, path::Path};
+
+/// Loads all symbol files found next to the ROM at `rom_path` (`<name>.sym` and `<name>.dbg` for
+/// the 65816, `<name>.spc.sym` for the SPC700), returning the 65816 and SPC700 symbol tables.
+pub fn load_for_rom(rom_path: &Path) -> (SymbolTable, SymbolTable) {
+    let mut cpu_symbols = SymbolTable::new();
+    let mut spc_symbols = SymbolTable::new();
+
+    let read = |extension: &str| {
+        let path = rom_path.with_extension(extension);
+        match fs::read(&path) {
+            Ok(content) => Some(String::from_utf8_lossy(&content).into_owned()),
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    error!(
+                        "Couldn't read symbol file",
+                        "Couldn't read `{}`: {}",
+                        path.display(),
+                        err
+                    );
+                }
+                None
+            }
+        }
+    };
+
+    if let Some(content) = read("sym") {
+        cpu_symbols.extend(SymbolTable::parse_sym(&content));
+    }
+    if let Some(content) = read("dbg") {
+        cpu_symbols.extend(SymbolTable::parse_ca65_dbg(&content));
+    }
+    if let Some(content) = read("spc.sym") {
+        spc_symbols.extend(SymbolTable::parse_sym(&content));
+    }
+
+    (cpu_symbols, spc_symbols)
+}
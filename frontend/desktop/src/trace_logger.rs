@@ -0,0 +1,185 @@
+use imgui::{Ui, Window};
+use ness_core::debugger::trace::Log;
+use rfd::FileDialog;
+use std::{fs::File, io::BufWriter, ops::RangeInclusive, path::PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Processor {
+    Cpu,
+    Spc,
+}
+
+fn parse_pc_ranges(input: &str, max_addr: u32) -> Result<Vec<RangeInclusive<u32>>, String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            let parse_addr = |addr: &str| {
+                u32::from_str_radix(addr.trim_start_matches('$'), 16)
+                    .ok()
+                    .filter(|&addr| addr <= max_addr)
+                    .ok_or_else(|| format!("Invalid address: `{}`", addr))
+            };
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (parse_addr(start)?, parse_addr(end)?),
+                None => {
+                    let addr = parse_addr(range)?;
+                    (addr, addr)
+                }
+            };
+            if end < start {
+                return Err(format!("Invalid range: `{}`", range));
+            }
+            Ok(start..=end)
+        })
+        .collect()
+}
+
+struct Target {
+    processor: Processor,
+    path: Option<PathBuf>,
+    pc_ranges_input: String,
+    active: bool,
+    error: Option<String>,
+}
+
+impl Target {
+    fn new(processor: Processor) -> Self {
+        Target {
+            processor,
+            path: None,
+            pc_ranges_input: String::new(),
+            active: false,
+            error: None,
+        }
+    }
+
+    fn start(&mut self) -> Option<Log> {
+        let path = self.path.as_ref()?;
+        let max_addr = match self.processor {
+            Processor::Cpu => 0xFF_FFFF,
+            Processor::Spc => 0xFFFF,
+        };
+        let pc_ranges = match parse_pc_ranges(&self.pc_ranges_input, max_addr) {
+            Ok(pc_ranges) => pc_ranges,
+            Err(err) => {
+                self.error = Some(err);
+                return None;
+            }
+        };
+        match File::create(path) {
+            Ok(file) => {
+                self.error = None;
+                self.active = true;
+                Some(Log::new(Box::new(BufWriter::new(file)), pc_ranges))
+            }
+            Err(err) => {
+                self.error = Some(format!("Couldn't create trace file: {}", err));
+                None
+            }
+        }
+    }
+
+    fn draw(&mut self, ui: &Ui, emu_running: bool) -> Option<Option<Log>> {
+        let _id = ui.push_id(match self.processor {
+            Processor::Cpu => "cpu",
+            Processor::Spc => "spc",
+        });
+        let mut result = None;
+
+        ui.text(match self.processor {
+            Processor::Cpu => "65816",
+            Processor::Spc => "SPC700",
+        });
+
+        if ui.button("Browse...") {
+            if let Some(path) = FileDialog::new()
+                .add_filter("Trace log", &["log", "txt"])
+                .save_file()
+            {
+                self.path = Some(path);
+            }
+        }
+        ui.same_line();
+        match &self.path {
+            Some(path) => ui.text(&path.to_string_lossy()),
+            None => ui.text_disabled("No file selected"),
+        }
+
+        ui.input_text("PC ranges", &mut self.pc_ranges_input)
+            .hint("e.g. 8000-80FF, C08000")
+            .read_only(self.active)
+            .build();
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Hexadecimal, comma-separated; leave empty to log all instructions");
+        }
+
+        if self.active {
+            if ui.button("Stop") {
+                self.active = false;
+                result = Some(None);
+            }
+        } else if ui.button("Start") && emu_running {
+            if self.path.is_none() {
+                self.error = Some("No file selected".to_string());
+            } else if let Some(log) = self.start() {
+                result = Some(Some(log));
+            }
+        }
+        if let Some(err) = &self.error {
+            ui.same_line();
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], err);
+        }
+
+        result
+    }
+}
+
+pub struct TraceLogger {
+    cpu: Target,
+    spc: Target,
+}
+
+impl TraceLogger {
+    pub fn new() -> Self {
+        TraceLogger {
+            cpu: Target::new(Processor::Cpu),
+            spc: Target::new(Processor::Spc),
+        }
+    }
+
+    /// Draws the trace logger window, returning the trace logs to replace for each processor
+    /// (with `None` stopping logging).
+    pub fn draw(
+        &mut self,
+        ui: &Ui,
+        emu_running: bool,
+        opened: &mut bool,
+    ) -> Vec<(Processor, Option<Log>)> {
+        let mut changes = Vec::new();
+        if !emu_running {
+            self.cpu.active = false;
+            self.spc.active = false;
+        }
+        Window::new("Trace logger")
+            .opened(opened)
+            .always_auto_resize(true)
+            .build(ui, || {
+                for target in [&mut self.cpu, &mut self.spc] {
+                    if let Some(log) = target.draw(ui, emu_running) {
+                        changes.push((target.processor, log));
+                    }
+                    ui.separator();
+                }
+            });
+        if !*opened {
+            for target in [&mut self.cpu, &mut self.spc] {
+                if target.active {
+                    target.active = false;
+                    changes.push((target.processor, None));
+                }
+            }
+        }
+        changes
+    }
+}
@@ -4,20 +4,24 @@ mod imgui_log;
 pub mod imgui_wgpu;
 pub mod window;
 
-#[cfg(feature = "debug-views")]
-use super::debug_views;
 use super::{
-    audio,
+    audio, cheats,
     config::{self, Config, LaunchConfig, LoggingKind},
-    emu, input, triple_buffer,
+    emu, input, screenshot, triple_buffer,
     utils::{config_base, scale_to_fit},
     FrameData,
 };
+#[cfg(feature = "debug-views")]
+use super::{cdl_logger, debug_views, symbols, trace_logger};
 use ness_core::{
     cart,
-    ppu::{FB_HEIGHT, FB_WIDTH, VIEW_HEIGHT_NTSC, VIEW_WIDTH},
-    utils::{zeroed_box, BoxedByteSlice},
+    ppu::{
+        FB_HEIGHT, FB_WIDTH, MAX_HD_MODE7_SCALE, MAX_WIDESCREEN_COLUMNS, VIEW_HEIGHT_NTSC,
+        VIEW_WIDTH,
+    },
+    utils::BoxedByteSlice,
 };
+use ness_video_filters::{HiResMode, InterlaceMode, NtscMode, Scaler};
 use parking_lot::RwLock;
 use rfd::FileDialog;
 #[cfg(feature = "discord-presence")]
@@ -35,6 +39,7 @@ use std::{
     thread,
     time::Duration,
 };
+use winit::event::VirtualKeyCode;
 
 #[cfg(feature = "log")]
 fn init_logging(
@@ -86,6 +91,7 @@ struct UiState {
     screen_focused: bool,
     input: input::State,
     input_editor: Option<input::Editor>,
+    cheats_editor: Option<cheats::Editor>,
 
     audio_channel: Option<audio::Channel>,
     audio_volume: f32,
@@ -93,6 +99,10 @@ struct UiState {
     audio_interp_method: audio::InterpMethod,
     sync_to_audio: config::RuntimeModifiable<bool>,
 
+    dot_accurate_ppu: config::RuntimeModifiable<bool>,
+    unlimited_sprites: config::RuntimeModifiable<bool>,
+    widescreen_columns: config::RuntimeModifiable<(u8, u8)>,
+
     #[cfg(feature = "log")]
     imgui_log: Option<(imgui_log::Console, imgui_log::Sender, bool)>,
     #[cfg(feature = "log")]
@@ -102,12 +112,19 @@ struct UiState {
     frame_rx: triple_buffer::Receiver<FrameData>,
     fps_fixed: Option<u64>,
     fb_texture_id: imgui::TextureId,
+    fb_view_width: usize,
     fb_view_height: usize,
     fb_width: usize,
     fb_height: usize,
+    fb_scale: usize,
+    screenshot_request: Option<screenshot::Kind>,
 
     #[cfg(feature = "debug-views")]
     debug_views: debug_views::UiState,
+    #[cfg(feature = "debug-views")]
+    trace_logger: Option<trace_logger::TraceLogger>,
+    #[cfg(feature = "debug-views")]
+    cdl_logger: Option<cdl_logger::CdlLogger>,
 
     message_tx: crossbeam_channel::Sender<emu::Message>,
     message_rx: crossbeam_channel::Receiver<emu::Message>,
@@ -201,6 +218,19 @@ impl UiState {
         ) {
             Ok(launch_config) => {
                 self.start(launch_config, game_title, game_config, rom, cart_info);
+
+                #[cfg(feature = "debug-views")]
+                if self.emu_thread.is_some() {
+                    let (cpu_symbols, spc_symbols) = symbols::load_for_rom(path);
+                    self.send_message(emu::Message::UpdateSymbols {
+                        cpu: Arc::new(cpu_symbols),
+                        spc: Arc::new(spc_symbols),
+                    });
+                    // Code/data logs are tied to a single ROM, so start over
+                    if self.cdl_logger.is_some() {
+                        self.cdl_logger = Some(cdl_logger::CdlLogger::new());
+                    }
+                }
             }
             Err(errors) => {
                 config_error!(
@@ -240,6 +270,9 @@ impl UiState {
 
         self.limit_framerate = config.limit_framerate;
         self.sync_to_audio = config.sync_to_audio;
+        self.dot_accurate_ppu = config.dot_accurate_ppu;
+        self.unlimited_sprites = config.unlimited_sprites;
+        self.widescreen_columns = config.widescreen_columns;
 
         if let Some(channel) = &mut self.audio_channel {
             channel
@@ -317,6 +350,9 @@ impl UiState {
                 })
                 .expect("Couldn't spawn emulation thread"),
         );
+        self.send_message(emu::Message::UpdateCheats(cheats::enabled_codes(
+            &self.game_config.as_ref().unwrap().contents.cheats,
+        )));
 
         #[cfg(feature = "debug-views")]
         self.debug_views.reload_emu_state();
@@ -346,6 +382,7 @@ impl UiState {
         }
         self.game_title = None;
         self.playing = false;
+        self.screenshot_request = None;
     }
 
     #[cfg(feature = "discord-presence")]
@@ -358,8 +395,38 @@ impl UiState {
     }
 }
 
-fn clear_fb_texture(id: imgui::TextureId, window: &mut window::Window) {
-    let mut data = zeroed_box::<[u8; FB_WIDTH * FB_HEIGHT * 4]>();
+fn create_fb_texture(window: &mut window::Window, scale: usize) -> imgui::TextureId {
+    let texture = window.gfx.imgui.create_texture(
+        &window.gfx.device_state.device,
+        &wgpu::SamplerDescriptor {
+            label: Some("framebuffer sampler"),
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        },
+        imgui_wgpu::TextureDescriptor {
+            label: Some("framebuffer texture".to_string()),
+            size: wgpu::Extent3d {
+                width: (FB_WIDTH * scale) as u32,
+                height: (FB_HEIGHT * scale) as u32,
+                depth_or_array_layers: 1,
+            },
+            format: Some(
+                if window.gfx.device_state.surf_config.format.describe().srgb {
+                    wgpu::TextureFormat::Rgba8UnormSrgb
+                } else {
+                    wgpu::TextureFormat::Rgba8Unorm
+                },
+            ),
+            ..Default::default()
+        },
+    );
+    let id = window.gfx.imgui.add_texture(texture);
+    clear_fb_texture(id, window, scale);
+    id
+}
+
+fn clear_fb_texture(id: imgui::TextureId, window: &mut window::Window, scale: usize) {
+    let mut data = vec![0; FB_WIDTH * FB_HEIGHT * scale * scale * 4];
     for i in (0..data.len()).step_by(4) {
         data[i + 3] = 0xFF;
     }
@@ -448,42 +515,7 @@ pub fn main() {
 
     let (message_tx, message_rx) = crossbeam_channel::unbounded::<emu::Message>();
 
-    let fb_texture_id = {
-        let texture = window_builder.window.gfx.imgui.create_texture(
-            &window_builder.window.gfx.device_state.device,
-            &wgpu::SamplerDescriptor {
-                label: Some("framebuffer sampler"),
-                min_filter: wgpu::FilterMode::Linear,
-                ..Default::default()
-            },
-            imgui_wgpu::TextureDescriptor {
-                label: Some("framebuffer texture".to_string()),
-                size: wgpu::Extent3d {
-                    width: FB_WIDTH as u32,
-                    height: FB_HEIGHT as u32,
-                    depth_or_array_layers: 1,
-                },
-                format: Some(
-                    if window_builder
-                        .window
-                        .gfx
-                        .device_state
-                        .surf_config
-                        .format
-                        .describe()
-                        .srgb
-                    {
-                        wgpu::TextureFormat::Rgba8UnormSrgb
-                    } else {
-                        wgpu::TextureFormat::Rgba8Unorm
-                    },
-                ),
-                ..Default::default()
-            },
-        );
-        window_builder.window.gfx.imgui.add_texture(texture)
-    };
-    clear_fb_texture(fb_texture_id, &mut window_builder.window);
+    let fb_texture_id = create_fb_texture(&mut window_builder.window, 1);
 
     let mut state = UiState {
         game_title: None,
@@ -496,6 +528,7 @@ pub fn main() {
         screen_focused: true,
         input: input::State::new(keymap),
         input_editor: None,
+        cheats_editor: None,
 
         audio_channel,
         audio_volume: global_config.contents.audio_volume,
@@ -503,6 +536,16 @@ pub fn main() {
         audio_interp_method: global_config.contents.audio_interp_method,
         sync_to_audio: config::RuntimeModifiable::global(global_config.contents.sync_to_audio),
 
+        dot_accurate_ppu: config::RuntimeModifiable::global(
+            global_config.contents.dot_accurate_ppu,
+        ),
+        unlimited_sprites: config::RuntimeModifiable::global(
+            global_config.contents.unlimited_sprites,
+        ),
+        widescreen_columns: config::RuntimeModifiable::global(
+            global_config.contents.widescreen_columns,
+        ),
+
         show_menu_bar: true,
 
         #[cfg(feature = "log")]
@@ -514,12 +557,19 @@ pub fn main() {
         frame_rx,
         fps_fixed: None,
         fb_texture_id,
+        fb_view_width: VIEW_WIDTH,
         fb_view_height: VIEW_HEIGHT_NTSC,
         fb_width: FB_WIDTH,
         fb_height: FB_HEIGHT,
+        fb_scale: 1,
+        screenshot_request: None,
 
         #[cfg(feature = "debug-views")]
         debug_views: debug_views::UiState::new(),
+        #[cfg(feature = "debug-views")]
+        trace_logger: None,
+        #[cfg(feature = "debug-views")]
+        cdl_logger: None,
 
         message_tx,
         message_rx,
@@ -574,6 +624,11 @@ pub fn main() {
 
             if state.emu_thread.is_some() {
                 if let Ok(frame) = state.frame_rx.get() {
+                    // The emulation thread can pause itself (e.g. after hitting a breakpoint)
+                    if let Some(shared_state) = &state.emu_shared_state {
+                        state.playing = shared_state.playing.load(Ordering::Relaxed);
+                    }
+
                     #[cfg(feature = "debug-views")]
                     state
                         .debug_views
@@ -589,16 +644,30 @@ pub fn main() {
                         ));
                     }
 
+                    state.fb_view_width = frame.view_width;
                     state.fb_view_height = frame.view_height;
-                    state.fb_width = frame.fb_width;
-                    state.fb_height = frame.fb_height;
+                    state.fb_width = frame.output_width;
+                    state.fb_height = frame.output_height;
+
+                    if frame.fb_scale != state.fb_scale {
+                        window.gfx.imgui.remove_texture(state.fb_texture_id);
+                        state.fb_texture_id = create_fb_texture(window, frame.fb_scale);
+                        state.fb_scale = frame.fb_scale;
+                    }
 
                     let fb_texture = window.gfx.imgui.texture_mut(state.fb_texture_id);
                     let data = unsafe {
-                        core::slice::from_raw_parts(
-                            frame.fb.0.as_ptr() as *const u8,
-                            FB_WIDTH * FB_HEIGHT * 4,
-                        )
+                        if frame.output_fb.is_empty() {
+                            core::slice::from_raw_parts(
+                                frame.fb.0.as_ptr() as *const u8,
+                                FB_WIDTH * FB_HEIGHT * 4,
+                            )
+                        } else {
+                            core::slice::from_raw_parts(
+                                frame.output_fb.as_ptr() as *const u8,
+                                frame.output_fb.len() * 4,
+                            )
+                        }
                     };
                     fb_texture.set_data(
                         &window.gfx.device_state.queue,
@@ -606,11 +675,22 @@ pub fn main() {
                         imgui_wgpu::TextureRange {
                             x: 0,
                             y: 0,
-                            width: NonZeroU32::new(frame.fb_width as u32),
-                            height: NonZeroU32::new(frame.fb_height as u32),
+                            width: NonZeroU32::new(frame.output_width as u32),
+                            height: NonZeroU32::new(frame.output_height as u32),
                             ..imgui_wgpu::TextureRange::default()
                         },
                     );
+
+                    if let Some(kind) = state.screenshot_request.take() {
+                        screenshot::save(
+                            frame,
+                            kind,
+                            &state.global_config.contents.screenshot_dir_path,
+                            state.game_title.as_ref().unwrap(),
+                            #[cfg(feature = "log")]
+                            state.logger.clone(),
+                        );
+                    }
                 }
             } else {
                 window.window.set_title("Ness - No game loaded");
@@ -629,6 +709,17 @@ pub fn main() {
                 state.show_menu_bar = !state.show_menu_bar;
             }
 
+            if state.emu_thread.is_some()
+                && ui.is_key_index_pressed(VirtualKeyCode::F12 as i32)
+                && !ui.is_any_item_focused()
+            {
+                state.screenshot_request = Some(if ui.io().key_shift {
+                    screenshot::Kind::Native
+                } else {
+                    screenshot::Kind::Output
+                });
+            }
+
             if state.show_menu_bar {
                 ui.main_menu_bar(|| {
                     ui.menu("Emulation", || {
@@ -666,7 +757,45 @@ pub fn main() {
                             .build(ui)
                         {
                             state.stop();
-                            clear_fb_texture(state.fb_texture_id, window);
+                            clear_fb_texture(state.fb_texture_id, window, state.fb_scale);
+                        }
+
+                        ui.separator();
+
+                        if imgui::MenuItem::new("Take screenshot")
+                            .shortcut("F12")
+                            .enabled(state.emu_thread.is_some())
+                            .build(ui)
+                        {
+                            state.screenshot_request = Some(screenshot::Kind::Output);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Saves the frame as displayed, with filters applied");
+                        }
+
+                        if imgui::MenuItem::new("Take native screenshot")
+                            .shortcut("Shift+F12")
+                            .enabled(state.emu_thread.is_some())
+                            .build(ui)
+                        {
+                            state.screenshot_request = Some(screenshot::Kind::Native);
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text("Saves the frame at the PPU's internal resolution");
+                        }
+
+                        ui.separator();
+
+                        let mut show_cheats = state.cheats_editor.is_some();
+                        if imgui::MenuItem::new("Cheats")
+                            .enabled(state.game_config.is_some())
+                            .build_with_ref(ui, &mut show_cheats)
+                        {
+                            state.cheats_editor = if show_cheats {
+                                Some(cheats::Editor::new())
+                            } else {
+                                None
+                            };
                         }
 
                         if imgui::MenuItem::new("Load game...").build(ui) {
@@ -779,6 +908,221 @@ pub fn main() {
                                 .expect("Couldn't send UI message");
                         }
 
+                        if imgui::MenuItem::new("Dot-accurate PPU")
+                            .build_with_ref(ui, &mut state.dot_accurate_ppu.value)
+                        {
+                            if state.dot_accurate_ppu.origin == config::SettingOrigin::Game {
+                                let game_config = state.game_config.as_mut().unwrap();
+                                game_config.contents.dot_accurate_ppu =
+                                    Some(state.dot_accurate_ppu.value);
+                                game_config.dirty = true;
+                            }
+                            state.global_config.contents.dot_accurate_ppu =
+                                state.dot_accurate_ppu.value;
+                            state.global_config.dirty = true;
+                            state
+                                .message_tx
+                                .send(emu::Message::UpdateDotAccuratePpu(
+                                    state.dot_accurate_ppu.value,
+                                ))
+                                .expect("Couldn't send UI message");
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(
+                                "Renders mid-scanline register changes, at a performance cost",
+                            );
+                        }
+
+                        if imgui::MenuItem::new("Remove sprite limits")
+                            .build_with_ref(ui, &mut state.unlimited_sprites.value)
+                        {
+                            if state.unlimited_sprites.origin == config::SettingOrigin::Game {
+                                let game_config = state.game_config.as_mut().unwrap();
+                                game_config.contents.unlimited_sprites =
+                                    Some(state.unlimited_sprites.value);
+                                game_config.dirty = true;
+                            }
+                            state.global_config.contents.unlimited_sprites =
+                                state.unlimited_sprites.value;
+                            state.global_config.dirty = true;
+                            state
+                                .message_tx
+                                .send(emu::Message::UpdateUnlimitedSprites(
+                                    state.unlimited_sprites.value,
+                                ))
+                                .expect("Couldn't send UI message");
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(
+                                "Draws every sprite on each line to reduce flicker; may reveal \
+                                 sprites that games hide on purpose",
+                            );
+                        }
+
+                        ui.menu("Widescreen", || {
+                            for columns in (0..=MAX_WIDESCREEN_COLUMNS as u8).step_by(16) {
+                                let label = if columns == 0 {
+                                    "Off".to_string()
+                                } else {
+                                    format!("{} columns per side", columns)
+                                };
+                                if !imgui::MenuItem::new(&label)
+                                    .selected(state.widescreen_columns.value == (columns, columns))
+                                    .build(ui)
+                                {
+                                    continue;
+                                }
+                                state.widescreen_columns.value = (columns, columns);
+                                if state.widescreen_columns.origin == config::SettingOrigin::Game {
+                                    let game_config = state.game_config.as_mut().unwrap();
+                                    game_config.contents.widescreen_columns =
+                                        Some(state.widescreen_columns.value);
+                                    game_config.dirty = true;
+                                }
+                                state.global_config.contents.widescreen_columns =
+                                    state.widescreen_columns.value;
+                                state.global_config.dirty = true;
+                                state
+                                    .message_tx
+                                    .send(emu::Message::UpdateWidescreenColumns(columns, columns))
+                                    .expect("Couldn't send UI message");
+                            }
+                        });
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(
+                                "Shows extra columns past the screen edges; may show glitches",
+                            );
+                        }
+
+                        ui.menu("HD Mode 7", || {
+                            let config = &mut state.global_config.contents;
+                            let mut updated = false;
+                            for scale in 1..=MAX_HD_MODE7_SCALE {
+                                let label = if scale == 1 {
+                                    "Off".to_string()
+                                } else {
+                                    format!("{}x", scale)
+                                };
+                                if imgui::MenuItem::new(&label)
+                                    .selected(config.hd_mode7_scale == scale)
+                                    .build(ui)
+                                {
+                                    config.hd_mode7_scale = scale;
+                                    updated = true;
+                                }
+                            }
+                            ui.separator();
+                            updated |= imgui::MenuItem::new("Perspective correction")
+                                .build_with_ref(ui, &mut config.hd_mode7_perspective_correction);
+                            if ui.is_item_hovered() {
+                                ui.tooltip_text("Interpolates the Mode 7 matrix between scanlines");
+                            }
+                            if updated {
+                                let message = emu::Message::UpdateHdMode7 {
+                                    scale: config.hd_mode7_scale.clamp(1, MAX_HD_MODE7_SCALE),
+                                    perspective_correction: config.hd_mode7_perspective_correction,
+                                };
+                                state.global_config.dirty = true;
+                                state
+                                    .message_tx
+                                    .send(message)
+                                    .expect("Couldn't send UI message");
+                            }
+                        });
+
+                        ui.menu("Video filters", || {
+                            let settings = &mut state.global_config.contents.video_filters;
+                            let prev_settings = *settings;
+                            ui.text_disabled("Interlacing");
+                            for (label, interlace) in [
+                                ("Weave", InterlaceMode::Weave),
+                                ("Bob", InterlaceMode::Bob),
+                                ("Blend fields", InterlaceMode::Blend),
+                            ] {
+                                if imgui::MenuItem::new(label)
+                                    .selected(settings.interlace == interlace)
+                                    .build(ui)
+                                {
+                                    settings.interlace = interlace;
+                                }
+                            }
+                            ui.separator();
+                            ui.text_disabled("Hi-res");
+                            for (label, hi_res) in [
+                                ("Native (512 pixels)", HiResMode::Native),
+                                ("Blend to 256 pixels", HiResMode::Blend),
+                            ] {
+                                if imgui::MenuItem::new(label)
+                                    .selected(settings.hi_res == hi_res)
+                                    .build(ui)
+                                {
+                                    settings.hi_res = hi_res;
+                                }
+                            }
+                            if ui.is_item_hovered() {
+                                ui.tooltip_text(
+                                    "Needed for pseudo-hires transparency effects, as on a TV",
+                                );
+                            }
+                            ui.separator();
+                            imgui::MenuItem::new("SNES color correction")
+                                .build_with_ref(ui, &mut settings.color_correction);
+                            if ui.is_item_hovered() {
+                                ui.tooltip_text("Applies the gamma curve of a TV to the output");
+                            }
+                            ui.separator();
+                            for (label, ntsc) in [
+                                ("No NTSC filter", None),
+                                ("Composite", Some(NtscMode::Composite)),
+                                ("S-Video", Some(NtscMode::SVideo)),
+                                ("RGB", Some(NtscMode::Rgb)),
+                            ] {
+                                if imgui::MenuItem::new(label)
+                                    .selected(settings.ntsc == ntsc)
+                                    .build(ui)
+                                {
+                                    settings.ntsc = ntsc;
+                                }
+                            }
+                            ui.separator();
+                            for (label, scaler) in [
+                                ("No scaler", Scaler::None),
+                                ("Scale2x", Scaler::Scale2x),
+                                ("Scale3x", Scaler::Scale3x),
+                                ("2xBR", Scaler::Xbr2x),
+                            ] {
+                                if imgui::MenuItem::new(label)
+                                    .selected(settings.scaler == scaler)
+                                    .build(ui)
+                                {
+                                    settings.scaler = scaler;
+                                }
+                            }
+                            if *settings != prev_settings {
+                                let message = emu::Message::UpdateVideoFilters(*settings);
+                                state.global_config.dirty = true;
+                                state
+                                    .message_tx
+                                    .send(message)
+                                    .expect("Couldn't send UI message");
+                            }
+                        });
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(
+                                "The NTSC filter and scalers don't apply while HD Mode 7 is enabled",
+                            );
+                        }
+
+                        if imgui::MenuItem::new("Screenshot directory...").build(ui) {
+                            if let Some(path) = FileDialog::new()
+                                .set_directory(&state.global_config.contents.screenshot_dir_path)
+                                .pick_folder()
+                            {
+                                state.global_config.contents.screenshot_dir_path = path;
+                                state.global_config.dirty = true;
+                            }
+                        }
+
                         if imgui::MenuItem::new("Fullscreen render")
                             .build_with_ref(ui, &mut state.global_config.contents.fullscreen_render)
                         {
@@ -812,6 +1156,43 @@ pub fn main() {
                                     ui.separator();
                                 }
                                 state.debug_views.render_menu(ui, window);
+
+                                ui.separator();
+                                let mut show_trace_logger = state.trace_logger.is_some();
+                                if imgui::MenuItem::new("Trace logger")
+                                    .build_with_ref(ui, &mut show_trace_logger)
+                                {
+                                    state.trace_logger = if show_trace_logger {
+                                        Some(trace_logger::TraceLogger::new())
+                                    } else {
+                                        None
+                                    };
+                                    if !show_trace_logger && state.emu_thread.is_some() {
+                                        for processor in [
+                                            trace_logger::Processor::Cpu,
+                                            trace_logger::Processor::Spc,
+                                        ] {
+                                            state.send_message(emu::Message::UpdateTraceLog(
+                                                processor, None,
+                                            ));
+                                        }
+                                    }
+                                }
+                                let mut show_cdl_logger = state.cdl_logger.is_some();
+                                if imgui::MenuItem::new("Code/data logger")
+                                    .build_with_ref(ui, &mut show_cdl_logger)
+                                {
+                                    state.cdl_logger = if show_cdl_logger {
+                                        Some(cdl_logger::CdlLogger::new())
+                                    } else {
+                                        None
+                                    };
+                                    if !show_cdl_logger && state.emu_thread.is_some() {
+                                        state.send_message(emu::Message::UpdateCdl(
+                                            cdl_logger::Request::SetEnabled(false),
+                                        ));
+                                    }
+                                }
                             }
                         });
                     }
@@ -836,6 +1217,40 @@ pub fn main() {
                     .expect("Couldn't send UI message");
             }
 
+            #[cfg(feature = "debug-views")]
+            if let Some(trace_logger) = &mut state.trace_logger {
+                let mut opened = true;
+                for (processor, log) in
+                    trace_logger.draw(ui, state.emu_thread.is_some(), &mut opened)
+                {
+                    if state.emu_thread.is_some() {
+                        state
+                            .message_tx
+                            .send(emu::Message::UpdateTraceLog(processor, log))
+                            .expect("Couldn't send UI message");
+                    }
+                }
+                if !opened {
+                    state.trace_logger = None;
+                }
+            }
+
+            #[cfg(feature = "debug-views")]
+            if let Some(cdl_logger) = &mut state.cdl_logger {
+                let mut opened = true;
+                for request in cdl_logger.draw(ui, state.emu_thread.is_some(), &mut opened) {
+                    if state.emu_thread.is_some() {
+                        state
+                            .message_tx
+                            .send(emu::Message::UpdateCdl(request))
+                            .expect("Couldn't send UI message");
+                    }
+                }
+                if !opened {
+                    state.cdl_logger = None;
+                }
+            }
+
             if let Some(input_editor) = &mut state.input_editor {
                 let mut opened = true;
                 input_editor.draw(ui, &mut state.input, &mut opened);
@@ -844,11 +1259,31 @@ pub fn main() {
                 }
             }
 
+            if let Some(cheats_editor) = &mut state.cheats_editor {
+                let mut opened = state.game_config.is_some();
+                if let Some(game_config) = &mut state.game_config {
+                    if cheats_editor.draw(ui, &mut game_config.contents.cheats, &mut opened) {
+                        game_config.dirty = true;
+                        state
+                            .message_tx
+                            .send(emu::Message::UpdateCheats(cheats::enabled_codes(
+                                &game_config.contents.cheats,
+                            )))
+                            .expect("Couldn't send UI message");
+                    }
+                }
+                if !opened {
+                    state.cheats_editor = None;
+                }
+            }
+
             let window_size = window.window.inner_size();
-            let aspect_ratio = VIEW_WIDTH as f32 / state.fb_view_height as f32;
+            // Hi-res, interlacing and filters change the output's size, but it always covers the
+            // same view
+            let aspect_ratio = state.fb_view_width as f32 / state.fb_view_height as f32;
             let uv1 = [
-                state.fb_width as f32 / FB_WIDTH as f32,
-                state.fb_height as f32 / FB_HEIGHT as f32,
+                state.fb_width as f32 / (FB_WIDTH * state.fb_scale) as f32,
+                state.fb_height as f32 / (FB_HEIGHT * state.fb_scale) as f32,
             ];
             if state.global_config.contents.fullscreen_render {
                 let ([x_base, y_base], [width, height]) = scale_to_fit(
@@ -2,6 +2,7 @@ use std::{
     env,
     lazy::SyncLazy,
     path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 macro_rules! warning {
@@ -63,6 +64,49 @@ pub fn scale_to_fit(aspect_ratio: f32, frame_size: [f32; 2]) -> ([f32; 2], [f32;
     )
 }
 
+/// Formats the current UTC time as `YYYY-MM-DD hh-mm-ss.mmm`, for use in file names.
+pub fn timestamp() -> String {
+    format_timestamp(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default(),
+    )
+}
+
+/// Formats a time since the Unix epoch like `timestamp`.
+fn format_timestamp(time: Duration) -> String {
+    let secs = time.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+
+    // Convert days since the epoch to a civil date (see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}-{:02}-{:02}.{:03}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        time.subsec_millis(),
+    )
+}
+
 static CONFIG_BASE: SyncLazy<PathBuf> = SyncLazy::new(|| match env::var_os("XDG_CONFIG_HOME") {
     Some(config_dir) => Path::new(&config_dir).join("ness"),
     None => home::home_dir()
@@ -84,3 +128,18 @@ pub fn config_base<'a>() -> &'a Path {
 pub fn data_base<'a>() -> &'a Path {
     &*DATA_BASE
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        let format = |secs, millis: u32| format_timestamp(Duration::new(secs, millis * 1_000_000));
+        assert_eq!(format(0, 0), "1970-01-01 00-00-00.000");
+        assert_eq!(format(951_782_400, 0), "2000-02-29 00-00-00.000");
+        assert_eq!(format(1_700_000_000, 123), "2023-11-14 22-13-20.123");
+        assert_eq!(format(4_107_542_399, 999), "2100-02-28 23-59-59.999");
+        assert_eq!(format(4_107_542_400, 0), "2100-03-01 00-00-00.000");
+    }
+}
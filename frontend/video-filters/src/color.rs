@@ -0,0 +1,77 @@
+use super::{pixel_from_rgb, rgb, write_frame, Frame};
+
+/// The brightness a CRT TV displays for each 5-bit SNES color channel value, which is noticeably
+/// darker for low values than the linear expansion the PPU framebuffer uses.
+const SNES_GAMMA_RAMP: [u8; 32] = [
+    0x00, 0x01, 0x03, 0x06, 0x0A, 0x0F, 0x15, 0x1C, 0x24, 0x2D, 0x37, 0x42, 0x4E, 0x5B, 0x69, 0x78,
+    0x88, 0x90, 0x98, 0xA0, 0xA8, 0xB0, 0xB8, 0xC0, 0xC8, 0xD0, 0xD8, 0xE0, 0xE8, 0xF0, 0xF8, 0xFF,
+];
+
+/// A per-channel lookup table from framebuffer color values to corrected ones.
+#[derive(Clone)]
+pub struct ColorLut(Box<[u8; 0x100]>);
+
+impl ColorLut {
+    /// Builds a table mapping the framebuffer's 8-bit channels to the SNES gamma curve. Values
+    /// that don't come straight from a 5-bit color (i.e. after blending or scaling) are linearly
+    /// interpolated between the two closest ramp entries.
+    pub fn snes() -> Self {
+        // The 8-bit value the PPU expands each 5-bit one to
+        let expand = |value: usize| value << 3 | value >> 2;
+        let mut table = Box::new([0; 0x100]);
+        for (i, pair) in SNES_GAMMA_RAMP.windows(2).enumerate() {
+            let (start, end) = (expand(i), expand(i + 1));
+            let (low, high) = (pair[0] as usize, pair[1] as usize);
+            for (offset, dst) in table[start..end].iter_mut().enumerate() {
+                *dst = (low + (high - low) * offset / (end - start)) as u8;
+            }
+        }
+        table[0xFF] = SNES_GAMMA_RAMP[31];
+        ColorLut(table)
+    }
+
+    #[inline]
+    pub fn map_pixel(&self, pixel: u32) -> u32 {
+        pixel_from_rgb(rgb(pixel).map(|value| self.0[value as usize]))
+    }
+
+    pub(super) fn apply<'a>(&self, frame: Frame, output: &'a mut Vec<u32>) -> Frame<'a> {
+        write_frame(output, &frame, frame.width, frame.height, |output| {
+            for (y, dst) in output.chunks_exact_mut(frame.width).enumerate() {
+                for (dst, &src) in dst.iter_mut().zip(frame.line(y)) {
+                    *dst = self.map_pixel(src);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snes_lut() {
+        let lut = ColorLut::snes();
+        // 5-bit values expanded by the PPU map straight to their ramp entries
+        for (value, &expected) in SNES_GAMMA_RAMP.iter().enumerate() {
+            let expanded = (value << 3 | value >> 2) as u8;
+            assert_eq!(lut.0[expanded as usize], expected, "{:#04X}", expanded);
+        }
+        assert!(lut.0.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(lut.map_pixel(0xFF84_0800), 0xFF88_0100);
+        assert_eq!(lut.map_pixel(0x00FF_FFFF), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn apply() {
+        let pixels = [0xFF00_0000, 0xFFFF_FFFF, 0xFF84_0800, 0];
+        let mut output = Vec::new();
+        let frame = ColorLut::snes().apply(Frame::native(&pixels, 2, 2), &mut output);
+        assert_eq!((frame.width, frame.height, frame.stride), (2, 2, 2));
+        assert_eq!(
+            frame.pixels,
+            [0xFF00_0000, 0xFFFF_FFFF, 0xFF88_0100, 0xFF00_0000]
+        );
+    }
+}
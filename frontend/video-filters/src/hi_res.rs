@@ -0,0 +1,72 @@
+use super::{average, write_frame, Frame};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HiResMode {
+    /// Keeps hi-res frames at 512 pixels per line.
+    #[default]
+    Native,
+    /// Averages each pair of hi-res pixels into one, like the limited horizontal resolution of a
+    /// TV does; games using pseudo-hires for transparency effects rely on this.
+    Blend,
+}
+
+/// Halves the width of a hi-res frame for `HiResMode::Blend`.
+pub(super) fn blend<'a>(frame: Frame, output: &'a mut Vec<u32>) -> Frame<'a> {
+    let width = frame.width / 2;
+    let output = write_frame(output, &frame, width, frame.height, |output| {
+        for (y, dst) in output.chunks_exact_mut(width).enumerate() {
+            let src = frame.line(y);
+            for (x, dst) in dst.iter_mut().enumerate() {
+                // Pixels are `scale` pixels wide, and each pair of them makes up a dot
+                let (dot, sub_x) = (x / frame.scale, x % frame.scale);
+                let first_x = dot * 2 * frame.scale + sub_x;
+                *dst = average(src[first_x], src[first_x + frame.scale]);
+            }
+        }
+    });
+    Frame {
+        hi_res: false,
+        ..output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_pairs() {
+        let pixels = [0xFF00_0000, 0xFF20_4060, 0xFF80_8080, 0xFFFF_FFFF];
+        let mut output = Vec::new();
+        let frame = blend(
+            Frame {
+                hi_res: true,
+                ..Frame::native(&pixels, 4, 1)
+            },
+            &mut output,
+        );
+        assert_eq!((frame.width, frame.height), (2, 1));
+        assert!(!frame.hi_res);
+        assert_eq!(frame.pixels, [0xFF10_2030, 0xFFBF_BFBF]);
+    }
+
+    #[test]
+    fn blend_scaled() {
+        // At 2x, each dot is a 2x2 block, and the blocks of each pair get averaged
+        let (a, b) = (0xFF00_0000, 0xFF20_4060);
+        let pixels = [a, a, b, b, a, a, b, b];
+        let mut output = Vec::new();
+        let frame = blend(
+            Frame {
+                hi_res: true,
+                scale: 2,
+                ..Frame::native(&pixels, 4, 2)
+            },
+            &mut output,
+        );
+        assert_eq!((frame.width, frame.height), (2, 2));
+        assert_eq!(frame.pixels, [0xFF10_2030; 4]);
+    }
+}
@@ -0,0 +1,93 @@
+use super::{average, write_frame, Frame};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InterlaceMode {
+    /// Shows the lines of both fields, as they're stored in the framebuffer: still images get the
+    /// full vertical resolution, but moving ones show combing.
+    #[default]
+    Weave,
+    /// Shows only the last field drawn, repeating each of its lines.
+    Bob,
+    /// Averages the lines of both fields, trading combing for blur.
+    Blend,
+}
+
+impl InterlaceMode {
+    pub(super) fn apply<'a>(
+        self,
+        frame: Frame,
+        field: bool,
+        output: &'a mut Vec<u32>,
+    ) -> Frame<'a> {
+        let output = write_frame(output, &frame, frame.width, frame.height, |output| {
+            for (y, dst) in output.chunks_exact_mut(frame.width).enumerate() {
+                // Lines are `scale` pixels tall, with even ones belonging to the first field
+                let (line, sub_y) = (y / frame.scale, y % frame.scale);
+                let even_y = ((line & !1) * frame.scale + sub_y).min(frame.height - 1);
+                let odd_y = ((line | 1) * frame.scale + sub_y).min(frame.height - 1);
+                match self {
+                    InterlaceMode::Weave => dst.copy_from_slice(frame.line(y)),
+                    InterlaceMode::Bob => {
+                        dst.copy_from_slice(frame.line(if field { odd_y } else { even_y }));
+                    }
+                    InterlaceMode::Blend => {
+                        for ((dst, &even), &odd) in dst
+                            .iter_mut()
+                            .zip(frame.line(even_y))
+                            .zip(frame.line(odd_y))
+                        {
+                            *dst = average(even, odd);
+                        }
+                    }
+                }
+            }
+        });
+        Frame {
+            interlace_field: None,
+            ..output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: [u32; 4] = [0xFF00_0000, 0xFF20_4060, 0xFF80_8080, 0xFFFF_FFFF];
+
+    fn apply(mode: InterlaceMode, field: bool) -> Vec<u32> {
+        let mut output = Vec::new();
+        let frame = mode.apply(
+            Frame {
+                interlace_field: Some(field),
+                ..Frame::native(&LINES, 1, 4)
+            },
+            field,
+            &mut output,
+        );
+        assert_eq!((frame.width, frame.height), (1, 4));
+        assert_eq!(frame.interlace_field, None);
+        frame.pixels.to_vec()
+    }
+
+    #[test]
+    fn bob() {
+        let [a, b, c, d] = LINES;
+        assert_eq!(apply(InterlaceMode::Bob, false), [a, a, c, c]);
+        assert_eq!(apply(InterlaceMode::Bob, true), [b, b, d, d]);
+    }
+
+    #[test]
+    fn blend() {
+        let (ab, cd) = (0xFF10_2030, 0xFFBF_BFBF);
+        assert_eq!(apply(InterlaceMode::Blend, false), [ab, ab, cd, cd]);
+        assert_eq!(apply(InterlaceMode::Blend, true), [ab, ab, cd, cd]);
+    }
+
+    #[test]
+    fn weave() {
+        assert_eq!(apply(InterlaceMode::Weave, true), LINES);
+    }
+}
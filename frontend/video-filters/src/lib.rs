@@ -0,0 +1,187 @@
+//! Filters applied to the emulator's output before it's displayed, and encoding it to images,
+//! independent of any specific frontend. Pixels use the PPU framebuffer's format: RGBA8 packed
+//! into a `u32` in little-endian order.
+
+mod color;
+mod hi_res;
+mod interlace;
+mod ntsc;
+mod scale;
+mod screenshot;
+
+pub use color::ColorLut;
+pub use hi_res::HiResMode;
+pub use interlace::InterlaceMode;
+pub use ntsc::NtscMode;
+pub use scale::Scaler;
+pub use screenshot::encode_png;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Settings {
+    pub interlace: InterlaceMode,
+    pub hi_res: HiResMode,
+    pub color_correction: bool,
+    pub ntsc: Option<NtscMode>,
+    pub scaler: Scaler,
+}
+
+impl Settings {
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.interlace != InterlaceMode::Weave
+            || self.hi_res != HiResMode::Native
+            || self.color_correction
+            || self.ntsc.is_some()
+            || self.scaler != Scaler::None
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Frame<'a> {
+    pub pixels: &'a [u32],
+    pub stride: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Whether the frame has two pixels per normal dot, like the PPU framebuffer when hi-res
+    /// lines were drawn.
+    pub hi_res: bool,
+    /// For interlaced frames, which contain the lines of both fields, whether the last field drawn
+    /// was the odd one.
+    pub interlace_field: Option<bool>,
+    /// How many times larger than the PPU framebuffer the frame is allowed to be in each
+    /// dimension. The NTSC filter and scalers only apply to native (`scale == 1`) frames, as
+    /// they rely on the original pixel grid.
+    pub scale: usize,
+}
+
+impl<'a> Frame<'a> {
+    #[inline]
+    fn line(&self, y: usize) -> &'a [u32] {
+        &self.pixels[y * self.stride..y * self.stride + self.width]
+    }
+
+    /// A progressive, non-hi-res frame at the native scale, for tests.
+    #[cfg(test)]
+    fn native(pixels: &'a [u32], width: usize, height: usize) -> Self {
+        Frame {
+            pixels,
+            stride: width,
+            width,
+            height,
+            hi_res: false,
+            interlace_field: None,
+            scale: 1,
+        }
+    }
+}
+
+/// Applies the selected filters to frames, keeping the intermediate buffers around between them.
+pub struct Filters {
+    settings: Settings,
+    color_lut: ColorLut,
+    ntsc: ntsc::NtscFilter,
+    buffers: [Vec<u32>; 5],
+}
+
+impl Filters {
+    pub fn new(settings: Settings) -> Self {
+        Filters {
+            settings,
+            color_lut: ColorLut::snes(),
+            ntsc: ntsc::NtscFilter::new(),
+            buffers: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    #[inline]
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Runs `frame` through deinterlacing, hi-res blending, the NTSC filter, the scaler and color
+    /// correction, in that order, returning the final frame (which is `frame` itself if no filters
+    /// apply to it).
+    pub fn apply<'a>(&'a mut self, frame: Frame<'a>) -> Frame<'a> {
+        let Filters {
+            settings,
+            color_lut,
+            ntsc,
+            buffers,
+        } = self;
+        let mut buffers = buffers.iter_mut();
+        let mut frame = frame;
+
+        if let Some(field) = frame.interlace_field {
+            if settings.interlace != InterlaceMode::Weave {
+                let output = buffers.next().unwrap();
+                frame = settings.interlace.apply(frame, field, output);
+            }
+        }
+        if frame.hi_res && settings.hi_res == HiResMode::Blend {
+            let output = buffers.next().unwrap();
+            frame = hi_res::blend(frame, output);
+        }
+
+        if frame.scale == 1 {
+            if let Some(mode) = settings.ntsc {
+                let output = buffers.next().unwrap();
+                frame = ntsc.apply(mode, frame, output);
+            }
+            if settings.scaler != Scaler::None {
+                let output = buffers.next().unwrap();
+                frame = settings.scaler.apply(frame, output);
+            }
+        }
+
+        if settings.color_correction {
+            let output = buffers.next().unwrap();
+            frame = color_lut.apply(frame, output);
+        }
+
+        frame
+    }
+}
+
+/// Averages each channel of two pixels.
+#[inline]
+fn average(a: u32, b: u32) -> u32 {
+    (a & b) + ((a ^ b) >> 1 & 0x7F7F_7F7F)
+}
+
+#[inline]
+fn rgb(pixel: u32) -> [u8; 3] {
+    [pixel as u8, (pixel >> 8) as u8, (pixel >> 16) as u8]
+}
+
+#[inline]
+fn pixel_from_rgb([r, g, b]: [u8; 3]) -> u32 {
+    r as u32 | (g as u32) << 8 | (b as u32) << 16 | 0xFF00_0000
+}
+
+/// Resizes `output` to fit a `width` by `height` frame, runs `f` to fill it, and returns a frame
+/// referencing it, with the rest of its properties copied from `source`.
+fn write_frame<'a>(
+    output: &'a mut Vec<u32>,
+    source: &Frame,
+    width: usize,
+    height: usize,
+    f: impl FnOnce(&mut [u32]),
+) -> Frame<'a> {
+    output.resize(width * height, 0);
+    f(output);
+    Frame {
+        pixels: output,
+        stride: width,
+        width,
+        height,
+        ..*source
+    }
+}
@@ -0,0 +1,250 @@
+use super::{pixel_from_rgb, rgb, write_frame, Frame};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NtscMode {
+    /// Luma and chroma share a single signal, so they bleed into each other: colors fringe at
+    /// sharp edges, and dithering patterns blend into solid colors.
+    Composite,
+    /// Luma and chroma are carried separately; only colors are blurred.
+    SVideo,
+    /// No encoding artifacts, just the slight blur of the analog output.
+    Rgb,
+}
+
+/// The PPU outputs a normal dot every 4 master clock cycles, and a hi-res one every 2; the signal
+/// is sampled once per master clock cycle.
+const SAMPLES_PER_DOT: usize = 4;
+/// The color subcarrier's period, in master clock cycles.
+const SUBCARRIER_PERIOD: usize = 6;
+/// Lines are 1364 master clock cycles long, which isn't a multiple of the subcarrier's period, so
+/// its phase shifts between lines.
+const LINE_PHASE_STEP: usize = 1364 % SUBCARRIER_PERIOD;
+
+/// Window lengths for the decoder's low-pass filters, in samples. Lengths that are a multiple of
+/// the subcarrier's period cancel it out completely; the chroma one also limits its bandwidth.
+const COMPOSITE_LUMA_WINDOW: usize = SUBCARRIER_PERIOD;
+const SVIDEO_LUMA_WINDOW: usize = 2;
+const CHROMA_WINDOW: usize = SUBCARRIER_PERIOD * 2;
+const RGB_WINDOW: usize = 3;
+
+#[inline]
+fn yiq_from_rgb([r, g, b]: [f32; 3]) -> [f32; 3] {
+    [
+        0.299 * r + 0.587 * g + 0.114 * b,
+        0.596 * r - 0.274 * g - 0.322 * b,
+        0.211 * r - 0.523 * g + 0.312 * b,
+    ]
+}
+
+#[inline]
+fn rgb_from_yiq([y, i, q]: [f32; 3]) -> [f32; 3] {
+    [
+        y + 0.956 * i + 0.621 * q,
+        y - 0.272 * i - 0.647 * q,
+        y - 1.106 * i + 1.703 * q,
+    ]
+}
+
+/// Averages `len` samples of `src` around each position, repeating the samples at the edges.
+fn box_filter<const N: usize>(src: &[[f32; N]], dst: &mut [[f32; N]], len: usize) {
+    let last = src.len() as isize - 1;
+    let sample = |i: isize| src[i.clamp(0, last) as usize];
+    let start = -(len as isize / 2);
+    let mut sum = [0.0; N];
+    for i in start..start + len as isize {
+        for (sum, value) in sum.iter_mut().zip(sample(i)) {
+            *sum += value;
+        }
+    }
+    for (i, dst) in dst.iter_mut().enumerate() {
+        *dst = sum.map(|value| value / len as f32);
+        let (removed, added) = (
+            sample(i as isize + start),
+            sample(i as isize + start + len as isize),
+        );
+        for ((sum, added), removed) in sum.iter_mut().zip(added).zip(removed) {
+            *sum += added - removed;
+        }
+    }
+}
+
+/// Simulates the SNES's analog video output by encoding each line into an NTSC signal and
+/// decoding it back, in the style of blargg's filters. The output always has two pixels per
+/// normal dot, so normal frames get twice as wide and hi-res ones keep their width.
+pub(super) struct NtscFilter {
+    carrier: [(f32, f32); SUBCARRIER_PERIOD],
+    /// The composite signal, or only luma for S-Video.
+    signal: Vec<[f32; 1]>,
+    chroma: Vec<[f32; 2]>,
+    filtered_luma: Vec<[f32; 1]>,
+    filtered_chroma: Vec<[f32; 2]>,
+    rgb: Vec<[f32; 3]>,
+    filtered_rgb: Vec<[f32; 3]>,
+}
+
+impl NtscFilter {
+    pub(super) fn new() -> Self {
+        NtscFilter {
+            carrier: core::array::from_fn(|i| {
+                let phase = i as f32 * core::f32::consts::TAU / SUBCARRIER_PERIOD as f32;
+                (phase.cos(), phase.sin())
+            }),
+            signal: Vec::new(),
+            chroma: Vec::new(),
+            filtered_luma: Vec::new(),
+            filtered_chroma: Vec::new(),
+            rgb: Vec::new(),
+            filtered_rgb: Vec::new(),
+        }
+    }
+
+    /// Encodes and decodes a line of pixels, writing the decoded samples (in RGB) to
+    /// `self.filtered_rgb`.
+    fn filter_line(
+        &mut self,
+        mode: NtscMode,
+        line: &[u32],
+        line_index: usize,
+        samples_per_pixel: usize,
+    ) {
+        let samples = line.len() * samples_per_pixel;
+        let phase = line_index * LINE_PHASE_STEP;
+        let input = |n: usize| rgb(line[n / samples_per_pixel]).map(|value| value as f32 / 255.0);
+        self.filtered_rgb.resize(samples, [0.0; 3]);
+
+        if mode == NtscMode::Rgb {
+            self.rgb.clear();
+            self.rgb.extend((0..samples).map(input));
+            box_filter(&self.rgb, &mut self.filtered_rgb, RGB_WINDOW);
+            return;
+        }
+
+        self.signal.clear();
+        self.chroma.clear();
+        for n in 0..samples {
+            let [y, i, q] = yiq_from_rgb(input(n));
+            let (cos, sin) = self.carrier[(n + phase) % SUBCARRIER_PERIOD];
+            let chroma = i * cos + q * sin;
+            match mode {
+                NtscMode::Composite => self.signal.push([y + chroma]),
+                _ => {
+                    self.signal.push([y]);
+                    self.chroma.push([chroma, 0.0]);
+                }
+            }
+        }
+
+        self.filtered_luma.resize(samples, [0.0]);
+        let luma_window = if mode == NtscMode::Composite {
+            COMPOSITE_LUMA_WINDOW
+        } else {
+            SVIDEO_LUMA_WINDOW
+        };
+        box_filter(&self.signal, &mut self.filtered_luma, luma_window);
+
+        // Separate the chroma from the composite signal, then demodulate it: multiplying by the
+        // carrier leaves half of I and Q plus components at twice its frequency, which the
+        // low-pass filter removes
+        if mode == NtscMode::Composite {
+            self.chroma.extend(
+                self.signal
+                    .iter()
+                    .zip(&self.filtered_luma)
+                    .map(|(signal, luma)| [signal[0] - luma[0], 0.0]),
+            );
+        }
+        for (n, chroma) in self.chroma.iter_mut().enumerate() {
+            let (cos, sin) = self.carrier[(n + phase) % SUBCARRIER_PERIOD];
+            *chroma = [2.0 * chroma[0] * cos, 2.0 * chroma[0] * sin];
+        }
+        self.filtered_chroma.resize(samples, [0.0; 2]);
+        box_filter(&self.chroma, &mut self.filtered_chroma, CHROMA_WINDOW);
+
+        for ((dst, luma), chroma) in self
+            .filtered_rgb
+            .iter_mut()
+            .zip(&self.filtered_luma)
+            .zip(&self.filtered_chroma)
+        {
+            *dst = rgb_from_yiq([luma[0], chroma[0], chroma[1]]);
+        }
+    }
+
+    pub(super) fn apply<'a>(
+        &mut self,
+        mode: NtscMode,
+        frame: Frame,
+        output: &'a mut Vec<u32>,
+    ) -> Frame<'a> {
+        let samples_per_pixel = if frame.hi_res {
+            SAMPLES_PER_DOT / 2
+        } else {
+            SAMPLES_PER_DOT
+        };
+        // Each output pixel covers two samples
+        let width = frame.width * samples_per_pixel / 2;
+        let output = write_frame(output, &frame, width, frame.height, |output| {
+            for (y, dst) in output.chunks_exact_mut(width).enumerate() {
+                self.filter_line(mode, frame.line(y), y, samples_per_pixel);
+                for (dst, samples) in dst.iter_mut().zip(self.filtered_rgb.chunks_exact(2)) {
+                    *dst = pixel_from_rgb(core::array::from_fn(|c| {
+                        ((samples[0][c] + samples[1][c]) * 127.5)
+                            .round()
+                            .clamp(0.0, 255.0) as u8
+                    }));
+                }
+            }
+        });
+        Frame {
+            hi_res: true,
+            ..output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAY: u32 = 0xFF80_8080;
+
+    #[test]
+    fn output_width() {
+        let pixels = [GRAY; 16 * 2];
+        let mut filter = NtscFilter::new();
+        let mut output = Vec::new();
+        for mode in [NtscMode::Composite, NtscMode::SVideo, NtscMode::Rgb] {
+            let frame = filter.apply(mode, Frame::native(&pixels, 16, 2), &mut output);
+            assert_eq!((frame.width, frame.height), (32, 2));
+            assert!(frame.hi_res);
+
+            let hi_res_frame = Frame {
+                hi_res: true,
+                ..Frame::native(&pixels, 16, 2)
+            };
+            let frame = filter.apply(mode, hi_res_frame, &mut output);
+            assert_eq!((frame.width, frame.height), (16, 2));
+            assert!(frame.hi_res);
+        }
+    }
+
+    #[test]
+    fn flat_colors() {
+        let mut filter = NtscFilter::new();
+        let mut output = Vec::new();
+        for color in [GRAY, 0xFF00_0000, 0xFFFF_FFFF] {
+            let pixels = [color; 16 * 2];
+            for mode in [NtscMode::Composite, NtscMode::SVideo, NtscMode::Rgb] {
+                let frame = filter.apply(mode, Frame::native(&pixels, 16, 2), &mut output);
+                assert!(
+                    frame.pixels.iter().all(|&pixel| pixel == color),
+                    "{:?} {:#010X}",
+                    mode,
+                    color
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use super::{pixel_from_rgb, rgb, write_frame, Frame};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scaler {
+    #[default]
+    None,
+    Scale2x,
+    Scale3x,
+    Xbr2x,
+}
+
+impl Scaler {
+    #[inline]
+    pub fn factor(self) -> usize {
+        match self {
+            Scaler::None => 1,
+            Scaler::Scale2x | Scaler::Xbr2x => 2,
+            Scaler::Scale3x => 3,
+        }
+    }
+
+    pub(super) fn apply<'a>(self, frame: Frame, output: &'a mut Vec<u32>) -> Frame<'a> {
+        match self {
+            Scaler::None => {
+                scale_with::<1, 1>(frame, output, |frame, x, y| [neighbor(frame, x, y, 0, 0)])
+            }
+            Scaler::Scale2x => scale_with::<2, 4>(frame, output, scale2x),
+            Scaler::Scale3x => scale_with::<3, 9>(frame, output, scale3x),
+            Scaler::Xbr2x => scale_with::<2, 4>(frame, output, xbr2x),
+        }
+    }
+}
+
+/// Scales `frame` by `FACTOR`, running `scale_pixel` for each source pixel to get its
+/// `FACTOR`x`FACTOR` block of output pixels in row-major order.
+fn scale_with<'a, const FACTOR: usize, const BLOCK_LEN: usize>(
+    frame: Frame,
+    output: &'a mut Vec<u32>,
+    scale_pixel: impl Fn(&Frame, usize, usize) -> [u32; BLOCK_LEN],
+) -> Frame<'a> {
+    let width = frame.width * FACTOR;
+    let output = write_frame(output, &frame, width, frame.height * FACTOR, |output| {
+        for (y, dst_lines) in output.chunks_exact_mut(width * FACTOR).enumerate() {
+            for x in 0..frame.width {
+                let block = scale_pixel(&frame, x, y);
+                for (block_line, dst_line) in block
+                    .chunks_exact(FACTOR)
+                    .zip(dst_lines.chunks_exact_mut(width))
+                {
+                    dst_line[x * FACTOR..(x + 1) * FACTOR].copy_from_slice(block_line);
+                }
+            }
+        }
+    });
+    Frame {
+        scale: frame.scale * FACTOR,
+        ..output
+    }
+}
+
+/// Returns the pixel at the given offset from (`x`, `y`), repeating the pixels at the edges.
+#[inline]
+fn neighbor(frame: &Frame, x: usize, y: usize, x_offset: isize, y_offset: isize) -> u32 {
+    let x = (x as isize + x_offset).clamp(0, frame.width as isize - 1) as usize;
+    let y = (y as isize + y_offset).clamp(0, frame.height as isize - 1) as usize;
+    frame.pixels[y * frame.stride + x]
+}
+
+fn scale2x(frame: &Frame, x: usize, y: usize) -> [u32; 4] {
+    let p = |x_offset, y_offset| neighbor(frame, x, y, x_offset, y_offset);
+    let (b, d, e, f, h) = (p(0, -1), p(-1, 0), p(0, 0), p(1, 0), p(0, 1));
+    if b == h || d == f {
+        return [e; 4];
+    }
+    [
+        if d == b { d } else { e },
+        if b == f { f } else { e },
+        if d == h { d } else { e },
+        if h == f { f } else { e },
+    ]
+}
+
+fn scale3x(frame: &Frame, x: usize, y: usize) -> [u32; 9] {
+    let p = |x_offset, y_offset| neighbor(frame, x, y, x_offset, y_offset);
+    let [a, b, c, d, e, f, g, h, i] = [
+        p(-1, -1),
+        p(0, -1),
+        p(1, -1),
+        p(-1, 0),
+        p(0, 0),
+        p(1, 0),
+        p(-1, 1),
+        p(0, 1),
+        p(1, 1),
+    ];
+    if b == h || d == f {
+        return [e; 9];
+    }
+    [
+        if d == b { d } else { e },
+        if (d == b && e != c) || (b == f && e != a) {
+            b
+        } else {
+            e
+        },
+        if b == f { f } else { e },
+        if (d == b && e != g) || (d == h && e != a) {
+            d
+        } else {
+            e
+        },
+        e,
+        if (b == f && e != i) || (h == f && e != c) {
+            f
+        } else {
+            e
+        },
+        if d == h { d } else { e },
+        if (d == h && e != i) || (h == f && e != g) {
+            h
+        } else {
+            e
+        },
+        if h == f { f } else { e },
+    ]
+}
+
+/// A perceptual distance between two colors, weighting luma differences above chroma ones.
+#[inline]
+fn color_distance(a: u32, b: u32) -> u32 {
+    let yuv = |pixel| {
+        let [r, g, b] = rgb(pixel).map(i32::from);
+        [
+            (299 * r + 587 * g + 114 * b) / 1000,
+            (-169 * r - 331 * g + 500 * b) / 1000,
+            (500 * r - 419 * g - 81 * b) / 1000,
+        ]
+    };
+    let (a, b) = (yuv(a), yuv(b));
+    48 * a[0].abs_diff(b[0]) + 7 * a[1].abs_diff(b[1]) + 6 * a[2].abs_diff(b[2])
+}
+
+#[inline]
+fn similar(a: u32, b: u32) -> bool {
+    color_distance(a, b) < 155
+}
+
+/// Blends `src` into `dst` with an opacity of `alpha / 256`.
+#[inline]
+fn blend(dst: u32, src: u32, alpha: u32) -> u32 {
+    let (dst, src) = (rgb(dst), rgb(src));
+    pixel_from_rgb(core::array::from_fn(|c| {
+        ((dst[c] as u32 * (256 - alpha) + src[c] as u32 * alpha) >> 8) as u8
+    }))
+}
+
+/// The neighborhood of a pixel, `e`, as seen from one of its corners; `i` is the diagonal
+/// neighbor towards that corner, and `f4`, `i4`, `h5`, `i5` lie one step further out:
+///
+/// ```text
+///       b
+///    d  e  f  f4
+///    g  h  i  i4
+///          h5 i5
+/// ```
+///
+/// (`c` is above `f`, and `g` is left of `h`.)
+struct Corner {
+    e: u32,
+    i: u32,
+    h: u32,
+    f: u32,
+    g: u32,
+    c: u32,
+    d: u32,
+    b: u32,
+    f4: u32,
+    i4: u32,
+    h5: u32,
+    i5: u32,
+}
+
+/// Runs xBR's edge detection for one corner of a pixel, blending the output pixels along any edge
+/// found; `[n1, n2, n3]` are the indices of the output pixels vertically next to the corner,
+/// horizontally next to it, and at the corner itself (all within the rotated neighborhood).
+fn xbr_corner(px: Corner, output: &mut [u32; 4], [n1, n2, n3]: [usize; 3]) {
+    let Corner {
+        e,
+        i,
+        h,
+        f,
+        g,
+        c,
+        d,
+        b,
+        f4,
+        i4,
+        h5,
+        i5,
+    } = px;
+    if e == h || e == f {
+        return;
+    }
+    let dist = color_distance;
+    let edge_weight = dist(e, c) + dist(e, g) + dist(i, h5) + dist(i, f4) + 4 * dist(h, f);
+    let cross_weight = dist(h, d) + dist(h, i5) + dist(f, i4) + dist(f, b) + 4 * dist(e, i);
+    let new_color = if dist(e, f) <= dist(e, h) { f } else { h };
+
+    if edge_weight < cross_weight
+        && ((!similar(f, b) && !similar(h, d))
+            || (similar(e, i) && !similar(f, i4) && !similar(h, i5))
+            || similar(e, g)
+            || similar(e, c))
+    {
+        let (ke, ki) = (dist(f, g), dist(h, c));
+        let shallow = ke * 2 <= ki && e != g && d != g;
+        let steep = ke >= ki * 2 && e != c && b != c;
+        if shallow && steep {
+            output[n3] = blend(output[n3], new_color, 224);
+            output[n2] = blend(output[n2], new_color, 64);
+            output[n1] = output[n2];
+        } else if shallow {
+            output[n3] = blend(output[n3], new_color, 192);
+            output[n2] = blend(output[n2], new_color, 64);
+        } else if steep {
+            output[n3] = blend(output[n3], new_color, 192);
+            output[n1] = blend(output[n1], new_color, 64);
+        } else {
+            output[n3] = blend(output[n3], new_color, 128);
+        }
+    } else if edge_weight <= cross_weight {
+        output[n3] = blend(output[n3], new_color, 64);
+    }
+}
+
+/// Hyllian's xBR (level 2) at 2x, which detects edges at several angles and blends along them.
+fn xbr2x(frame: &Frame, x: usize, y: usize) -> [u32; 4] {
+    let p = |x_offset, y_offset| neighbor(frame, x, y, x_offset, y_offset);
+    //       a1 b1 c1
+    //    a0 a  b  c  c4
+    //    d0 d  e  f  f4
+    //    g0 g  h  i  i4
+    //       g5 h5 i5
+    let (a1, b1, c1) = (p(-1, -2), p(0, -2), p(1, -2));
+    let (a0, a, b, c, c4) = (p(-2, -1), p(-1, -1), p(0, -1), p(1, -1), p(2, -1));
+    let (d0, d, e, f, f4) = (p(-2, 0), p(-1, 0), p(0, 0), p(1, 0), p(2, 0));
+    let (g0, g, h, i, i4) = (p(-2, 1), p(-1, 1), p(0, 1), p(1, 1), p(2, 1));
+    let (g5, h5, i5) = (p(-1, 2), p(0, 2), p(1, 2));
+
+    let mut output = [e; 4];
+    #[rustfmt::skip]
+    let corners = [
+        (Corner { e, i, h, f, g, c, d, b, f4, i4, h5, i5 }, [1, 2, 3]),
+        (Corner { e, i: c, h: f, f: b, g: i, c: a, d: h, b: d, f4: b1, i4: c1, h5: f4, i5: c4 }, [0, 3, 1]),
+        (Corner { e, i: a, h: b, f: d, g: c, c: g, d: f, b: h, f4: d0, i4: a0, h5: b1, i5: a1 }, [2, 1, 0]),
+        (Corner { e, i: g, h: d, f: h, g: a, c: i, d: b, b: f, f4: h5, i4: g5, h5: d0, i5: g0 }, [3, 0, 2]),
+    ];
+    for (corner, indices) in corners {
+        xbr_corner(corner, &mut output, indices);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: u32 = 0xFF00_00FF;
+    const B: u32 = 0xFFFF_0000;
+
+    #[test]
+    fn output_sizes() {
+        let pixels = [A; 6 * 4];
+        let mut output = Vec::new();
+        for scaler in [
+            Scaler::None,
+            Scaler::Scale2x,
+            Scaler::Scale3x,
+            Scaler::Xbr2x,
+        ] {
+            let factor = scaler.factor();
+            let frame = scaler.apply(Frame::native(&pixels, 6, 4), &mut output);
+            assert_eq!(
+                (frame.width, frame.height, frame.stride, frame.scale),
+                (6 * factor, 4 * factor, 6 * factor, factor),
+                "{:?}",
+                scaler
+            );
+            // Flat areas stay untouched
+            assert!(frame.pixels.iter().all(|&pixel| pixel == A), "{:?}", scaler);
+        }
+    }
+
+    #[test]
+    fn scale2x_corner() {
+        // A single pixel in the corner of a flat area only keeps its outer corner
+        let pixels = [A, B, B, B];
+        let mut output = Vec::new();
+        let frame = Scaler::Scale2x.apply(Frame::native(&pixels, 2, 2), &mut output);
+        assert_eq!(&frame.pixels[..2], [A, A]);
+        assert_eq!(&frame.pixels[4..6], [A, B]);
+        assert!(frame.pixels[2..4].iter().all(|&pixel| pixel == B));
+        assert!(frame.pixels[8..].iter().all(|&pixel| pixel == B));
+    }
+
+    #[test]
+    fn scale3x_corner() {
+        let pixels = [A, B, B, B];
+        let mut output = Vec::new();
+        let frame = Scaler::Scale3x.apply(Frame::native(&pixels, 2, 2), &mut output);
+        let block = |x: usize, y: usize| frame.pixels[y * 6 + x];
+        assert_eq!([block(0, 0), block(1, 0), block(2, 0)], [A, A, A]);
+        assert_eq!([block(0, 1), block(1, 1), block(2, 1)], [A, A, B]);
+        assert_eq!([block(0, 2), block(1, 2), block(2, 2)], [A, B, B]);
+    }
+
+    #[test]
+    fn xbr2x_blends_edges() {
+        // A diagonal edge between two colors gets its stair steps blended
+        #[rustfmt::skip]
+        let pixels = [
+            A, A, A, A,
+            A, A, A, B,
+            A, A, B, B,
+            A, B, B, B,
+        ];
+        let mut output = Vec::new();
+        let frame = Scaler::Xbr2x.apply(Frame::native(&pixels, 4, 4), &mut output);
+        assert_eq!(frame.pixels[0], A);
+        assert_eq!(frame.pixels[7 * 8 + 7], B);
+        assert!(frame.pixels.iter().any(|&pixel| pixel != A && pixel != B));
+    }
+}
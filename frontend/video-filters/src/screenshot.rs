@@ -0,0 +1,23 @@
+/// Encodes a `width` by `height` image, stored in rows of `stride` pixels, as an RGBA8 PNG file.
+pub fn encode_png(
+    pixels: &[u32],
+    stride: usize,
+    width: usize,
+    height: usize,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let mut data = Vec::with_capacity(width * height * 4);
+    for line in pixels.chunks(stride).take(height) {
+        for pixel in &line[..width] {
+            data.extend_from_slice(&pixel.to_le_bytes());
+        }
+    }
+
+    let mut output = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut output, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&data)?;
+    }
+    Ok(output)
+}
@@ -4,8 +4,15 @@ mod console_log;
 use core::str;
 use js_sys::{Uint32Array, Uint8Array};
 use ness_core::{
-    apu::dsp, cart, controllers::joypad::Keys, emu::Emu, utils::BoxedByteSlice, Model,
+    apu::dsp,
+    cart,
+    controllers::joypad::Keys,
+    emu::Emu,
+    ppu::{FB_HEIGHT, FB_WIDTH, VIEW_WIDTH},
+    utils::BoxedByteSlice,
+    Model,
 };
+use ness_video_filters::encode_png;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -13,8 +20,12 @@ pub struct EmuState {
     cart_info: cart::info::Info,
     cart: cart::Cart,
     emu: Emu,
+    fb: Box<[u32]>,
 }
 
+// The UI's texture is 512 pixels wide, as widescreen columns aren't used here
+const UI_FB_WIDTH: usize = VIEW_WIDTH << 1;
+
 #[wasm_bindgen]
 pub struct FrameMetadata {
     pub fb_width: usize,
@@ -68,7 +79,29 @@ impl EmuState {
 
     pub fn run_frame(&mut self) -> Uint32Array {
         self.emu.run_frame();
-        Uint32Array::from(&self.emu.ppu.framebuffer.0[..])
+        let fb_width = self.emu.ppu.fb_width();
+        let fb_height = self.emu.ppu.fb_height();
+        for (dst, src) in self
+            .fb
+            .chunks_exact_mut(UI_FB_WIDTH)
+            .zip(self.emu.ppu.framebuffer.0.chunks_exact(FB_WIDTH))
+            .take(fb_height)
+        {
+            dst[..fb_width].copy_from_slice(&src[..fb_width]);
+        }
+        Uint32Array::from(&self.fb[..UI_FB_WIDTH * fb_height])
+    }
+
+    /// Encodes the last frame as a PNG image, at the PPU's internal resolution.
+    pub fn screenshot_png(&self) -> Result<Uint8Array, JsValue> {
+        let data = encode_png(
+            &self.emu.ppu.framebuffer.0,
+            FB_WIDTH,
+            self.emu.ppu.fb_width(),
+            self.emu.ppu.fb_height(),
+        )
+        .map_err(|err| JsValue::from_str(&format!("Couldn't encode screenshot: {}", err)))?;
+        Ok(Uint8Array::from(&data[..]))
     }
 
     pub fn frame_metadata(&self) -> FrameMetadata {
@@ -118,5 +151,6 @@ pub fn create_emu_state(rom_arr: Uint8Array, carts_db: &[u8], boards_db: &[u8])
             #[cfg(feature = "log")]
             &slog::Logger::root(console_log::Console::new(), slog::o!()),
         ),
+        fb: vec![0; UI_FB_WIDTH * FB_HEIGHT].into_boxed_slice(),
     }
 }
@@ -106,6 +106,24 @@ import type * as wasm from "../../pkg";
                 break;
             }
 
+            case UiToEmu.MessageType.Screenshot: {
+                let buffer: Uint8Array;
+                try {
+                    buffer = emu!.screenshot_png();
+                } catch (e) {
+                    console.error(e);
+                    break;
+                }
+                sendMessage(
+                    {
+                        type: EmuToUi.MessageType.Screenshot,
+                        buffer,
+                    },
+                    [buffer.buffer]
+                );
+                break;
+            }
+
             case UiToEmu.MessageType.UpdateInput: {
                 emu!.update_input(data.pressed, data.released);
                 break;
@@ -20,6 +20,7 @@ export namespace UiToEmu {
         Stop,
         LoadSave,
         ExportSave,
+        Screenshot,
         UpdateInput,
         UpdatePlaying,
         UpdateLimitFramerate,
@@ -34,7 +35,11 @@ export namespace UiToEmu {
     }
 
     export interface RawMessage {
-        type: MessageType.Reset | MessageType.ExportSave | MessageType.Stop;
+        type:
+            | MessageType.Reset
+            | MessageType.ExportSave
+            | MessageType.Screenshot
+            | MessageType.Stop;
     }
 
     export interface LoadSaveMessage {
@@ -65,6 +70,7 @@ export namespace EmuToUi {
     export const enum MessageType {
         Loaded,
         ExportSave,
+        Screenshot,
         RenderFrame,
     }
 
@@ -77,6 +83,11 @@ export namespace EmuToUi {
         buffer: Uint8Array;
     }
 
+    export interface ScreenshotMessage {
+        type: MessageType.Screenshot;
+        buffer: Uint8Array;
+    }
+
     export interface RenderFrameMessage {
         type: MessageType.RenderFrame;
         buffer: Uint32Array;
@@ -88,5 +99,6 @@ export namespace EmuToUi {
     export type Message =
         | LoadedMessage
         | ExportSaveMessage
+        | ScreenshotMessage
         | RenderFrameMessage;
 }
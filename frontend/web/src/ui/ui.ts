@@ -14,6 +14,7 @@ export class Ui {
     private playButton: HTMLButtonElement;
     private resetButton: HTMLButtonElement;
     private exportSaveButton: HTMLButtonElement;
+    private screenshotButton: HTMLButtonElement;
 
     private files: Files;
 
@@ -47,6 +48,9 @@ export class Ui {
         this.exportSaveButton = document.getElementById(
             "export-save"
         ) as HTMLButtonElement;
+        this.screenshotButton = document.getElementById(
+            "screenshot"
+        ) as HTMLButtonElement;
 
         this.files = new Files(
             (id, name, buffer) => {
@@ -87,6 +91,12 @@ export class Ui {
             });
         });
 
+        this.screenshotButton.addEventListener("click", () => {
+            this.sendMessage({
+                type: UiToEmu.MessageType.Screenshot,
+            });
+        });
+
         const gl = this.canvas.getContext("webgl", {
             alpha: false,
             depth: false,
@@ -241,6 +251,7 @@ export class Ui {
         if (!this.worker) return;
         this.files.toggleEnabled(FileId.Save, false);
         this.exportSaveButton.disabled = true;
+        this.screenshotButton.disabled = true;
         this.playButton.disabled = true;
         this.resetButton.disabled = true;
         this.sendMessage({
@@ -253,6 +264,7 @@ export class Ui {
         this.stop();
         this.files.toggleEnabled(FileId.Save, true);
         this.exportSaveButton.disabled = false;
+        this.screenshotButton.disabled = false;
         this.playButton.disabled = false;
         this.resetButton.disabled = false;
         this.worker = new Worker("emu.bundle.js");
@@ -281,6 +293,18 @@ export class Ui {
                 break;
             }
 
+            case EmuToUi.MessageType.Screenshot: {
+                const url = URL.createObjectURL(
+                    new Blob([event.buffer], { type: "image/png" })
+                );
+                const link = document.createElement("a");
+                link.href = url;
+                link.download = "screenshot.png";
+                link.click();
+                setTimeout(() => URL.revokeObjectURL(url));
+                break;
+            }
+
             case EmuToUi.MessageType.RenderFrame: {
                 this.gl.texSubImage2D(
                     this.gl.TEXTURE_2D,